@@ -1,10 +1,18 @@
 use std::collections::HashSet;
-use std::sync::mpsc::Receiver;
+use std::fmt::Display;
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::{Arc, RwLock};
 
 mod commands;
 mod eventhandler;
+mod parse;
+mod registry;
+#[cfg(test)]
+mod test;
 
 pub use self::commands::{GameCommand, LobbyCommand};
+pub use self::parse::{parse_command, ParseError};
+pub use self::registry::{GameRegistry, RegistryError};
 
 use super::core::*;
 use super::discord::*;
@@ -15,14 +23,92 @@ fn create_game(users: &HashSet<UserID>) -> (Game<UserID>, Receiver<Event<UserID>
     let users = users.into_iter().map(|u| *u).collect();
     let (players, contracts) = get_players(users, roles);
     let (tx, rx) = std::sync::mpsc::channel();
-    let game = Game::new(0, players, contracts, Comm { tx });
+    let game = Game::new(0, players, contracts, Comm::new(&tx));
     (game, rx)
 }
 
+/// The single entry point for the Discord layer to send a GameCommand into a game.
+/// Choices the Action could never take are rejected here, and the rest is left to
+/// the game. Fails with NoGame if the controller has stopped.
+pub fn dispatch(
+    game_tx: &Sender<Command>,
+    cmd: GameCommand,
+) -> Result<(), InvalidActionError<UserID>> {
+    let invalid = match &cmd {
+        GameCommand::Vote {
+            ballot: Some(choice @ Choice::Pair(..)),
+            ..
+        } => Some((choice, ActionKind::Vote)),
+        GameCommand::Mark {
+            mark: choice @ Choice::Pair(..),
+            ..
+        } => Some((choice, ActionKind::Mark)),
+        _ => None,
+    };
+    if let Some((choice, action)) = invalid {
+        return Err(InvalidActionError::InvalidChoice {
+            choice: choice.to_owned(),
+            action,
+        });
+    }
+    game_tx
+        .send(Command::Game(cmd.into()))
+        .map_err(|_| InvalidActionError::NoGame)
+}
+
+/// The Roster of the running game, kept up to date by the Controller so that the
+/// Discord layer can check Choices without asking the game
+pub type SharedRoster = Arc<RwLock<Roster<UserID>>>;
+
+/// Why `dispatch_checked()` didn't send a GameCommand into the game
+#[derive(Debug)]
+pub enum DispatchError {
+    /// The Roster caught a Choice the game would reject
+    Choice(ChoiceError<UserID>),
+    /// `dispatch()` couldn't send the GameCommand
+    Action(InvalidActionError<UserID>),
+}
+
+impl Display for DispatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Choice(error) => write!(f, "{}", error),
+            Self::Action(error) => write!(f, "{}", error),
+        }
+    }
+}
+impl std::error::Error for DispatchError {}
+
+/// Like `dispatch()`, but first checks the players a GameCommand chooses against a
+/// Roster, so that a Choice naming someone dead or not playing never reaches the game.
+pub fn dispatch_checked(
+    game_tx: &Sender<Command>,
+    roster: &Roster<UserID>,
+    cmd: GameCommand,
+) -> Result<(), DispatchError> {
+    let chosen = match &cmd {
+        GameCommand::Vote {
+            voter,
+            ballot: Some(choice),
+        } => Some((*voter, choice, ActionKind::Vote)),
+        GameCommand::Target { actor, target } => Some((*actor, target, ActionKind::Target)),
+        GameCommand::Mark { killer, mark } => Some((*killer, mark, ActionKind::Mark)),
+        _ => None,
+    };
+    if let Some((actor, choice, action)) = chosen {
+        roster
+            .check_choice(actor, choice, action)
+            .map_err(DispatchError::Choice)?;
+    }
+    dispatch(game_tx, cmd).map_err(DispatchError::Action)
+}
+
 pub struct Controller {
     rx: Receiver<Command>,
     game_state: GameState,
     lobby: LobbyController,
+    /// Who is in the running game, for `dispatch_checked()`
+    roster: SharedRoster,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -43,6 +129,8 @@ pub struct GameController {
     game: Game<UserID>,
     channels: GameChannels,
     event_queue: Receiver<Event<UserID>>,
+    /// Where the game is saved when the controller stops
+    save_file: String,
 }
 
 #[derive(Debug)]
@@ -100,6 +188,7 @@ impl LobbyController {
                         game,
                         channels: channels.clone(),
                         event_queue,
+                        save_file: format!("{}.json", channels.main),
                     });
                 }
                 _ => todo!("Game can't be started"),
@@ -137,10 +226,53 @@ impl GameController {
         }
         Ok(())
     }
+
+    /// Lets a spectator read the main channel, and sends them what only spectators
+    /// see in their own thread of it. A living player is told they can't spectate
+    fn spectate(&mut self, user: UserID) -> Result<(), ()> {
+        let thread = SpectatorThread {
+            channel: self.channels.main,
+            user,
+        };
+        if let Err(err) = self.game.spectate(user, thread, SpectatorView::FullInfo) {
+            let reply = format!("{} {}", mention(user), err);
+            return send_to_channel(self.channels.main, reply).map(|_| ());
+        }
+        add_users_to_channel(self.channels.main, vec![user])
+    }
+}
+
+/// Sends a spectator the Events meant for spectators, in a thread of the main
+/// channel. Public Events are left to the main channel they can already read.
+#[derive(Debug)]
+struct SpectatorThread {
+    channel: ChannelID,
+    user: UserID,
+}
+
+impl Transport<UserID> for SpectatorThread {
+    fn tx(&self, event: Event<UserID>) -> Result<(), SendError> {
+        if event.audience() == Audience::Public {
+            return Ok(());
+        }
+        send_to_thread(self.channel, self.user, event.to_string())
+            .map(|_| ())
+            .map_err(|_| SendError)
+    }
 }
 
 impl Controller {
-    fn new(guild: GuildID, rx: Receiver<Command>) -> Self {
+    /// Runs a Controller for a guild on its own thread, handling the Commands sent
+    /// to `rx` and keeping `roster` up to date with its game
+    pub fn spawn(
+        guild: GuildID,
+        rx: Receiver<Command>,
+        roster: SharedRoster,
+    ) -> std::thread::JoinHandle<Result<(), GameError<UserID>>> {
+        std::thread::spawn(move || Self::new(guild, rx, roster).controller_thread())
+    }
+
+    fn new(guild: GuildID, rx: Receiver<Command>, roster: SharedRoster) -> Self {
         let (category, channel) = get_lobby_channels(guild).expect("TODO");
         let lobby = LobbyController {
             category,
@@ -153,22 +285,29 @@ impl Controller {
             rx,
             game_state,
             lobby,
+            roster,
         }
     }
 
-    fn start(self) -> std::thread::JoinHandle<()> {
+    /// Takes a new Roster from the running game, or an empty one if there is none
+    fn update_roster(&self) {
+        let roster = match &self.game_state {
+            GameState::Game(game_controller) => game_controller.game.roster(),
+            _ => Roster::default(),
+        };
+        *self.roster.write().unwrap() = roster;
+    }
+
+    fn start(self) -> std::thread::JoinHandle<Result<(), GameError<UserID>>> {
         std::thread::spawn(move || self.controller_thread())
     }
 
     // TODO: Use tokio recv?
 
-    fn controller_thread(mut self) {
-        loop {
-            let cmd = match self.rx.recv() {
-                Ok(cmd) => cmd,
-                Err(err) => continue,
-            };
-
+    /// Handles Commands until every Command sender has been dropped, at which point
+    /// no more can arrive. Any running game is then saved before the thread exits.
+    fn controller_thread(mut self) -> Result<(), GameError<UserID>> {
+        while let Ok(cmd) = self.rx.recv() {
             // Handle command.
             let result = match cmd {
                 Command::Lobby(cmd) => {
@@ -188,11 +327,28 @@ impl Controller {
                     GameState::Game(game_controller) => game_controller.handle(act),
                     _ => todo!("No game to handle action"),
                 },
+                Command::Spectate(user) => match &mut self.game_state {
+                    GameState::Game(game_controller) => game_controller.spectate(user),
+                    _ => send_to_channel(
+                        self.lobby.channel,
+                        format!("{} There is no game to spectate", mention(user)),
+                    )
+                    .map(|_| ()),
+                },
             };
 
+            self.update_roster();
+
             if let Err(err) = result {
                 todo!("Handle error: {:?}", err);
             }
         }
+
+        match &self.game_state {
+            GameState::Game(game_controller) => {
+                game_controller.game.save_game(&game_controller.save_file)
+            }
+            _ => Ok(()),
+        }
     }
 }
@@ -1,5 +1,5 @@
 use crate::{
-    core::{Action, Choice},
+    core::{Action, ActionKind, Choice},
     discord::{ChannelID, MessageID, UserID},
 };
 
@@ -7,6 +7,8 @@ use crate::{
 pub enum Command {
     Lobby(LobbyCommand),
     Game(Action<UserID>),
+    /// Someone who isn't playing starts watching the running game
+    Spectate(UserID),
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -36,3 +38,15 @@ pub enum GameCommand {
         mark: Choice<UserID>,
     },
 }
+
+impl GameCommand {
+    pub fn kind(&self) -> ActionKind {
+        match self {
+            GameCommand::Vote { ballot: None, .. } => ActionKind::Retract,
+            GameCommand::Vote { .. } => ActionKind::Vote,
+            GameCommand::Reveal { .. } => ActionKind::Reveal,
+            GameCommand::Target { .. } => ActionKind::Target,
+            GameCommand::Mark { .. } => ActionKind::Mark,
+        }
+    }
+}
@@ -1,4 +1,6 @@
-use crate::core::{ContractResult, Role, Team};
+use crate::core::{
+    ContractResult, GameRules, InvestigationResult, MafiaKnowledge, ResolutionStep, Role, Team,
+};
 use crate::discord::*;
 use crate::{
     core::{Event, Player},
@@ -14,12 +16,89 @@ trait EventHandler {
 pub struct ResponseEventHandler {
     channels: GameChannels,
     start_players: Vec<Player<UserID>>,
-    _rules: (), // Todo...
+    rules: GameRules,
+    /// Who is sent the moderator's view of the game, such as each ResolutionTrace
+    moderators: Vec<UserID>,
+}
+
+impl ResponseEventHandler {
+    fn send_to_moderators(&self, text: String) -> Result<(), DiscordError> {
+        for moderator in &self.moderators {
+            send_to_thread(self.channels.main, *moderator, text.to_owned())?;
+        }
+        Ok(())
+    }
+}
+
+/// One line of a ResolutionTrace, as a moderator reads it
+fn describe_step(step: &ResolutionStep<UserID>) -> Result<String, DiscordError> {
+    let names = |players: &Vec<Player<UserID>>| {
+        players
+            .iter()
+            .map(|p| get_name(p.user_id))
+            .collect::<Result<Vec<_>, _>>()
+            .map(|names| names.join(", "))
+    };
+    Ok(match step {
+        ResolutionStep::Redirected { driver, swapped } => format!(
+            "{} swapped {} and {}",
+            get_name(driver.user_id)?,
+            get_name(swapped.0.user_id)?,
+            get_name(swapped.1.user_id)?
+        ),
+        ResolutionStep::Fizzled { actor, target } => format!(
+            "{}'s action on {} fizzled",
+            get_name(actor.user_id)?,
+            get_name(target.user_id)?
+        ),
+        ResolutionStep::Blocked { blocked, strippers } => format!(
+            "{} blocked {}",
+            names(strippers)?,
+            get_name(blocked.user_id)?
+        ),
+        ResolutionStep::Intercepted { bodyguard, guarded } => format!(
+            "{} stepped in front of the attack on {}",
+            get_name(bodyguard.user_id)?,
+            get_name(guarded.user_id)?
+        ),
+        ResolutionStep::Saved {
+            killer,
+            saved,
+            doctors,
+        } => format!(
+            "{} saved {} from {}",
+            names(doctors)?,
+            get_name(saved.user_id)?,
+            get_name(killer.user_id)?
+        ),
+        ResolutionStep::Survived { killer, victim } => format!(
+            "{} survived an attack from {}",
+            get_name(victim.user_id)?,
+            get_name(killer.user_id)?
+        ),
+        ResolutionStep::Killed { killer, mark } => format!(
+            "{} killed {}",
+            get_name(killer.user_id)?,
+            get_name(mark.user_id)?
+        ),
+    })
 }
 
 impl EventHandler for ResponseEventHandler {
     fn handle_event(&mut self, event: Event<UserID>) -> Result<(), DiscordError> {
         match event {
+            Event::PlayerJoined { player } => {
+                send_to_channel(
+                    self.channels.main,
+                    format!("{} has joined the game!", get_name(player.user_id)?),
+                )?;
+            }
+            Event::PlayerLeft { player } => {
+                send_to_channel(
+                    self.channels.main,
+                    format!("{} has left the game.", get_name(player.user_id)?),
+                )?;
+            }
             Event::Start {
                 players, contracts, ..
             } => {
@@ -29,19 +108,20 @@ impl EventHandler for ResponseEventHandler {
                     .filter(|p| p.role.team() == Team::Mafia)
                     .map(|p| p.user_id)
                     .collect();
-                add_users_to_channel(self.channels.mafia, mafia_users)?;
-                // Send players role messages
+                // Blind Mafia don't know each other, so they don't share a channel.
+                // What the Mafia would see there is sent to their own threads instead.
+                if self.rules.mafia_knowledge == MafiaKnowledge::Known {
+                    add_users_to_channel(self.channels.mafia, mafia_users)?;
+                }
+                // Role messages are sent as YourRole events
                 for player in players {
-                    send_to_thread(
-                        self.channels.main,
-                        player.user_id,
-                        format!(
-                            "Your Role is {}. You are {}. {}",
-                            player.role,
-                            player.role.team(),
-                            player.role.description(),
-                        ),
-                    )?;
+                    for modifier in player.modifiers {
+                        send_to_thread(
+                            self.channels.main,
+                            player.user_id,
+                            format!("You are {}. {}", modifier, modifier.description()),
+                        )?;
+                    }
                 }
                 for contract in contracts {
                     send_to_thread(
@@ -51,21 +131,233 @@ impl EventHandler for ResponseEventHandler {
                     )?;
                 }
                 // Send main channel start message
-                //  (Start_roles info)
                 // Send mafia channel start message
             }
-            Event::Day { day_no, players } => {
-                let thresh = players.len() / 2 + 1;
+            Event::MafiaAnnounce { mafia, others } => {
+                send_to_channel(
+                    self.channels.main,
+                    format!("This game: {} Mafia Aligned, {} not", mafia, others),
+                )?;
+            }
+            Event::TeamAnnounce { team_counts } => {
+                let teams: Vec<String> = team_counts
+                    .iter()
+                    .map(|(team, count)| format!("{} {}", count, team))
+                    .collect();
+                send_to_channel(
+                    self.channels.main,
+                    format!("This game: {}", teams.join(", ")),
+                )?;
+            }
+            Event::SetupAnnounce { role_counts } => {
+                let roles: Vec<String> = role_counts
+                    .iter()
+                    .map(|(role, count)| format!("{} {}", count, role))
+                    .collect();
+                send_to_channel(
+                    self.channels.main,
+                    format!("This game: {}", roles.join(", ")),
+                )?;
+            }
+            Event::YourRole {
+                player,
+                role,
+                team,
+                description,
+                teammates,
+            } => {
+                send_to_thread(
+                    self.channels.main,
+                    player.user_id,
+                    format!("Your Role is {}. You are {}. {}", role, team, description),
+                )?;
+                if !teammates.is_empty() {
+                    let names = teammates
+                        .iter()
+                        .map(|p| get_name(p.user_id))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    send_to_thread(
+                        self.channels.main,
+                        player.user_id,
+                        format!("Your teammates are {}.", names.join(", ")),
+                    )?;
+                }
+            }
+            Event::Phase { to, phase, number } => {
+                let text = match number {
+                    Some(number) => format!("It is currently {} {}.", phase, number),
+                    None => format!("The game is in its {} phase.", phase),
+                };
+                send_to_thread(self.channels.main, to, text)?;
+            }
+            Event::Revived { player } => {
+                send_to_channel(
+                    self.channels.main,
+                    format!(
+                        "{} has been brought back into the game!",
+                        get_name(player.user_id)?
+                    ),
+                )?;
+            }
+            Event::Undone {
+                phase,
+                number,
+                restored,
+            } => {
+                let phase = match number {
+                    Some(number) => format!("{} {}", phase, number),
+                    None => phase.to_string(),
+                };
+                send_to_channel(
+                    self.channels.main,
+                    format!(
+                        "A moderator has undone the last change. It is {} again.",
+                        phase
+                    ),
+                )?;
+                for player in restored {
+                    send_to_channel(
+                        self.channels.main,
+                        format!("{} is alive again!", get_name(player.user_id)?),
+                    )?;
+                }
+            }
+            Event::ResolutionTrace { night_no, steps } => {
+                let steps = steps
+                    .iter()
+                    .map(describe_step)
+                    .collect::<Result<Vec<_>, _>>()?;
+                let text = match steps.is_empty() {
+                    true => format!("Night {}: nothing happened.", night_no),
+                    false => format!("Night {}:\n{}", night_no, steps.join("\n")),
+                };
+                self.send_to_moderators(text)?;
+            }
+            Event::VoteCast { .. } | Event::NightReport { .. } => {
+                // TODO: Send to a channel for dead players and spectators once there is one
+            }
+            Event::PhaseExtended { new_remaining } => {
                 send_to_channel(
                     self.channels.main,
                     format!(
-                        "Day #{} begins. {} votes required. Elect someone to die!",
-                        day_no, thresh,
+                        "A moderator has extended the phase. {} seconds remain.",
+                        new_remaining.as_secs()
+                    ),
+                )?;
+            }
+            Event::ModInspect {
+                moderator,
+                player,
+                role,
+                alive,
+            } => {
+                let status = match alive {
+                    true => "alive",
+                    false => "dead",
+                };
+                send_to_thread(
+                    self.channels.main,
+                    moderator,
+                    format!(
+                        "{} is {} ({}), and is {}.",
+                        get_name(player.user_id)?,
+                        role,
+                        role.team(),
+                        status
                     ),
                 )?;
+            }
+            Event::LegalTargets { player, targets } => {
+                let names = targets
+                    .iter()
+                    .map(|p| get_name(p.user_id))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let text = match names.is_empty() {
+                    true => "You have no one to target.".to_string(),
+                    false => format!("You can target: {}", names.join(", ")),
+                };
+                send_to_thread(self.channels.main, player.user_id, text)?;
+            }
+            Event::PendingActions { moderator, missing } => {
+                let names = missing
+                    .iter()
+                    .map(|p| get_name(p.user_id))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let text = match names.is_empty() {
+                    true => "Every night action is in.".to_string(),
+                    false => format!("Still waiting on: {}", names.join(", ")),
+                };
+                send_to_thread(self.channels.main, moderator, text)?;
+            }
+            Event::SetupPreview { moderator, roles } => {
+                let roles = roles
+                    .iter()
+                    .map(|(player, role)| Ok(format!("{}: {}", get_name(*player)?, role)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                send_to_thread(self.channels.main, moderator, roles.join("\n"))?;
+            }
+            Event::NotVoted { moderator, missing } => {
+                let names = missing
+                    .iter()
+                    .map(|p| get_name(p.user_id))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let text = match names.is_empty() {
+                    true => "Everyone has voted.".to_string(),
+                    false => format!("Yet to vote: {}", names.join(", ")),
+                };
+                send_to_thread(self.channels.main, moderator, text)?;
+            }
+            Event::WrongPhase {
+                player,
+                action,
+                expected,
+                got,
+            } => {
+                send_to_thread(
+                    self.channels.main,
+                    player.user_id,
+                    format!(
+                        "You can't {:?} during the {}. Wait until the {}.",
+                        action, got, expected
+                    ),
+                )?;
+            }
+            Event::DeadSilence { player } => {
+                send_to_thread(
+                    self.channels.main,
+                    player.user_id,
+                    "Dead players can't talk in this game.".to_string(),
+                )?;
+            }
+            Event::ActionsLocked { player, action } => {
+                send_to_thread(
+                    self.channels.main,
+                    player.user_id,
+                    format!(
+                        "It's too close to the deadline to {:?}. Night actions are locked.",
+                        action
+                    ),
+                )?;
+            }
+            Event::Day { .. } => {
                 change_channel_permission(self.channels.main, Access::Message)?;
                 change_channel_permission(self.channels.mafia, Access::View)?;
             }
+            Event::DayStart {
+                day_no,
+                alive,
+                threshold,
+            } => {
+                send_to_channel(
+                    self.channels.main,
+                    format!(
+                        "Day #{}. {} players alive, {} votes required. Elect someone to die!",
+                        day_no,
+                        alive.len(),
+                        threshold,
+                    ),
+                )?;
+            }
             Event::Night { night_no, players } => {
                 send_to_channel(self.channels.main, format!("Night #{} falls...", night_no))?;
                 change_channel_permission(self.channels.main, Access::View)?;
@@ -73,14 +365,40 @@ impl EventHandler for ResponseEventHandler {
                 let options = players.iter().map(|p| p.user_id).collect();
                 for player in &players {
                     let verb = match player.role {
-                        Role::COP => "investigate",
+                        Role::COP | Role::ROLECOP => "investigate",
                         Role::DOCTOR => "save",
                         Role::STRIPPER => "strip",
+                        Role::BUSDRIVER => "swap",
+                        Role::VIGILANTE => "shoot",
+                        Role::BODYGUARD => "guard",
+                        Role::CULTLEADER => "convert",
+                        Role::Custom(_) if player.role.targeting() => "target",
                         _ => continue,
                     };
                     send_target_message(self.channels.main, player.user_id, &options, verb)?;
                 }
-                send_mark_message(self.channels.mafia, &options)?;
+                match self.rules.mafia_knowledge {
+                    MafiaKnowledge::Known => send_mark_message(self.channels.mafia, &options)?,
+                    MafiaKnowledge::Blind => {
+                        let names = players
+                            .iter()
+                            .map(|p| get_name(p.user_id))
+                            .collect::<Result<Vec<_>, _>>()?;
+                        let message = format!(
+                            "Who will you mark tonight? Choose one of {}",
+                            names.join(", ")
+                        );
+                        for mafia in players.iter().filter(|p| p.role.team() == Team::Mafia) {
+                            send_to_thread(self.channels.main, mafia.user_id, message.clone())?;
+                        }
+                    }
+                }
+            }
+            Event::NightComplete => {
+                send_to_channel(
+                    self.channels.main,
+                    "All night actions are in. Resolving the night...".to_string(),
+                )?;
             }
             Event::Dawn => {
                 send_to_channel(self.channels.main, "Dawn breaks...".to_string())?;
@@ -96,30 +414,156 @@ impl EventHandler for ResponseEventHandler {
                     Some(player) => get_name(player.user_id)?,
                     None => "peace".to_string(),
                 };
+                let voter = match voter {
+                    Some(player) => get_name(player.user_id)?,
+                    None => "Someone".to_string(),
+                };
+                send_to_channel(
+                    self.channels.main,
+                    match count {
+                        Some(count) => {
+                            format!("{} votes for {}! ({}/{})", voter, votee, count, threshold)
+                        }
+                        None => format!("{} votes for {}!", voter, votee),
+                    },
+                )?;
+            }
+            Event::NightVote {
+                voter,
+                ballot,
+                count,
+                threshold,
+            } => {
+                let ballot = match ballot {
+                    Some(player) => get_name(player.user_id)?,
+                    None => "No Election".to_string(),
+                };
                 send_to_channel(
                     self.channels.main,
                     format!(
-                        "{} votes for {}! ({}/{})",
+                        "{} votes at night for {}! ({}/{})",
                         get_name(voter.user_id)?,
-                        votee,
+                        ballot,
                         count,
-                        threshold,
+                        threshold
                     ),
                 )?;
             }
-            Event::Retract { voter, .. } => {
+            Event::NightElection { elected, .. } => {
                 send_to_channel(
                     self.channels.main,
-                    format!("{} retracts vote", get_name(voter.user_id)?,),
+                    format!(
+                        "{} was voted out during the night!",
+                        get_name(elected.user_id)?
+                    ),
                 )?;
             }
+            Event::SkipVote {
+                voter,
+                threshold,
+                count,
+            } => {
+                let voter = match voter {
+                    Some(player) => get_name(player.user_id)?,
+                    None => "Someone".to_string(),
+                };
+                send_to_channel(
+                    self.channels.main,
+                    format!(
+                        "{} votes to skip the day! Skip Day ({}/{})",
+                        voter, count, threshold
+                    ),
+                )?;
+            }
+            Event::GhostVote {
+                ghost,
+                ballot,
+                count,
+                threshold,
+            } => {
+                let ghost = match ghost {
+                    Some(player) => get_name(player.user_id)?,
+                    None => "someone".to_string(),
+                };
+                let votee = match ballot {
+                    Some(player) => get_name(player.user_id)?,
+                    None => "peace".to_string(),
+                };
+                send_to_channel(
+                    self.channels.main,
+                    format!(
+                        "The ghost of {} votes for {}! ({}/{})",
+                        ghost, votee, count, threshold
+                    ),
+                )?;
+            }
+            Event::DaySkipped { .. } => {
+                send_to_channel(
+                    self.channels.main,
+                    "The town has agreed to skip the day...".to_string(),
+                )?;
+            }
+            Event::Dusk { actor, options } => {
+                let options = options
+                    .iter()
+                    .map(|o| get_name(o.user_id))
+                    .collect::<Result<Vec<_>, _>>()?;
+                send_to_channel(
+                    self.channels.main,
+                    format!(
+                        "Dusk has come. {} may take one of {} with them...",
+                        get_name(actor.user_id)?,
+                        options.join(", ")
+                    ),
+                )?;
+            }
+            Event::AlreadyVoted { voter, ballot } => {
+                let votee = match ballot {
+                    Some(player) => get_name(player.user_id)?,
+                    None => "peace".to_string(),
+                };
+                send_to_thread(
+                    self.channels.main,
+                    voter.user_id,
+                    format!("You have already voted for {}", votee),
+                )?;
+            }
+            Event::Retract { voter, .. } => {
+                let voter = match voter {
+                    Some(player) => get_name(player.user_id)?,
+                    None => "Someone".to_string(),
+                };
+                send_to_channel(self.channels.main, format!("{} retracts vote", voter))?;
+            }
+            Event::VoteTally { tally } => {
+                for (ballot, electors) in tally {
+                    let votee = match ballot {
+                        Some(player) => get_name(player.user_id)?,
+                        None => "peace".to_string(),
+                    };
+                    let electors = electors
+                        .iter()
+                        .map(|player| get_name(player.user_id))
+                        .collect::<Result<Vec<_>, _>>()?
+                        .join(", ");
+                    send_to_channel(
+                        self.channels.main,
+                        format!("{} voted for {}", electors, votee),
+                    )?;
+                }
+            }
             Event::Reveal { celeb } => {
                 send_to_channel(
                     self.channels.main,
                     format!("{} is CELEB!", get_name(celeb.user_id)?),
                 )?;
             }
-            Event::Election { ballot, .. } => {
+            Event::Election {
+                ballot,
+                hammer,
+                standings,
+                ..
+            } => {
                 let elect = match ballot {
                     Some(player) => {
                         format!("{} has been elected to die...", get_name(player.user_id)?)
@@ -128,11 +572,33 @@ impl EventHandler for ResponseEventHandler {
                 };
 
                 send_to_channel(self.channels.main, elect)?;
+                if let Some(hammer) = hammer {
+                    let hammer = get_name(hammer.user_id)?;
+                    send_to_channel(self.channels.main, format!("{} placed the hammer.", hammer))?;
+                }
+                if !standings.is_empty() {
+                    let standings = standings
+                        .iter()
+                        .map(|(ballot, count)| {
+                            let votee = match ballot {
+                                Some(player) => get_name(player.user_id)?,
+                                None => "peace".to_string(),
+                            };
+                            Ok(format!("{}: {}", votee, count))
+                        })
+                        .collect::<Result<Vec<_>, _>>()?
+                        .join(", ");
+                    send_to_channel(self.channels.main, format!("Final votes: {}", standings))?;
+                }
             }
-            Event::Target { actor, target } => {
-                let target_str = match target {
-                    Some(player) => get_name(player.user_id)?,
-                    None => "no one".to_string(),
+            Event::ActionConfirmed { actor, target } => {
+                let target_str = match target.is_empty() {
+                    true => "no one".to_string(),
+                    false => target
+                        .iter()
+                        .map(|player| get_name(player.user_id))
+                        .collect::<Result<Vec<_>, _>>()?
+                        .join(" and "),
                 };
                 send_to_thread(
                     self.channels.main,
@@ -140,19 +606,58 @@ impl EventHandler for ResponseEventHandler {
                     format!("You have targeted {}", target_str),
                 )?;
             }
+            Event::Avenged { avenger, victim } => {
+                send_to_channel(
+                    self.channels.main,
+                    format!(
+                        "{} takes {} down with them!",
+                        get_name(avenger.user_id)?,
+                        get_name(victim.user_id)?
+                    ),
+                )?;
+            }
+            Event::AbilityUsed { player, remaining } => {
+                send_to_thread(
+                    self.channels.main,
+                    player.user_id,
+                    format!("You have {} uses of your ability left", remaining),
+                )?;
+            }
             Event::Mark { killer, mark } => {
                 let mark_str = match mark {
                     Some(player) => get_name(player.user_id)?,
                     None => "no one".to_string(),
                 };
-                send_to_channel(
-                    self.channels.mafia,
-                    format!(
-                        "{} has marked {} to be killed",
-                        get_name(killer.user_id)?,
-                        mark_str
-                    ),
-                )?;
+                let message = format!(
+                    "{} has marked {} to be killed",
+                    get_name(killer.user_id)?,
+                    mark_str
+                );
+                match self.rules.mafia_knowledge {
+                    MafiaKnowledge::Known => send_to_channel(self.channels.mafia, message)?,
+                    MafiaKnowledge::Blind => {
+                        send_to_thread(self.channels.main, killer.user_id, message)?
+                    }
+                };
+            }
+            Event::TeamMessage { from, to, text } => {
+                let message = format!("{}: {}", get_name(from.user_id)?, text);
+                match from.role.team() {
+                    Team::Mafia => {
+                        send_to_channel(self.channels.mafia, message)?;
+                    }
+                    _ => {
+                        for player in to {
+                            send_to_thread(self.channels.main, player.user_id, message.clone())?;
+                        }
+                    }
+                }
+            }
+            Event::DeadChat { from, to, text } => {
+                let message = format!("{}: {}", get_name(from.user_id)?, text);
+                for player in to {
+                    send_to_thread(self.channels.main, player.user_id, message.clone())?;
+                }
             }
             Event::Strip { stripper, blocked } => {
                 send_to_thread(
@@ -168,6 +673,13 @@ impl EventHandler for ResponseEventHandler {
                     "You were blocked...".to_string(),
                 )?;
             }
+            Event::Intercept { bodyguard, guarded } => {
+                send_to_thread(
+                    self.channels.main,
+                    bodyguard.user_id,
+                    format!("You step in to protect {}!", get_name(guarded.user_id)?),
+                )?;
+            }
             Event::Save { doctor, saved } => {
                 send_to_thread(
                     self.channels.main,
@@ -175,11 +687,76 @@ impl EventHandler for ResponseEventHandler {
                     format!("You successfully save {}!", get_name(saved.user_id)?),
                 )?;
             }
-            Event::Investigate { cop, suspect, role } => {
+            Event::Investigate {
+                cop,
+                suspect,
+                result,
+            } => {
+                let result = match result {
+                    InvestigationResult::Guilty(true) => "Mafia Aligned".to_string(),
+                    InvestigationResult::Guilty(false) => "Not Mafia Aligned".to_string(),
+                    InvestigationResult::Role(role) => role.to_string(),
+                };
                 send_to_thread(
                     self.channels.main,
                     cop.user_id,
-                    format!("{} is {}", get_name(suspect.user_id)?, role.team()),
+                    format!("{} is {}", get_name(suspect.user_id)?, result),
+                )?;
+            }
+            Event::CustomResult { actor, text } => {
+                send_to_thread(self.channels.main, actor.user_id, text)?;
+            }
+            Event::InvestigateBlocked { cop } => {
+                send_to_thread(
+                    self.channels.main,
+                    cop.user_id,
+                    "Your investigation was blocked, so there is no result tonight.".to_string(),
+                )?;
+            }
+            Event::PollResult { watcher, tally } => {
+                for (ballot, electors) in tally {
+                    let votee = match ballot {
+                        Some(player) => get_name(player.user_id)?,
+                        None => "peace".to_string(),
+                    };
+                    let electors = electors
+                        .iter()
+                        .map(|player| get_name(player.user_id))
+                        .collect::<Result<Vec<_>, _>>()?
+                        .join(", ");
+                    send_to_thread(
+                        self.channels.main,
+                        watcher.user_id,
+                        format!("{} voted for {}", electors, votee),
+                    )?;
+                }
+            }
+            Event::VisitFizzled { actor, target } => {
+                send_to_thread(
+                    self.channels.main,
+                    actor.user_id,
+                    format!("Your visit to {} had no effect", get_name(target.user_id)?),
+                )?;
+            }
+            Event::Converted {
+                cult_leader,
+                convert,
+            } => {
+                send_to_thread(
+                    self.channels.main,
+                    convert.user_id,
+                    format!(
+                        "{} has converted you! You are now {}. {}",
+                        get_name(cult_leader.user_id)?,
+                        convert.role,
+                        convert.role.description(),
+                    ),
+                )?;
+            }
+            Event::Survived { victim } => {
+                send_to_channel(
+                    self.channels.main,
+                    format!("{} was attacked, but survived!", get_name(victim.user_id)?),
                 )?;
             }
             Event::Kill { mark, .. } => {
@@ -194,6 +771,92 @@ impl EventHandler for ResponseEventHandler {
                     "Everyone seems to be fine...".to_string(),
                 )?;
             }
+            Event::Modkill { player, .. } => {
+                send_to_channel(
+                    self.channels.main,
+                    format!(
+                        "{} has been removed from the game by a moderator.",
+                        get_name(player.user_id)?
+                    ),
+                )?;
+            }
+            Event::HammerGrace { remaining } => {
+                send_to_channel(
+                    self.channels.main,
+                    format!("Night falls in {} seconds.", remaining.as_secs()),
+                )?;
+            }
+            Event::Trial { defendant } => {
+                send_to_channel(
+                    self.channels.main,
+                    format!(
+                        "{} is on trial! Is the verdict Guilty or Innocent?",
+                        get_name(defendant.user_id)?
+                    ),
+                )?;
+            }
+            Event::Verdict { juror, guilty } => {
+                let verdict = match guilty {
+                    true => "Guilty",
+                    false => "Innocent",
+                };
+                send_to_channel(
+                    self.channels.main,
+                    format!("{} finds them {}.", get_name(juror.user_id)?, verdict),
+                )?;
+            }
+            Event::Acquitted {
+                defendant,
+                guilty,
+                innocent,
+            } => {
+                send_to_channel(
+                    self.channels.main,
+                    format!(
+                        "{} is acquitted, {} to {}.",
+                        get_name(defendant.user_id)?,
+                        guilty,
+                        innocent
+                    ),
+                )?;
+            }
+            Event::HammerPending { hammer, elected } => {
+                send_to_thread(
+                    self.channels.main,
+                    hammer.user_id,
+                    format!(
+                        "Your vote will elect {}. Confirm it to end the day.",
+                        get_name(elected.user_id)?
+                    ),
+                )?;
+            }
+            Event::HammerCancelled { hammer, .. } => {
+                send_to_thread(
+                    self.channels.main,
+                    hammer.user_id,
+                    "Your vote is no longer waiting to be confirmed.".to_string(),
+                )?;
+            }
+            Event::IdleModkill { player, phases } => {
+                send_to_channel(
+                    self.channels.main,
+                    format!(
+                        "{} has been removed from the game after {} phases without acting.",
+                        get_name(player.user_id)?,
+                        phases
+                    ),
+                )?;
+            }
+            Event::PlayerExcused { player, threshold } => {
+                send_to_channel(
+                    self.channels.main,
+                    format!(
+                        "{} has been excused from voting today. {} votes are now needed to elect.",
+                        get_name(player.user_id)?,
+                        threshold
+                    ),
+                )?;
+            }
             Event::Eliminate { player } => {
                 send_to_channel(
                     self.channels.main,
@@ -211,7 +874,11 @@ impl EventHandler for ResponseEventHandler {
                 winner,
                 contract_results,
             } => {
-                send_to_channel(self.channels.main, format!("{} wins!", winner,))?;
+                let end = match winner {
+                    Some(winner) => format!("{} wins!", winner),
+                    None => "The game ends in a draw!".to_string(),
+                };
+                send_to_channel(self.channels.main, end)?;
                 for result in contract_results {
                     let (user, result) = match result {
                         ContractResult::Success { holder } => (holder, "succeeded!"),
@@ -220,6 +887,24 @@ impl EventHandler for ResponseEventHandler {
                     send_to_channel(self.channels.main, format!("{} {}", user, result))?;
                 }
             }
+            Event::GameStats { stats } => {
+                let most_votes = match stats.votes_received.first() {
+                    Some((user, count)) => format!("{} received {} votes", get_name(*user)?, count),
+                    None => "No one received any votes".to_string(),
+                };
+                send_to_channel(
+                    self.channels.main,
+                    format!(
+                        "{} votes were cast. {}. {} kills, {} saves, {}/{} correct investigations",
+                        stats.votes_cast,
+                        most_votes,
+                        stats.kills,
+                        stats.saves,
+                        stats.correct_investigations,
+                        stats.investigations,
+                    ),
+                )?;
+            }
             _ => todo!(),
         }
         Ok(())
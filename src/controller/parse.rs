@@ -0,0 +1,120 @@
+use std::fmt::Display;
+
+use super::*;
+
+/// Reads a game command from the text of a Discord message, such as `/vote <@123>`,
+/// `vote abstain`, `unvote`, `target <@123>`, `mark <@123>`, or `reveal`. The leading
+/// `/` or `!` is optional, and players are chosen by mentioning them.
+pub fn parse_command(text: &str, author: UserID) -> Result<GameCommand, ParseError> {
+    let mut words = text.split_whitespace();
+    let command = words.next().ok_or(ParseError::Empty)?;
+    let command = command.trim_start_matches(['/', '!']).to_lowercase();
+    let args: Vec<&str> = words.collect();
+
+    let cmd = match command.as_str() {
+        "vote" => GameCommand::Vote {
+            voter: author,
+            ballot: Some(parse_choice(&args, ActionKind::Vote, false)?),
+        },
+        "unvote" | "retract" => {
+            no_args(&args)?;
+            GameCommand::Vote {
+                voter: author,
+                ballot: None,
+            }
+        }
+        "target" => GameCommand::Target {
+            actor: author,
+            target: parse_choice(&args, ActionKind::Target, true)?,
+        },
+        "mark" => GameCommand::Mark {
+            killer: author,
+            mark: parse_choice(&args, ActionKind::Mark, false)?,
+        },
+        "reveal" => {
+            no_args(&args)?;
+            GameCommand::Reveal { celeb: author }
+        }
+        _ => return Err(ParseError::UnknownCommand { command }),
+    };
+    Ok(cmd)
+}
+
+/// Reads who a command chooses: a mentioned player, `abstain`, or, if a pair is
+/// allowed, two mentioned players
+fn parse_choice(
+    args: &[&str],
+    action: ActionKind,
+    pair: bool,
+) -> Result<Choice<UserID>, ParseError> {
+    let player = |text: &str| {
+        parse_mention(text).ok_or_else(|| ParseError::InvalidTarget {
+            text: text.to_string(),
+        })
+    };
+    match args {
+        [] => Err(ParseError::MissingTarget { action }),
+        [abstain] if abstain.eq_ignore_ascii_case("abstain") => Ok(Choice::Abstain),
+        [p] => Ok(Choice::Player(player(p)?)),
+        [a, b] if pair => Ok(Choice::Pair(player(a)?, player(b)?)),
+        _ => {
+            let extra = if pair { args[2] } else { args[1] };
+            Err(ParseError::UnexpectedArgument {
+                text: extra.to_string(),
+            })
+        }
+    }
+}
+
+fn no_args(args: &[&str]) -> Result<(), ParseError> {
+    match args.first() {
+        Some(extra) => Err(ParseError::UnexpectedArgument {
+            text: extra.to_string(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Why the text of a message couldn't be read as a game command
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    Empty,
+    UnknownCommand {
+        command: String,
+    },
+    /// The command needs a player, or `abstain`
+    MissingTarget {
+        action: ActionKind,
+    },
+    /// Not a mention of a player
+    InvalidTarget {
+        text: String,
+    },
+    UnexpectedArgument {
+        text: String,
+    },
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Error reading command: ")?;
+        match self {
+            Self::Empty => {
+                write!(f, "No command given")
+            }
+            Self::UnknownCommand { command } => {
+                write!(f, "Unknown command \"{}\"", command)
+            }
+            Self::MissingTarget { action } => {
+                write!(f, "{:?} needs a player to choose, or abstain", action)
+            }
+            Self::InvalidTarget { text } => {
+                write!(f, "\"{}\" isn't a mention of a player", text)
+            }
+            Self::UnexpectedArgument { text } => {
+                write!(f, "Didn't expect \"{}\"", text)
+            }
+        }
+    }
+}
+impl std::error::Error for ParseError {}
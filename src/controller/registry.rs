@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Sender};
+use std::thread::JoinHandle;
+
+use super::*;
+
+/// A game running on its own thread, which handles the Requests sent to it
+#[derive(Debug)]
+struct RunningGame {
+    tx: Sender<Request>,
+    handle: JoinHandle<Game<UserID>>,
+}
+
+/// What a running game can be asked to do
+#[derive(Debug)]
+enum Request {
+    Action(Action<UserID>),
+    /// Send the game's Events to a new receiver, after the old one went away
+    Reconnect(Sender<Event<UserID>>),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum RegistryError {
+    GameExists { id: ChannelID },
+    NoGame { id: ChannelID },
+}
+
+/// Every game the bot is running, each identified by its main channel,
+/// so that Actions can be routed to the right one
+#[derive(Debug, Default)]
+pub struct GameRegistry {
+    games: HashMap<ChannelID, RunningGame>,
+}
+
+impl GameRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts running a game under the given id
+    pub fn create(&mut self, id: ChannelID, mut game: Game<UserID>) -> Result<(), RegistryError> {
+        if self.games.contains_key(&id) {
+            return Err(RegistryError::GameExists { id });
+        }
+        let (tx, rx) = mpsc::channel();
+        let handle = std::thread::spawn(move || {
+            // Runs until the registry drops the sender when the game is ended. A game
+            // whose Events aren't being received anymore keeps running, so that it
+            // can be reconnected and Resynced
+            while let Ok(request) = rx.recv() {
+                match request {
+                    // TODO: Report rejected Actions back to the Discord layer
+                    Request::Action(action) => {
+                        let _ = game.handle(action);
+                    }
+                    Request::Reconnect(tx) => game.reconnect(tx),
+                }
+            }
+            game
+        });
+        self.games.insert(id, RunningGame { tx, handle });
+        Ok(())
+    }
+
+    /// Whether a game is running under the given id
+    pub fn contains(&self, id: ChannelID) -> bool {
+        self.games.contains_key(&id)
+    }
+
+    /// Sends an Action to the game with the given id
+    pub fn route(&self, id: ChannelID, action: Action<UserID>) -> Result<(), RegistryError> {
+        self.request(id, Request::Action(action))
+    }
+
+    /// Sends a game's Events to a new receiver, such as after the old one went away
+    /// and the game was disconnected. Route a Resync next to catch the receiver up.
+    pub fn reconnect(&self, id: ChannelID, tx: Sender<Event<UserID>>) -> Result<(), RegistryError> {
+        self.request(id, Request::Reconnect(tx))
+    }
+
+    fn request(&self, id: ChannelID, request: Request) -> Result<(), RegistryError> {
+        self.games
+            .get(&id)
+            .ok_or(RegistryError::NoGame { id })?
+            .tx
+            .send(request)
+            .map_err(|_| RegistryError::NoGame { id })
+    }
+
+    /// Stops running a game once it has handled every Action already sent to it,
+    /// and gives it back, such as to save it
+    pub fn end(&mut self, id: ChannelID) -> Result<Game<UserID>, RegistryError> {
+        let RunningGame { tx, handle } =
+            self.games.remove(&id).ok_or(RegistryError::NoGame { id })?;
+        drop(tx);
+        handle.join().map_err(|_| RegistryError::NoGame { id })
+    }
+}
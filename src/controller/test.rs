@@ -0,0 +1,344 @@
+use std::sync::mpsc;
+use std::sync::mpsc::Receiver;
+
+use super::*;
+
+#[test]
+fn dispatch_game_commands() {
+    let (tx, rx) = mpsc::channel();
+    let commands = vec![
+        (
+            GameCommand::Vote {
+                voter: 101,
+                ballot: Some(Choice::Player(104)),
+            },
+            Action::Vote {
+                voter: 101,
+                ballot: Some(Choice::Player(104)),
+            },
+        ),
+        (
+            GameCommand::Vote {
+                voter: 101,
+                ballot: None,
+            },
+            Action::Vote {
+                voter: 101,
+                ballot: None,
+            },
+        ),
+        (
+            GameCommand::Reveal { celeb: 102 },
+            Action::Reveal { celeb: 102 },
+        ),
+        (
+            GameCommand::Target {
+                actor: 103,
+                target: Choice::Pair(101, 102),
+            },
+            Action::Target {
+                actor: 103,
+                target: Choice::Pair(101, 102),
+            },
+        ),
+        (
+            GameCommand::Mark {
+                killer: 104,
+                mark: Choice::Abstain,
+            },
+            Action::Mark {
+                killer: 104,
+                mark: Choice::Abstain,
+            },
+        ),
+    ];
+    for (cmd, action) in commands {
+        assert!(dispatch(&tx, cmd).is_ok());
+        assert_eq!(rx.try_recv(), Ok(Command::Game(action)));
+    }
+}
+
+#[test]
+fn dispatch_rejects() {
+    let (tx, rx) = mpsc::channel();
+
+    // Only a BUSDRIVER can choose a pair of players
+    let vote = GameCommand::Vote {
+        voter: 101,
+        ballot: Some(Choice::Pair(102, 103)),
+    };
+    assert!(matches!(
+        dispatch(&tx, vote),
+        Err(InvalidActionError::InvalidChoice {
+            action: ActionKind::Vote,
+            ..
+        })
+    ));
+    let mark = GameCommand::Mark {
+        killer: 104,
+        mark: Choice::Pair(102, 103),
+    };
+    assert!(matches!(
+        dispatch(&tx, mark),
+        Err(InvalidActionError::InvalidChoice {
+            action: ActionKind::Mark,
+            ..
+        })
+    ));
+    assert!(rx.try_recv().is_err());
+
+    // Once the controller has stopped, there is no game to send to
+    drop(rx);
+    assert!(matches!(
+        dispatch(&tx, GameCommand::Reveal { celeb: 102 }),
+        Err(InvalidActionError::NoGame)
+    ));
+}
+
+#[test]
+fn dispatch_checked_rejects() {
+    let (tx, rx) = mpsc::channel();
+    let roster = Roster {
+        alive: vec![101, 102, 104],
+        dead: vec![103],
+        legal_targets: [(102, vec![101, 104])].into_iter().collect(),
+    };
+
+    let vote = GameCommand::Vote {
+        voter: 101,
+        ballot: Some(Choice::Player(103)),
+    };
+    assert!(matches!(
+        dispatch_checked(&tx, &roster, vote),
+        Err(DispatchError::Choice(ChoiceError::PlayerDead { pid: 103 }))
+    ));
+    let mark = GameCommand::Mark {
+        killer: 104,
+        mark: Choice::Player(999),
+    };
+    assert!(matches!(
+        dispatch_checked(&tx, &roster, mark),
+        Err(DispatchError::Choice(ChoiceError::NoSuchPlayer {
+            pid: 999
+        }))
+    ));
+    let target = GameCommand::Target {
+        actor: 102,
+        target: Choice::Player(102),
+    };
+    assert!(matches!(
+        dispatch_checked(&tx, &roster, target),
+        Err(DispatchError::Choice(ChoiceError::IllegalTarget {
+            pid: 102
+        }))
+    ));
+    assert!(rx.try_recv().is_err());
+
+    let target = GameCommand::Target {
+        actor: 102,
+        target: Choice::Player(104),
+    };
+    assert!(dispatch_checked(&tx, &roster, target).is_ok());
+    assert!(rx.try_recv().is_ok());
+}
+
+fn running_game() -> (Game<UserID>, Receiver<Event<UserID>>) {
+    let players = vec![
+        Player::new(101, Role::TOWN),
+        Player::new(102, Role::COP),
+        Player::new(103, Role::DOCTOR),
+        Player::new(104, Role::MAFIA),
+        Player::new(105, Role::TOWN),
+    ];
+    let (tx, rx) = mpsc::channel();
+    let mut game = Game::new(1, players, Vec::new(), Comm::new(&tx));
+    assert!(game.start().is_ok());
+    (game, rx)
+}
+
+#[test]
+fn registry_routes_by_id() {
+    let mut registry = GameRegistry::new();
+    let (first, _first_rx) = running_game();
+    let (second, _second_rx) = running_game();
+    assert!(registry.create(1, first).is_ok());
+    assert!(registry.create(2, second).is_ok());
+    let (third, _third_rx) = running_game();
+    assert_eq!(
+        registry.create(1, third),
+        Err(RegistryError::GameExists { id: 1 })
+    );
+
+    let vote = |voter, ballot| Action::Vote {
+        voter,
+        ballot: Some(Choice::Player(ballot)),
+    };
+    assert!(registry.route(1, vote(101, 104)).is_ok());
+    assert!(registry.route(2, vote(102, 105)).is_ok());
+    assert!(registry.route(2, vote(103, 105)).is_ok());
+    assert_eq!(
+        registry.route(3, vote(101, 104)),
+        Err(RegistryError::NoGame { id: 3 })
+    );
+
+    // Each game only handled the Actions routed to it
+    let votes = |game: &Game<UserID>| match game.current_phase() {
+        Phase::Day(day) => day.votes.len(),
+        phase => panic!("Expected Day, got {:?}", phase),
+    };
+    let first = registry.end(1).unwrap();
+    assert_eq!(votes(&first), 1);
+    assert!(!registry.contains(1));
+    let second = registry.end(2).unwrap();
+    assert_eq!(votes(&second), 2);
+    assert!(matches!(
+        registry.end(2),
+        Err(RegistryError::NoGame { id: 2 })
+    ));
+}
+
+#[test]
+fn registry_reconnects() {
+    let mut registry = GameRegistry::new();
+    let (game, rx) = running_game();
+    let last_seq = rx.try_iter().count() as u64;
+    drop(rx);
+    assert!(registry.create(1, game).is_ok());
+
+    // No one receives the game's Events, so it is disconnected, and rejects the
+    // second vote
+    let vote = |voter| Action::Vote {
+        voter,
+        ballot: Some(Choice::Player(104)),
+    };
+    assert!(registry.route(1, vote(101)).is_ok());
+    assert!(registry.route(1, vote(102)).is_ok());
+
+    // The game keeps running, so it can be reconnected and caught up
+    let (tx, rx) = mpsc::channel();
+    assert!(registry.reconnect(1, tx).is_ok());
+    let resync = Action::Resync {
+        from: 101,
+        after_seq: last_seq,
+    };
+    assert!(registry.route(1, resync).is_ok());
+    assert!(registry.route(1, vote(102)).is_ok());
+    assert!(registry.route(1, vote(103)).is_ok());
+    assert!(registry.end(1).is_ok());
+    let voters: Vec<_> = rx
+        .try_iter()
+        .filter_map(|e| match e {
+            Event::Vote { voter, .. } => voter.map(|v| v.user_id),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(voters, vec![101, 102, 103]);
+}
+
+#[test]
+fn controller_saves_when_disconnected() {
+    let fname =
+        std::env::temp_dir().join(format!("mafiabot_controller_{}.json", std::process::id()));
+    let fname = fname.to_str().unwrap().to_owned();
+    let _ = std::fs::remove_file(&fname);
+
+    let (game, event_queue) = running_game();
+    let (tx, rx) = mpsc::channel();
+    let controller = Controller {
+        rx,
+        game_state: GameState::Game(GameController {
+            game,
+            channels: GameChannels { main: 1, mafia: 2 },
+            event_queue,
+            save_file: fname.to_owned(),
+        }),
+        lobby: LobbyController {
+            category: 0,
+            channel: 0,
+            guild: 0,
+        },
+        roster: SharedRoster::default(),
+    };
+    let handle = controller.start();
+    drop(tx);
+
+    assert!(handle.join().unwrap().is_ok());
+    let comm = Comm::new(&mpsc::channel().0);
+    let saved = Game::<UserID>::load_game(&fname, SaveFormat::PrettyJson, comm).unwrap();
+    assert_eq!(saved.phase.kind(), PhaseKind::Day);
+    std::fs::remove_file(&fname).unwrap();
+}
+
+#[test]
+fn parse_commands() {
+    let parse = |text| parse_command(text, 101);
+    assert_eq!(
+        parse("/vote <@104>"),
+        Ok(GameCommand::Vote {
+            voter: 101,
+            ballot: Some(Choice::Player(104))
+        })
+    );
+    assert_eq!(
+        parse("vote abstain"),
+        Ok(GameCommand::Vote {
+            voter: 101,
+            ballot: Some(Choice::Abstain)
+        })
+    );
+    assert_eq!(parse("!unvote").map(|c| c.kind()), Ok(ActionKind::Retract));
+    assert_eq!(
+        parse("target <@102>"),
+        Ok(GameCommand::Target {
+            actor: 101,
+            target: Choice::Player(102)
+        })
+    );
+    assert_eq!(
+        parse("target <@102> <@!103>"),
+        Ok(GameCommand::Target {
+            actor: 101,
+            target: Choice::Pair(102, 103)
+        })
+    );
+    assert_eq!(
+        parse("MARK <@105>"),
+        Ok(GameCommand::Mark {
+            killer: 101,
+            mark: Choice::Player(105)
+        })
+    );
+    assert_eq!(parse("reveal"), Ok(GameCommand::Reveal { celeb: 101 }));
+
+    assert_eq!(parse("  "), Err(ParseError::Empty));
+    assert_eq!(
+        parse("/lynch <@104>"),
+        Err(ParseError::UnknownCommand {
+            command: "lynch".to_string()
+        })
+    );
+    assert_eq!(
+        parse("vote"),
+        Err(ParseError::MissingTarget {
+            action: ActionKind::Vote
+        })
+    );
+    assert_eq!(
+        parse("mark alice"),
+        Err(ParseError::InvalidTarget {
+            text: "alice".to_string()
+        })
+    );
+    assert_eq!(
+        parse("vote <@104> <@105>"),
+        Err(ParseError::UnexpectedArgument {
+            text: "<@105>".to_string()
+        })
+    );
+    assert_eq!(
+        parse("reveal now"),
+        Err(ParseError::UnexpectedArgument {
+            text: "now".to_string()
+        })
+    );
+}
@@ -1,15 +1,16 @@
 mod game;
 mod interface;
 mod rules;
+#[cfg(test)]
 mod test;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 use std::fs::File;
 
 // TODO: decide exactly what to export!!
 pub use game::*;
-pub use interface::{action::*, error::*, event::*, *};
+pub use interface::{action::*, error::*, event::*, locale::*, roster::*, *};
 
 pub use game::{Game, Player, Players, RawPID};
 pub use rules::*;
@@ -2,13 +2,24 @@ mod contract;
 mod phase;
 mod player;
 mod roles;
+mod stats;
+mod timer;
+mod transcript;
 
 use super::*;
+use serde::de::DeserializeOwned;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 pub use contract::*;
 pub use phase::*;
 pub use player::*;
-pub use roles::{Role, Team};
+pub use roles::{CustomRole, InvestigationResult, Modifier, Role, RoleBehavior, RoleContext, Team};
+pub use stats::*;
+pub use timer::*;
+pub use transcript::*;
 
 pub type Players<U> = Vec<Player<U>>;
 
@@ -24,13 +35,401 @@ impl<U: RawPID> PlayerCheck<U> for Players<U> {
     }
 }
 
-#[derive(Debug, Serialize /*Deserialize*/)]
+/// Turns a Vote's ballot into the Ballot it is cast as, or None for a retraction.
+/// A pair of players can't be voted for.
+fn check_ballot<U: RawPID>(
+    players: &Players<U>,
+    c: Option<Choice<U>>,
+) -> Result<Option<Ballot>, InvalidActionError<U>> {
+    match c {
+        Some(Choice::Player(p)) => Ok(Some(Ballot::Player(players.check(p)?))),
+        Some(Choice::Abstain) => Ok(Some(Ballot::Abstain)),
+        Some(choice @ Choice::Pair(..)) => Err(InvalidActionError::InvalidChoice {
+            choice,
+            action: ActionKind::Vote,
+        }),
+        None => Ok(None),
+    }
+}
+
+/// How a game is written by `save_game()`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SaveFormat {
+    #[default]
+    /// Readable JSON, for debugging
+    PrettyJson,
+    /// Compact bincode, for frequent autosaves
+    Binary,
+}
+
+/// Where finished games are archived unless `save_on_end` says otherwise
+pub const ARCHIVE_DIR: &str = "games";
+
+/// What happens to an autosaved game's save once the game has ended, so that a
+/// finished game isn't mistaken for an active one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SaveOnEnd {
+    /// Leave the final save where it is
+    Keep,
+    Delete,
+    /// Move the final save into this directory, as `{game_id}-{timestamp}`
+    Archive {
+        dir: String,
+    },
+}
+
+impl Default for SaveOnEnd {
+    fn default() -> Self {
+        SaveOnEnd::Archive {
+            dir: ARCHIVE_DIR.to_string(),
+        }
+    }
+}
+
+/// The parts of a Game that an Undo restores
+#[derive(Debug, Clone)]
+struct Snapshot<U: RawPID> {
+    players: Players<U>,
+    dead: Players<U>,
+    phase: Phase<U>,
+    contracts: Vec<Contract<U>>,
+    ability_uses: HashMap<U, usize>,
+    grudges: HashMap<U, U>,
+    ghosts_spent: Vec<U>,
+    idle: HashMap<U, usize>,
+    active: Vec<U>,
+    timer: Option<PhaseTimer>,
+    paused: bool,
+}
+
+/// The version of the save format written by `save_game()`. Older pretty JSON saves
+/// are upgraded with `migrate()` when loaded, but saves with any other version can't be.
+pub const SAVE_VERSION: u32 = 25;
+
+/// Upgrades a pretty JSON save written with an older version of the save format to
+/// the current one, one version at a time
+pub fn migrate(version: u32, save: serde_json::Value) -> Result<serde_json::Value, LoadError> {
+    let incompatible = LoadError::IncompatibleVersion {
+        found: version,
+        expected: SAVE_VERSION,
+    };
+    if version == 0 || version > SAVE_VERSION {
+        return Err(incompatible);
+    }
+    let mut save = save;
+    for from in version..SAVE_VERSION {
+        save = match from {
+            1 => migrate_v1(save),
+            2 => migrate_v2(save),
+            3 => migrate_v3(save),
+            4 => migrate_v4(save),
+            5 => migrate_v5(save),
+            6 => migrate_v6(save),
+            7 => migrate_v7(save),
+            8 => migrate_v8(save),
+            9 => migrate_v9(save),
+            10 => migrate_v10(save),
+            11 => migrate_v11(save),
+            12 => migrate_v12(save),
+            13 => migrate_v13(save),
+            14 => migrate_v14(save),
+            15 => migrate_v15(save),
+            16 => migrate_v16(save),
+            17 => migrate_v17(save),
+            18 => migrate_v18(save),
+            19 => migrate_v19(save),
+            20 => migrate_v20(save),
+            21 => migrate_v21(save),
+            22 => migrate_v22(save),
+            23 => migrate_v23(save),
+            24 => migrate_v24(save),
+            _ => return Err(incompatible),
+        };
+        save["version"] = serde_json::Value::from(from + 1);
+    }
+    Ok(save)
+}
+
+/// Version 2 didn't change how the game is laid out, so there's nothing to upgrade
+fn migrate_v1(save: serde_json::Value) -> serde_json::Value {
+    save
+}
+
+/// Version 3 added the player cap, which older games didn't have
+fn migrate_v2(mut save: serde_json::Value) -> serde_json::Value {
+    save["game"]["max_players"] = serde_json::Value::Null;
+    save
+}
+
+/// Version 4 added player names, which players in older games didn't have
+fn migrate_v3(mut save: serde_json::Value) -> serde_json::Value {
+    for players in ["players", "dead"] {
+        if let Some(players) = save["game"][players].as_array_mut() {
+            for player in players {
+                player["name"] = serde_json::Value::Null;
+            }
+        }
+    }
+    save
+}
+
+/// Version 5 added the VoteCounts rule, and older games showed every count
+fn migrate_v4(mut save: serde_json::Value) -> serde_json::Value {
+    save["game"]["rules"]["vote_counts"] = serde_json::json!(VoteCounts::Exact);
+    save
+}
+
+/// Version 6 added Dusk, and older games had no deadline for it
+fn migrate_v5(mut save: serde_json::Value) -> serde_json::Value {
+    save["game"]["rules"]["dusk_length"] = serde_json::Value::Null;
+    save
+}
+
+/// Version 7 added the AVENGER, and no one in an older game holds a grudge
+fn migrate_v6(mut save: serde_json::Value) -> serde_json::Value {
+    save["game"]["grudges"] = serde_json::json!({});
+    save
+}
+
+/// Version 8 added the MafiaKill rule, and older games let the Mafia kill no one
+fn migrate_v7(mut save: serde_json::Value) -> serde_json::Value {
+    save["game"]["rules"]["mafia_kill"] = serde_json::json!(MafiaKill::Optional);
+    save
+}
+
+/// Version 9 added the action_lock rule, and older games never locked night actions
+fn migrate_v8(mut save: serde_json::Value) -> serde_json::Value {
+    save["game"]["rules"]["action_lock"] = serde_json::Value::Null;
+    save
+}
+
+/// Version 10 added excused players to the Day, and no one in an older game was excused
+fn migrate_v9(mut save: serde_json::Value) -> serde_json::Value {
+    if let Some(day) = save["game"]["phase"].get_mut("Day") {
+        day["excused"] = serde_json::json!([]);
+    }
+    save
+}
+
+/// Version 11 added the ghost_vote rule, and no one in an older game cast a ghost vote
+fn migrate_v10(mut save: serde_json::Value) -> serde_json::Value {
+    save["game"]["rules"]["ghost_vote"] = serde_json::json!(false);
+    save["game"]["ghosts_spent"] = serde_json::json!([]);
+    if let Some(day) = save["game"]["phase"].get_mut("Day") {
+        day["ghosts"] = serde_json::json!([]);
+    }
+    save
+}
+
+/// Version 12 added the SaveAgainst rule, and older games let a DOCTOR save against any kill
+fn migrate_v11(mut save: serde_json::Value) -> serde_json::Value {
+    save["game"]["rules"]["save_against"] = serde_json::json!(SaveAgainst::AnyKill);
+    save
+}
+
+/// Version 13 added the StartInfo rule, and older games announced nothing at the start
+fn migrate_v12(mut save: serde_json::Value) -> serde_json::Value {
+    save["game"]["rules"]["start_info"] = serde_json::json!(StartInfo::None);
+    save
+}
+
+/// Version 14 added the idle_modkill rule, and older games never modkill idle players
+fn migrate_v13(mut save: serde_json::Value) -> serde_json::Value {
+    save["game"]["rules"]["idle_modkill"] = serde_json::Value::Null;
+    save["game"]["idle"] = serde_json::json!({});
+    save["game"]["active"] = serde_json::json!([]);
+    save
+}
+
+/// Version 15 added the confirm_hammer rule, and older games elect on the hammer right away
+fn migrate_v14(mut save: serde_json::Value) -> serde_json::Value {
+    save["game"]["rules"]["confirm_hammer"] = serde_json::json!(false);
+    if let Some(day) = save["game"]["phase"].get_mut("Day") {
+        day["pending"] = serde_json::Value::Null;
+    }
+    save
+}
+
+/// Version 16 added the hammer_grace rule, and older games start the Night right away
+fn migrate_v15(mut save: serde_json::Value) -> serde_json::Value {
+    save["game"]["rules"]["hammer_grace"] = serde_json::Value::Null;
+    save
+}
+
+/// Version 17 added the day_style rule, and older games elect a player directly
+fn migrate_v16(mut save: serde_json::Value) -> serde_json::Value {
+    save["game"]["rules"]["day_style"] = serde_json::json!(DayStyle::DirectLynch);
+    if let Some(day) = save["game"]["phase"].get_mut("Day") {
+        day["trial"] = serde_json::Value::Null;
+    }
+    save
+}
+
+/// Version 18 added the deadline_behavior rule, and older games follow their election_process
+fn migrate_v17(mut save: serde_json::Value) -> serde_json::Value {
+    save["game"]["rules"]["deadline_behavior"] = serde_json::Value::Null;
+    save
+}
+
+/// Version 19 added the ability_nights rule, and older games use abilities every night
+fn migrate_v18(mut save: serde_json::Value) -> serde_json::Value {
+    save["game"]["rules"]["ability_nights"] = serde_json::json!({});
+    save
+}
+
+/// Version 20 added the dead_chat rule, and older games keep dead players silent
+fn migrate_v19(mut save: serde_json::Value) -> serde_json::Value {
+    save["game"]["rules"]["dead_chat"] = serde_json::json!(false);
+    save
+}
+
+/// Version 21 added the IdiotElect rule and general dusk actions. Older games always
+/// took an elected IDIOT to Dusk, and had no one stunned at night
+fn migrate_v20(mut save: serde_json::Value) -> serde_json::Value {
+    save["game"]["rules"]["idiot_elect"] = serde_json::json!(IdiotElect::Dusk);
+    if let Some(night) = save["game"]["phase"].get_mut("Night") {
+        night["stunned"] = serde_json::json!([]);
+    }
+    if let Some(dusk) = save["game"]["phase"].get_mut("Dusk") {
+        let idiot = dusk["idiot"].to_owned();
+        let mut actions = serde_json::Map::new();
+        actions.insert(idiot.to_string(), dusk["electors"].to_owned());
+        *dusk = serde_json::json!({
+            "day_no": dusk["day_no"],
+            "elected": idiot,
+            "hammer": dusk["hammer"],
+            "actions": actions,
+            "picks": {},
+        });
+    }
+    save
+}
+
+/// Version 22 saved the log the transcript is built from. Older games only have
+/// a record of what happens after they are loaded
+fn migrate_v21(mut save: serde_json::Value) -> serde_json::Value {
+    let mut log = serde_json::json!(GameLog::<u64>::default());
+    log["transcript"]["game_id"] = save["game"]["game_id"].to_owned();
+    save["game"]["log"] = log;
+    save
+}
+
+/// Version 23 counted the stats in the saved log. Older games only count what
+/// happens after they are loaded
+fn migrate_v22(mut save: serde_json::Value) -> serde_json::Value {
+    let log = serde_json::json!(GameLog::<u64>::default());
+    save["game"]["log"]["stats"] = log["stats"].to_owned();
+    save["game"]["log"]["after_night"] = log["after_night"].to_owned();
+    save
+}
+
+/// Version 24 saved the sequence number of the last Event. Older games number
+/// their Events from 1 again once loaded
+fn migrate_v23(mut save: serde_json::Value) -> serde_json::Value {
+    save["game"]["log"]["last_seq"] = serde_json::json!(0);
+    save
+}
+
+/// Version 25 saved the ability_limits and ability_nights rules as lists of
+/// pairs, so they can hold custom roles
+fn migrate_v24(mut save: serde_json::Value) -> serde_json::Value {
+    for rule in ["ability_limits", "ability_nights"] {
+        let map = &mut save["game"]["rules"][rule];
+        if let Some(roles) = map.as_object() {
+            let pairs: Vec<_> = roles
+                .iter()
+                .map(|(role, value)| serde_json::json!([role, value]))
+                .collect();
+            *map = serde_json::json!(pairs);
+        }
+    }
+    save
+}
+
+/// Saves a game's Comm as its GameLog. A loaded game's Comm has no Transport
+/// until `load_game()` gives it the one it was given, with the same log.
+mod saved_log {
+    use super::*;
+
+    pub fn serialize<U: RawPID, S: serde::Serializer>(
+        comm: &Comm<U>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        comm.log().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, U, D>(deserializer: D) -> Result<Comm<U>, D::Error>
+    where
+        U: RawPID + Deserialize<'de>,
+        D: serde::Deserializer<'de>,
+    {
+        let mut comm = Comm::default();
+        comm.resume(GameLog::deserialize(deserializer)?);
+        Ok(comm)
+    }
+}
+
+/// What `save_game()` writes: the game, along with the version of the save format
+#[derive(Serialize, Deserialize)]
+struct Save<G> {
+    version: u32,
+    game: G,
+}
+
+/// Just the version of a save, read before the rest of it
+#[derive(Deserialize)]
+struct SaveHeader {
+    version: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(bound(deserialize = "U: Deserialize<'de>"))]
 pub struct Game<U: RawPID> {
     pub game_id: usize,
     pub players: Players<U>,
+    /// Eliminated players, in the order they died
+    pub dead: Players<U>,
     pub phase: Phase<U>,
     pub contracts: Vec<Contract<U>>,
+    pub rules: GameRules,
+    pub timer: Option<PhaseTimer>,
+    pub moderators: Vec<U>,
+    /// The most players that can sign up, if there is a limit
+    pub max_players: Option<usize>,
+    pub paused: bool,
+    /// How many times each player has used a limited night action
+    pub ability_uses: HashMap<U, usize>,
+    /// Who each AVENGER has picked to take down with them
+    pub grudges: HashMap<U, U>,
+    /// Dead players who have cast their ghost vote
+    pub ghosts_spent: Vec<U>,
+    /// How many phases in a row each living player has gone without an action,
+    /// for the idle_modkill rule
+    pub idle: HashMap<U, usize>,
+    /// Players who have sent an action this phase
+    pub active: Vec<U>,
+    /// Who is watching the game with `spectate()`. Not saved, since their
+    /// Transports can't be, so spectators watch a loaded game again
+    #[serde(skip)]
+    pub spectators: Vec<U>,
+    /// The state before the last change, which a moderator can Undo
+    #[serde(skip)]
+    undo: Option<Snapshot<U>>,
+    /// Whether the game has changed since it was last autosaved
+    #[serde(skip)]
+    dirty: bool,
+    #[serde(skip)]
+    last_save: Option<SystemTime>,
+    /// Where `autosave()` writes the game, so that its final save can be dealt
+    /// with as soon as the game ends
+    #[serde(skip)]
+    save_file: Option<String>,
     #[serde(skip)]
+    pub save_format: SaveFormat,
+    #[serde(skip)]
+    pub save_on_end: SaveOnEnd,
+    /// Saved as just its GameLog, since its Transports can't be
+    #[serde(rename = "log", with = "saved_log")]
     comm: Comm<U>,
 }
 
@@ -44,110 +443,1452 @@ impl<U: RawPID> Game<U> {
         let mut game = Self {
             game_id,
             players: Vec::new(),
+            dead: Vec::new(),
             phase: Phase::Init,
             contracts,
+            rules: GameRules::default(),
+            timer: None,
+            moderators: Vec::new(),
+            max_players: None,
+            paused: false,
+            ability_uses: HashMap::new(),
+            grudges: HashMap::new(),
+            ghosts_spent: Vec::new(),
+            idle: HashMap::new(),
+            active: Vec::new(),
+            spectators: Vec::new(),
+            undo: None,
+            dirty: true,
+            last_save: None,
+            save_file: None,
+            save_format: SaveFormat::default(),
+            save_on_end: SaveOnEnd::default(),
             comm,
         };
 
-        game.comm.tx(Event::Init {
-            game_id: game.game_id,
+        game.comm.send(Event::Init {
+            game_id: game.game_id,
+        });
+
+        // Ensure no duplicate players
+        for player in players {
+            let _ = game.add_player(player);
+        }
+
+        game
+    }
+
+    /// Builds a game from each player's user, name, and role, ready to `start()`.
+    /// Fails with the first player `add_player()` rejects, such as a repeated user.
+    /// No contracts are made, so contract roles should be added to `contracts`.
+    pub fn from_setup(
+        game_id: usize,
+        spec: Vec<(U, Option<String>, Role)>,
+        comm: Comm<U>,
+    ) -> Result<Self, GameError<U>> {
+        let mut game = Self::new(game_id, Vec::new(), Vec::new(), comm);
+        for (user, name, role) in spec {
+            let player = match name {
+                Some(name) => Player::new(user, role).with_name(&name),
+                None => Player::new(user, role),
+            };
+            game.add_player(player)?;
+        }
+        Ok(game)
+    }
+
+    /// Adds a player before the game starts
+    pub fn add_player(&mut self, player: Player<U>) -> Result<(), GameError<U>> {
+        if self.phase != Phase::Init {
+            return Err(GameError::GameStarted);
+        }
+        if self.players.check(player.user_id).is_ok() {
+            return Err(GameError::PlayerExists {
+                pid: player.user_id,
+            });
+        }
+        if let Some(max) = self.max_players.filter(|&max| self.players.len() >= max) {
+            return Err(GameError::GameFull { max });
+        }
+        self.players.push(player);
+        Ok(())
+    }
+
+    /// Gives a signed up player their role, such as one who joined UNASSIGNED
+    pub fn assign_role(&mut self, player: U, role: Role) -> Result<(), GameError<U>> {
+        if self.phase != Phase::Init {
+            return Err(GameError::GameStarted);
+        }
+        let p = self
+            .players
+            .check(player)
+            .map_err(|_| GameError::PlayerNotFound { pid: player })?;
+        self.players[p].role = role;
+        Ok(())
+    }
+
+    /// Finds the living player with the given name, ignoring case. An exact match
+    /// is preferred, then a name starting with it, then a name containing it.
+    pub fn resolve_name(&self, name: &str) -> Result<U, GameError<U>> {
+        let name = name.trim().to_lowercase();
+        if name.is_empty() {
+            return Err(GameError::NameNotFound { name });
+        }
+        let named: Vec<(U, String)> = self
+            .players
+            .iter()
+            .filter_map(|p| Some((p.user_id, p.name.as_ref()?.to_lowercase())))
+            .collect();
+        let matching = |matches: &dyn Fn(&str) -> bool| -> Vec<U> {
+            named
+                .iter()
+                .filter(|(_, n)| matches(n))
+                .map(|(pid, _)| *pid)
+                .collect()
+        };
+        let candidates = [
+            matching(&|n| n == name),
+            matching(&|n| n.starts_with(&name)),
+            matching(&|n| n.contains(&name)),
+        ]
+        .into_iter()
+        .find(|candidates| !candidates.is_empty())
+        .unwrap_or_default();
+
+        match candidates.as_slice() {
+            [] => Err(GameError::NameNotFound { name }),
+            [pid] => Ok(*pid),
+            _ => Err(GameError::AmbiguousName { name, candidates }),
+        }
+    }
+
+    /// Limits how many players can sign up. Players who have already signed up
+    /// stay, even if there are more of them than the new limit.
+    pub fn set_max_players(&mut self, max: Option<usize>) -> Result<(), GameError<U>> {
+        if self.phase != Phase::Init {
+            return Err(GameError::GameStarted);
+        }
+        self.max_players = max;
+        Ok(())
+    }
+
+    /// Takes a player back out of the game before it starts. Once the game has
+    /// started, players can only leave it by being eliminated.
+    pub fn remove_player(&mut self, pid: U) -> Result<Player<U>, GameError<U>> {
+        if self.phase != Phase::Init {
+            return Err(GameError::GameStarted);
+        }
+        let player = self
+            .players
+            .check(pid)
+            .map_err(|_| GameError::PlayerNotFound { pid })?;
+        Ok(self.players.remove(player))
+    }
+}
+
+pub fn get_players_that<U: RawPID>(
+    players: &Players<U>,
+    f: impl Fn((Pidx, &Player<U>)) -> bool,
+) -> impl Iterator<Item = (Pidx, &Player<U>)> {
+    players.iter().enumerate().filter(move |(i, p)| f((*i, p)))
+}
+impl<U: RawPID> Game<U> {
+    // Handle if directory doesn't exist?
+    pub fn save_game(&self, fname: &str) -> Result<(), GameError<U>> {
+        let failed = |reason: String| GameError::SaveFailed { reason };
+        let mut f = File::create(fname).map_err(|e| failed(e.to_string()))?;
+        let save = Save {
+            version: SAVE_VERSION,
+            game: self,
+        };
+        match self.save_format {
+            SaveFormat::PrettyJson => {
+                serde_json::to_writer_pretty(&mut f, &save).map_err(|e| failed(e.to_string()))
+            }
+            SaveFormat::Binary => {
+                bincode::serialize_into(&mut f, &save).map_err(|e| failed(e.to_string()))
+            }
+        }
+    }
+
+    /// Saves the game if it has changed since the last autosave, and at least
+    /// `interval` has passed since then. Returns whether the game was saved.
+    /// Once the game has ended, the final save is always written, then handled
+    /// as `save_on_end` says. The game does that itself when it ends, if it has
+    /// been autosaved before.
+    pub fn autosave(
+        &mut self,
+        fname: &str,
+        now: SystemTime,
+        interval: Duration,
+    ) -> Result<bool, GameError<U>> {
+        self.save_file = Some(fname.to_string());
+        let too_soon = self
+            .last_save
+            .and_then(|last| now.duration_since(last).ok())
+            .is_some_and(|since| since < interval);
+        if !self.dirty || (too_soon && !self.has_ended()) {
+            return Ok(false);
+        }
+        self.save_game(fname)?;
+        if self.has_ended() {
+            self.end_save(fname, now)?;
+        }
+        self.dirty = false;
+        self.last_save = Some(now);
+        Ok(true)
+    }
+
+    /// Lets someone who isn't playing watch the game, sending them every Event
+    /// their SpectatorView shows from now on. From then on, every Action they
+    /// send is rejected. A player still alive can't spectate. A dead player can,
+    /// and can still ghost vote and use the dead chat, but only sees public Events
+    /// while they have a ghost vote left, so they can't cast it knowing every role.
+    pub fn spectate(
+        &mut self,
+        spectator: U,
+        transport: impl Transport<U> + 'static,
+        view: SpectatorView,
+    ) -> Result<(), GameError<U>> {
+        if self.players.iter().any(|p| p.user_id == spectator) {
+            return Err(GameError::PlayerExists { pid: spectator });
+        }
+        if !self.spectators.contains(&spectator) {
+            self.spectators.push(spectator);
+        }
+        let ghost = self.rules.ghost_vote
+            && self.dead.check(spectator).is_ok()
+            && !self.ghosts_spent.contains(&spectator);
+        let view = match ghost {
+            true => SpectatorView::PublicOnly,
+            false => view,
+        };
+        self.comm
+            .subscribe(transport, move |event| view.shows(event));
+        Ok(())
+    }
+
+    /// Writes the final save of a game that has just ended, if it has been autosaved,
+    /// and deletes or archives it as `save_on_end` says. Nothing is left to report
+    /// a failure to, so it is only logged.
+    fn save_ended(&mut self, now: SystemTime) {
+        if !self.has_ended() || !self.dirty {
+            return;
+        }
+        let fname = match self.save_file.take() {
+            Some(fname) => fname,
+            None => return,
+        };
+        match self
+            .save_game(&fname)
+            .and_then(|_| self.end_save(&fname, now))
+        {
+            Ok(()) => {
+                self.dirty = false;
+                self.last_save = Some(now);
+            }
+            Err(e) => log::warn!("Couldn't finish the save of game {}: {}", self.game_id, e),
+        }
+    }
+
+    /// Deletes or archives a finished game's save, as `save_on_end` says
+    fn end_save(&self, fname: &str, now: SystemTime) -> Result<(), GameError<U>> {
+        let failed = |e: std::io::Error| GameError::SaveFailed {
+            reason: e.to_string(),
+        };
+        match &self.save_on_end {
+            SaveOnEnd::Keep => Ok(()),
+            SaveOnEnd::Delete => std::fs::remove_file(fname).map_err(failed),
+            SaveOnEnd::Archive { dir } => {
+                let timestamp = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+                let ext = Path::new(fname).extension().and_then(|e| e.to_str());
+                let archived = format!("{}-{}.{}", self.game_id, timestamp, ext.unwrap_or("json"));
+                std::fs::create_dir_all(dir).map_err(failed)?;
+                std::fs::rename(fname, Path::new(dir).join(archived)).map_err(failed)
+            }
+        }
+    }
+
+    /// Runs every check `start()` makes, without starting the game,
+    /// returning all of the problems found rather than just the first.
+    pub fn validate_setup(&self) -> Result<(), Vec<GameError<U>>> {
+        let mut errors = Vec::new();
+        if self.phase != Phase::Init {
+            errors.push(GameError::GameStarted);
+        }
+        let unassigned: Vec<U> = self
+            .players
+            .iter()
+            .filter(|p| p.role == Role::UNASSIGNED)
+            .map(|p| p.user_id)
+            .collect();
+        if self.players.len() < 3 {
+            errors.push(GameError::NotEnoughPlayers {
+                found: self.players.len(),
+            });
+        } else if unassigned.is_empty() {
+            if let Some(winner) = check_team_numbers(&self.players) {
+                errors.push(GameError::GameDecided { winner });
+            }
+        }
+        for pid in unassigned {
+            errors.push(GameError::Unassigned { pid });
+        }
+        for player in &self.players {
+            if let Role::Custom(custom) = &player.role {
+                if !custom.bound() {
+                    errors.push(GameError::UnknownRole {
+                        name: custom.name().to_owned(),
+                    });
+                }
+            }
+        }
+
+        let mut unique_roles: Vec<Role> = Vec::new();
+        for player in self.players.iter().filter(|p| p.role.unique()) {
+            if !unique_roles.contains(&player.role) {
+                unique_roles.push(player.role.to_owned());
+            }
+        }
+        for role in unique_roles {
+            let count = self.players.iter().filter(|p| p.role == role).count();
+            if count > 1 {
+                errors.push(GameError::DuplicateRole { role, count });
+            }
+        }
+
+        let rules = &self.rules;
+        let mut incompatible = |rule, requires| {
+            errors.push(GameError::IncompatibleRule { rule, requires });
+        };
+        if rules.max_days_winner.is_some() && rules.max_days.is_none() {
+            incompatible("max_days_winner", "max_days");
+        }
+        let no_deadline = rules.day_length.is_none() && rules.night_length.is_none();
+        if !rules.time_warnings.is_empty() && no_deadline {
+            incompatible("time_warnings", "a day_length or night_length");
+        }
+        // A Dynamic election elects a majority as soon as it is reached, so ties are
+        // only settled at a deadline: by Plurality, or between several majorities
+        if rules.tie_break != TieBreak::default()
+            && rules.election_process == ElectionProcess::Dynamic
+            && rules.deadline_behavior() != DeadlineBehavior::Plurality
+        {
+            incompatible(
+                "tie_break",
+                "a deadline election or Plurality deadline_behavior",
+            );
+        }
+        if rules.ability_limits.keys().any(|role| !role.targeting()) {
+            incompatible("ability_limits", "roles with a night action");
+        }
+        if rules.ability_nights.keys().any(|role| !role.targeting()) {
+            incompatible("ability_nights", "roles with a night action");
+        }
+
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(errors),
+        }
+    }
+
+    pub fn start(&mut self) -> Result<(), GameError<U>> {
+        if let Err(mut errors) = self.validate_setup() {
+            return Err(errors.remove(0));
+        }
+        let start_night = match self.rules.start_night {
+            StartNight::Always => true,
+            StartNight::Even => self.players.len() % 2 == 0,
+            StartNight::Never => false,
+        };
+        let next_phase = match start_night {
+            true => Phase::new_night(1),
+            false => Phase::new_day(1, Vec::new()),
+        };
+        self.comm.send(Event::Start {
+            players: self.players.clone(),
+            contracts: self.contracts.clone(),
+            phase: next_phase.kind(),
+        });
+        match self.rules.start_info {
+            StartInfo::None => {}
+            StartInfo::Mafia => {
+                let mafia = self.team_count(Team::Mafia);
+                self.comm.send(Event::MafiaAnnounce {
+                    mafia,
+                    others: self.players.len() - mafia,
+                });
+            }
+            StartInfo::Team => {
+                let teams = [Team::Town, Team::Mafia, Team::Rogue, Team::Cult];
+                let team_counts = teams.into_iter().map(|team| (team, self.team_count(team)));
+                self.comm.send(Event::TeamAnnounce {
+                    team_counts: team_counts.filter(|(_, count)| *count > 0).collect(),
+                });
+            }
+            StartInfo::Role => self.comm.send(Event::SetupAnnounce {
+                role_counts: self.role_counts(),
+            }),
+        }
+        for p in 0..self.players.len() {
+            self.comm.send(self.role_pm(p));
+        }
+        self.next_phase(next_phase);
+        Ok(())
+    }
+
+    fn team_count(&self, team: Team) -> usize {
+        self.players
+            .iter()
+            .filter(|p| p.role.team() == team)
+            .count()
+    }
+
+    /// How many players have each role, in alphabetical order so that the order
+    /// says nothing about who has which role
+    fn role_counts(&self) -> Vec<(Role, usize)> {
+        let mut counts: Vec<(Role, usize)> = Vec::new();
+        for player in &self.players {
+            match counts.iter_mut().find(|(role, _)| *role == player.role) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((player.role.to_owned(), 1)),
+            }
+        }
+        counts.sort_by_key(|(role, _)| role.to_string());
+        counts
+    }
+
+    /// Moves to the next phase, restarting the phase timer
+    fn next_phase(&mut self, next_phase: Phase<U>) {
+        // A Day is counted once its Dusk ends, so no one is modkilled mid-Dusk
+        let expected = match (&self.phase, &next_phase) {
+            (_, Phase::End(..)) | (Phase::Day(_), Phase::Dusk(_)) => None,
+            (Phase::Day(_) | Phase::Dusk(_), _) => {
+                Some(self.players.iter().map(|p| p.user_id).collect())
+            }
+            (Phase::Night(night), _) => Some(self.expected_at_night(night)),
+            // Nothing done before the game starts counts
+            (Phase::Init, _) => Some(Vec::new()),
+            _ => None,
+        };
+        self.timer = None;
+        self.dirty = true;
+        self.phase
+            .next_phase(next_phase, &self.players, &self.rules, &self.comm);
+        if let Some(expected) = expected {
+            let active = std::mem::take(&mut self.active);
+            self.check_idle(active, expected);
+        }
+    }
+
+    /// The players who had something to do during a Night: those with a night action
+    /// that wasn't resting or stunned, or everyone under the night_vote rule
+    fn expected_at_night(&self, night: &Night) -> Vec<U> {
+        let expected = match self.rules.night_vote {
+            true => (0..self.players.len()).collect(),
+            false => night.actors(&self.players, &self.rules),
+        };
+        expected.iter().map(|p| self.players[*p].user_id).collect()
+    }
+
+    /// Under the idle_modkill rule, counts the phase just ended against every living
+    /// player who was expected to act during it but made no action, and modkills
+    /// those who have now gone without one for too many phases in a row
+    fn check_idle(&mut self, active: Vec<U>, expected: Vec<U>) {
+        let limit = match self.rules.idle_modkill {
+            Some(limit) => limit,
+            None => return,
+        };
+        let players = &self.players;
+        self.idle.retain(|id, _| players.check(*id).is_ok());
+        let mut idle = Vec::new();
+        for (p, player) in self.players.iter().enumerate() {
+            if active.contains(&player.user_id) {
+                self.idle.remove(&player.user_id);
+                continue;
+            }
+            if !expected.contains(&player.user_id) {
+                continue;
+            }
+            let phases = self.idle.entry(player.user_id).or_default();
+            *phases += 1;
+            if *phases >= limit {
+                idle.push(p);
+            }
+        }
+        if idle.is_empty() {
+            return;
+        }
+        for &p in &idle {
+            self.idle.remove(&self.players[p].user_id);
+            self.comm.send(Event::IdleModkill {
+                player: self.players[p].to_owned(),
+                phases: limit,
+            });
+        }
+        // Each idle player is their own proxy, as no one else caused their death
+        let deaths: Vec<_> = idle.iter().map(|p| (*p, *p)).collect();
+        if let Some(end) = self.eliminate_all(&deaths) {
+            self.next_phase(end);
+        }
+    }
+
+    /// Advances the phase timer to the given time, sending any warnings that are due.
+    /// When the deadline is reached, the phase is resolved. Should be called regularly
+    /// by whatever is running the game. The timer for a phase starts on its first tick.
+    pub fn tick(&mut self, now: SystemTime) {
+        let length = match self.phase.kind() {
+            PhaseKind::Day => self.rules.day_length,
+            PhaseKind::Dusk => Some(self.rules.dusk_length.unwrap_or(DUSK_LENGTH)),
+            PhaseKind::Grace => self.rules.hammer_grace,
+            PhaseKind::Night => self.rules.night_length,
+            _ => None,
+        };
+        let length = match length {
+            Some(length) => length,
+            None => return,
+        };
+
+        let timer = self
+            .timer
+            .get_or_insert_with(|| PhaseTimer::new(now, length, &self.rules.time_warnings));
+        if self.paused {
+            timer.pause(now);
+            timer.hold(now);
+            return;
+        }
+        for remaining in timer.advance(now) {
+            self.comm.send(Event::TimeWarning { remaining });
+        }
+        if !timer.expired(now) {
+            return;
+        }
+
+        // Undoing the deadline gives the phase its full length again
+        self.timer = None;
+        self.undo = Some(self.snapshot());
+        self.dirty = true;
+        let _ = match self.phase.kind() {
+            PhaseKind::Day => self.resolve_day_at_deadline(),
+            PhaseKind::Dusk => self.resolve_dusk_at_deadline(),
+            PhaseKind::Grace => self.end_grace(),
+            _ => self.resolve_night_at_deadline(),
+        };
+        self.save_ended(now);
+    }
+
+    pub fn handle(&mut self, cmd: Action<U>) -> Result<(), InvalidActionError<U>> {
+        if self.comm.disconnected() {
+            return Err(InvalidActionError::Disconnected);
+        }
+        let (actor, action) = (cmd.actor(), cmd.kind());
+        // A dead player watching as a spectator still takes part as a dead player
+        let playing = self
+            .players
+            .iter()
+            .chain(&self.dead)
+            .any(|p| p.user_id == actor);
+        if self.spectators.contains(&actor) && !playing {
+            return Err(InvalidActionError::Spectating { pid: actor });
+        }
+        let query = matches!(
+            action,
+            ActionKind::MyRole
+                | ActionKind::LegalTargets
+                | ActionKind::TeamChat
+                | ActionKind::Phase
+                | ActionKind::Resync
+                | ActionKind::ModInspect
+                | ActionKind::PendingActions
+                | ActionKind::NotVoted
+                | ActionKind::SetupPreview
+        );
+        // Only actions that change the game can be undone
+        let undoable = !query
+            && !matches!(
+                action,
+                ActionKind::Pause | ActionKind::Resume | ActionKind::ExtendPhase | ActionKind::Undo
+            );
+        let snapshot = undoable.then(|| self.snapshot());
+        // Any action at all, even one that is rejected, shows the player isn't idle
+        if !self.active.contains(&actor) {
+            self.active.push(actor);
+        }
+        let result = match cmd {
+            Action::Pause { moderator } => self.handle_pause(moderator, true),
+            Action::Resume { moderator } => self.handle_pause(moderator, false),
+            Action::ExtendPhase { moderator, by } => self.handle_extend_phase(moderator, by),
+            Action::EndGrace { moderator } => self.handle_end_grace(moderator),
+            Action::MyRole { player } => self.handle_my_role(player),
+            Action::LegalTargets { player } => self.handle_legal_targets(player),
+            Action::Phase { from } => self.handle_phase_query(from),
+            Action::Resync { after_seq, .. } => self.comm.resync(after_seq),
+            Action::ModInspect { moderator, player } => self.handle_mod_inspect(moderator, player),
+            Action::PendingActions { moderator } => self.handle_pending_actions(moderator),
+            Action::NotVoted { moderator } => self.handle_not_voted(moderator),
+            Action::SetupPreview { moderator } => self.handle_setup_preview(moderator),
+            Action::Join { player } => self.handle_join(player),
+            Action::Leave { player } => self.handle_leave(player),
+            Action::Remove { moderator, player } => self.handle_remove(moderator, player),
+            Action::Modkill { moderator, player } => self.handle_modkill(moderator, player),
+            Action::Excuse { moderator, player } => self.handle_excuse(moderator, player),
+            Action::Revive { moderator, player } => self.handle_revive(moderator, player),
+            Action::Undo { moderator } => self.handle_undo(moderator),
+            _ if self.paused => Err(InvalidActionError::Paused),
+            Action::Vote { voter, ballot } => self.handle_vote(voter, ballot),
+            Action::SkipDay { voter } => self.handle_skip_day(voter),
+            Action::ConfirmHammer { voter } => self.handle_confirm_hammer(voter),
+            Action::Verdict { juror, guilty } => self.handle_verdict(juror, guilty),
+            Action::Reveal { celeb } => self.handle_reveal(celeb),
+            Action::Target { actor, target } => self.handle_target(actor, target),
+            Action::Mark { killer, mark } => self.handle_mark(killer, mark),
+            Action::TeamChat { from, text } => self.handle_team_chat(from, text),
+        };
+
+        if let Err(InvalidActionError::InvalidPhase { expected, found }) = &result {
+            self.wrong_phase(actor, action.to_owned(), expected.to_owned(), found.kind());
+        }
+        if result.is_ok() && !query {
+            self.dirty = true;
+            if snapshot.is_some() {
+                self.undo = snapshot;
+            }
+            self.save_ended(SystemTime::now());
+        }
+        result
+    }
+
+    /// Tells a player why their Day action was rejected at Night, or vice versa
+    fn wrong_phase(&self, actor: U, action: ActionKind, expected: PhaseKind, got: PhaseKind) {
+        let day_or_night = |kind: &PhaseKind| matches!(kind, PhaseKind::Day | PhaseKind::Night);
+        if !day_or_night(&expected) || !day_or_night(&got) {
+            return;
+        }
+        if let Ok(player) = self.players.check(actor) {
+            self.comm.send(Event::WrongPhase {
+                player: self.players[player].to_owned(),
+                action,
+                expected,
+                got,
+            });
+        }
+    }
+
+    /// Rejects a night action once the deadline is within the `action_lock` rule,
+    /// telling the player why
+    fn check_unlocked(&self, actor: U, action: ActionKind) -> Result<(), InvalidActionError<U>> {
+        let locked = match (&self.timer, self.rules.action_lock) {
+            (Some(timer), Some(lock)) => timer.locked(lock),
+            _ => false,
+        };
+        if !locked {
+            return Ok(());
+        }
+        if let Ok(player) = self.players.check(actor) {
+            self.comm.send(Event::ActionsLocked {
+                player: self.players[player].to_owned(),
+                action,
+            });
+        }
+        Err(InvalidActionError::ActionsLocked)
+    }
+
+    fn check_moderator(&self, moderator: U) -> Result<(), InvalidActionError<U>> {
+        match self.moderators.contains(&moderator) {
+            true => Ok(()),
+            false => Err(InvalidActionError::NotModerator { pid: moderator }),
+        }
+    }
+
+    /// Privately shows a moderator any player's role, living or dead,
+    /// regardless of what the rules reveal to everyone else
+    fn handle_mod_inspect(&mut self, moderator: U, pid: U) -> Result<(), InvalidActionError<U>> {
+        self.check_moderator(moderator)?;
+        let (player, alive) = match self.players.check(pid) {
+            Ok(p) => (self.players[p].to_owned(), true),
+            Err(err) => match self.dead.iter().find(|p| p.user_id == pid) {
+                Some(player) => (player.to_owned(), false),
+                None => return Err(err),
+            },
+        };
+        self.comm.send(Event::ModInspect {
+            moderator,
+            role: player.role.to_owned(),
+            player,
+            alive,
+        });
+        Ok(())
+    }
+
+    /// Privately shows a moderator who still has to make their night action
+    fn handle_pending_actions(&mut self, moderator: U) -> Result<(), InvalidActionError<U>> {
+        self.check_moderator(moderator)?;
+        let missing = self
+            .phase
+            .is_night()?
+            .pending_actions(&self.players)
+            .into_iter()
+            .map(|p| self.players[p].to_owned())
+            .collect();
+        self.comm.send(Event::PendingActions { moderator, missing });
+        Ok(())
+    }
+
+    /// Privately shows a moderator every role that has been dealt, before the game starts
+    fn handle_setup_preview(&mut self, moderator: U) -> Result<(), InvalidActionError<U>> {
+        self.check_moderator(moderator)?;
+        if self.phase != Phase::Init {
+            return Err(InvalidActionError::InvalidPhase {
+                expected: PhaseKind::Init,
+                found: Box::new(self.phase.to_owned()),
+            });
+        }
+        let roles = self
+            .players
+            .iter()
+            .map(|p| (p.user_id, p.role.to_owned()))
+            .collect();
+        self.comm.send(Event::SetupPreview { moderator, roles });
+        Ok(())
+    }
+
+    /// Privately shows a moderator who hasn't voted today
+    fn handle_not_voted(&mut self, moderator: U) -> Result<(), InvalidActionError<U>> {
+        self.check_moderator(moderator)?;
+        let missing = self
+            .phase
+            .is_day()?
+            .not_voted(&self.players)
+            .into_iter()
+            .map(|p| self.players[p].to_owned())
+            .collect();
+        self.comm.send(Event::NotVoted { moderator, missing });
+        Ok(())
+    }
+
+    /// Signs a user up for the game before it starts. They are UNASSIGNED until
+    /// given a role with `assign_role()`, and the game can't start before then.
+    fn handle_join(&mut self, pid: U) -> Result<(), InvalidActionError<U>> {
+        let player = Player::new(pid, Role::UNASSIGNED);
+        self.add_player(player.to_owned())
+            .map_err(|e| self.setup_error(e))?;
+        self.comm.send(Event::PlayerJoined { player });
+        Ok(())
+    }
+
+    /// Lets a player back out of the game before it starts
+    fn handle_leave(&mut self, pid: U) -> Result<(), InvalidActionError<U>> {
+        let player = self.remove_player(pid).map_err(|e| self.setup_error(e))?;
+        self.comm.send(Event::PlayerLeft { player });
+        Ok(())
+    }
+
+    /// Removes a player before the game starts, without eliminating them
+    fn handle_remove(&mut self, moderator: U, pid: U) -> Result<(), InvalidActionError<U>> {
+        self.check_moderator(moderator)?;
+        self.remove_player(pid).map_err(|e| self.setup_error(e))?;
+        Ok(())
+    }
+
+    /// Why an Action that changes the game's setup was rejected
+    fn setup_error(&self, error: GameError<U>) -> InvalidActionError<U> {
+        match error {
+            GameError::GameStarted => InvalidActionError::InvalidPhase {
+                expected: PhaseKind::Init,
+                found: Box::new(self.phase.to_owned()),
+            },
+            GameError::PlayerNotFound { pid } => InvalidActionError::PlayerNotFound { pid },
+            error => InvalidActionError::Setup { error },
+        }
+    }
+
+    /// Eliminates a player outside of an election or a kill, such as for inactivity.
+    /// Like any elimination, this clears the current phase's votes or night actions.
+    fn handle_modkill(&mut self, moderator: U, pid: U) -> Result<(), InvalidActionError<U>> {
+        self.check_moderator(moderator)?;
+        if !matches!(self.phase, Phase::Day(_) | Phase::Night(_)) {
+            return Err(InvalidActionError::InvalidPhase {
+                expected: PhaseKind::Day,
+                found: Box::new(self.phase.to_owned()),
+            });
+        }
+        let player = self.players.check(pid)?;
+        self.comm.send(Event::Modkill {
+            player: self.players[player].to_owned(),
+            role: self.players[player].role.to_owned(),
+        });
+        self.refund_pending_uses();
+        // The player is their own proxy, as no one else caused their death
+        if let Some(end) = self.eliminate(&[player], player) {
+            self.next_phase(end);
+        }
+        Ok(())
+    }
+
+    /// Gives back the use of every limited ability Targeted tonight, as an elimination
+    /// before dawn clears the night actions without resolving them
+    fn refund_pending_uses(&mut self) {
+        let night = match &self.phase {
+            Phase::Night(night) => night,
+            _ => return,
+        };
+        for (&actor, target) in &night.targets {
+            let player = &self.players[actor];
+            let limit = match self.rules.ability_limits.get(&player.role) {
+                Some(&limit) if *target != Target::Abstain => limit,
+                _ => continue,
+            };
+            if let Some(used) = self.ability_uses.get_mut(&player.user_id) {
+                *used = used.saturating_sub(1);
+                self.comm.send(Event::AbilityUsed {
+                    player: player.to_owned(),
+                    remaining: limit - *used,
+                });
+            }
+        }
+    }
+
+    /// Excuses a player from voting for the rest of the Day, such as when they're away.
+    /// They no longer count towards the thresholds, and their standing vote is dropped.
+    fn handle_excuse(&mut self, moderator: U, pid: U) -> Result<(), InvalidActionError<U>> {
+        self.check_moderator(moderator)?;
+        let day = self.phase.is_day()?;
+        let player = self.players.check(pid)?;
+        day.excuse(player);
+        self.comm.send(Event::PlayerExcused {
+            player: self.players[player].to_owned(),
+            threshold: majority(day.voters(&self.players)),
+        });
+        Ok(())
+    }
+
+    /// Brings a dead player back with their original role, for when they were
+    /// eliminated by mistake. If this means the game no longer has a winner, the
+    /// game continues with the phase that would have come next.
+    fn handle_revive(&mut self, moderator: U, pid: U) -> Result<(), InvalidActionError<U>> {
+        self.check_moderator(moderator)?;
+        let dead = self
+            .dead
+            .iter()
+            .position(|p| p.user_id == pid)
+            .ok_or(InvalidActionError::PlayerNotFound { pid })?;
+        let player = self.dead.remove(dead);
+        // Adding to the end keeps the other players' Pidxs valid
+        self.players.push(player.to_owned());
+        self.comm.send(Event::Revived { player });
+
+        if let Phase::End(..) = self.phase {
+            let next_phase = self.check_win().unwrap_or_else(|| self.phase_after_end());
+            if !matches!(next_phase, Phase::End(..)) {
+                self.next_phase(next_phase);
+            }
+        }
+        Ok(())
+    }
+
+    fn snapshot(&self) -> Snapshot<U> {
+        Snapshot {
+            players: self.players.clone(),
+            dead: self.dead.clone(),
+            phase: self.phase.clone(),
+            contracts: self.contracts.clone(),
+            ability_uses: self.ability_uses.clone(),
+            grudges: self.grudges.clone(),
+            ghosts_spent: self.ghosts_spent.clone(),
+            idle: self.idle.clone(),
+            active: self.active.clone(),
+            timer: self.timer.clone(),
+            paused: self.paused,
+        }
+    }
+
+    /// Reverts the last accepted action or deadline, including any resolution
+    /// it caused. Only one step can be undone.
+    fn handle_undo(&mut self, moderator: U) -> Result<(), InvalidActionError<U>> {
+        self.check_moderator(moderator)?;
+        let snapshot = self.undo.take().ok_or(InvalidActionError::NothingToUndo)?;
+        let restored = snapshot
+            .players
+            .iter()
+            .filter(|p| self.players.check(p.user_id).is_err())
+            .cloned()
+            .collect();
+
+        self.players = snapshot.players;
+        self.dead = snapshot.dead;
+        self.phase = snapshot.phase;
+        self.contracts = snapshot.contracts;
+        self.ability_uses = snapshot.ability_uses;
+        self.grudges = snapshot.grudges;
+        self.ghosts_spent = snapshot.ghosts_spent;
+        self.idle = snapshot.idle;
+        self.active = snapshot.active;
+        self.timer = snapshot.timer;
+        if self.paused != snapshot.paused {
+            self.paused = snapshot.paused;
+            self.comm.send(match self.paused {
+                true => Event::Paused,
+                false => Event::Resumed,
+            });
+        }
+
+        self.comm.send(Event::Undone {
+            phase: self.phase.kind(),
+            number: self.phase.number(),
+            restored,
+        });
+        Ok(())
+    }
+
+    /// The phase that would have followed the last Day or Night, had the game not ended
+    fn phase_after_end(&self) -> Phase<U> {
+        let last_phase = self.comm.history().into_iter().rev().find_map(|e| match e {
+            Event::Day { day_no, .. } => Some(Phase::new_night(day_no)),
+            Event::Night { night_no, .. } => Some(Phase::new_day(night_no + 1, Vec::new())),
+            _ => None,
+        });
+        last_phase.unwrap_or_else(|| Phase::new_day(1, Vec::new()))
+    }
+
+    fn handle_pause(&mut self, moderator: U, paused: bool) -> Result<(), InvalidActionError<U>> {
+        self.check_moderator(moderator)?;
+        if self.paused == paused {
+            return Ok(());
+        }
+        self.paused = paused;
+        if let Some(timer) = self.timer.as_mut() {
+            let now = timer.now();
+            match paused {
+                true => timer.pause(now),
+                false => timer.resume(now),
+            }
+        }
+        self.comm.send(match paused {
+            true => Event::Paused,
+            false => Event::Resumed,
+        });
+        Ok(())
+    }
+
+    /// Gives the current phase more time before its deadline. The phase timer
+    /// starts on its first tick, so there is nothing to extend before then.
+    fn handle_extend_phase(
+        &mut self,
+        moderator: U,
+        by: Duration,
+    ) -> Result<(), InvalidActionError<U>> {
+        self.check_moderator(moderator)?;
+        let timer = self.timer.as_mut().ok_or(InvalidActionError::NoTimer)?;
+        let new_remaining = timer.extend(by, &self.rules.time_warnings);
+        self.comm.send(Event::PhaseExtended { new_remaining });
+        Ok(())
+    }
+
+    fn handle_end_grace(&mut self, moderator: U) -> Result<(), InvalidActionError<U>> {
+        self.check_moderator(moderator)?;
+        self.end_grace()
+    }
+
+    /// Starts the Night once the hammer_grace period is over
+    pub fn end_grace(&mut self) -> Result<(), InvalidActionError<U>> {
+        let day_no = match &self.phase {
+            Phase::Grace(grace) => grace.day_no,
+            _ => {
+                return Err(InvalidActionError::InvalidPhase {
+                    expected: PhaseKind::Grace,
+                    found: Box::new(self.phase.to_owned()),
+                })
+            }
+        };
+        self.next_phase(Phase::new_night(day_no));
+        Ok(())
+    }
+
+    /// Replies with the current phase without changing anything, so it works
+    /// for anyone, even while paused.
+    fn handle_phase_query(&mut self, from: U) -> Result<(), InvalidActionError<U>> {
+        self.comm.send(Event::Phase {
+            to: from,
+            phase: self.phase.kind(),
+            number: self.phase.number(),
         });
+        Ok(())
+    }
 
-        // Ensure no duplicate players
-        for player in players {
-            if game.players.check(player.user_id).is_err() {
-                game.players.push(player.to_owned());
-            }
-        }
+    /// Replaces the Transport the game's Events are sent to, such as when a
+    /// consumer that went away comes back. Use a Resync to catch it up.
+    pub fn reconnect(&mut self, transport: impl Transport<U> + 'static) {
+        self.comm.reconnect(transport);
+    }
 
-        game
+    pub fn current_phase(&self) -> &Phase<U> {
+        &self.phase
+    }
+    pub fn in_day(&self) -> bool {
+        matches!(self.phase, Phase::Day(_))
+    }
+    pub fn in_night(&self) -> bool {
+        matches!(self.phase, Phase::Night(_))
+    }
+    pub fn has_ended(&self) -> bool {
+        matches!(self.phase, Phase::End(..))
     }
-}
 
-pub fn get_players_that<U: RawPID>(
-    players: &Players<U>,
-    f: impl Fn((Pidx, &Player<U>)) -> bool,
-) -> impl Iterator<Item = (Pidx, &Player<U>)> {
-    players.iter().enumerate().filter(move |(i, p)| f((*i, p)))
-}
-impl<U: RawPID> Game<U> {
-    // TODO: Custom error?
-    // Handle if directory doesn't exist?
-    pub fn save_game(&self, fname: &str) -> Result<(), ()> {
-        let mut f = File::create(fname).map_err(|_| ())?;
-        serde_json::to_writer_pretty(&mut f, &self).map_err(|_| ())?;
+    /// A record of the whole game for write-ups. None until the game has ended,
+    /// since it reveals every role and night action
+    pub fn transcript(&self) -> Option<Transcript<U>> {
+        self.has_ended().then(|| self.comm.log().transcript)
+    }
+
+    fn handle_my_role(&mut self, player: U) -> Result<(), InvalidActionError<U>> {
+        let player = self.players.check(player)?;
+        self.comm.send(self.role_pm(player));
         Ok(())
     }
 
-    pub fn start(&mut self) -> Result<(), ()> {
-        match self.phase {
-            Phase::Init => {}
-            _ => return Err(()),
-        }
-        if self.players.len() < 3 {
-            // self.comm.tx(Event::InvalidCommand(
-            //     "Can't start game with less than 3 players".to_string(),
-            // ));
-            return Err(());
-        }
-        if check_team_numbers(&self.players).is_some() {
-            // self.comm.tx(Event::InvalidCommand(
-            //     "Can't start game with given roles".to_string(),
-            // ));
-            return Err(());
-        }
-        let next_phase = match self.players.len() % 2 == 0 {
-            true => Phase::new_night(1),
-            false => Phase::new_day(1, Vec::new()),
-        };
-        self.comm.tx(Event::Start {
-            players: self.players.clone(),
-            contracts: self.contracts.clone(),
-            phase: next_phase.kind(),
+    /// Privately shows a player who their Target could name right now
+    fn handle_legal_targets(&mut self, player: U) -> Result<(), InvalidActionError<U>> {
+        let actor = self.players.check(player)?;
+        let targets = self
+            .legal_targets(actor)
+            .into_iter()
+            .map(|p| self.players[p].to_owned())
+            .collect();
+        self.comm.send(Event::LegalTargets {
+            player: self.players[actor].to_owned(),
+            targets,
         });
-        self.phase.next_phase(next_phase, &self.players, &self.comm);
         Ok(())
     }
 
-    pub fn handle(&mut self, cmd: Action<U>) -> Result<(), InvalidActionError<U>> {
-        let result = match cmd {
-            Action::Vote { voter, ballot } => self.handle_vote(voter, ballot),
-            Action::Reveal { celeb } => self.handle_reveal(celeb),
-            Action::Target { actor, target } => self.handle_target(actor, target),
-            Action::Mark { killer, mark } => self.handle_mark(killer, mark),
+    /// The living players an actor's Target could name right now, following their
+    /// role's restrictions. At Night, empty for a role without a Target, once its uses
+    /// are spent, or on a night its ability is resting. At Dusk, the options of the
+    /// actor's dusk action. An AVENGER can name anyone else during the Day or Night,
+    /// and no one else has a Target in any other phase.
+    /// A BUSDRIVER can name any of these as either half of their pair.
+    pub fn legal_targets(&self, actor: Pidx) -> Vec<Pidx> {
+        let role = &self.players[actor].role;
+        let everyone = 0..self.players.len();
+        match &self.phase {
+            Phase::Day(_) | Phase::Night(_) if *role == Role::AVENGER => {
+                everyone.filter(|p| *p != actor).collect()
+            }
+            Phase::Dusk(dusk) => dusk.actions.get(&actor).cloned().unwrap_or_default(),
+            Phase::Night(night)
+                if !night.stunned.contains(&actor) && self.check_ability(actor, false).is_ok() =>
+            {
+                everyone
+                    .filter(|p| {
+                        let choice = Choice::Player(*p);
+                        role.valid_target(actor, &choice, &self.players, &self.rules)
+                            .is_ok()
+                    })
+                    .collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Checks that an actor can use their night action right now, whether for a
+    /// Target or for listing their legal targets. Abstaining is always allowed to a
+    /// role with a night action. Changing a target already made tonight doesn't use
+    /// the ability again, so it's allowed once the uses are spent.
+    fn check_ability(&self, actor: Pidx, abstain: bool) -> Result<(), InvalidActionError<U>> {
+        let night = match &self.phase {
+            Phase::Night(night) => night,
+            phase => {
+                return Err(InvalidActionError::InvalidPhase {
+                    expected: PhaseKind::Night,
+                    found: Box::new(phase.to_owned()),
+                })
+            }
+        };
+        let player = &self.players[actor];
+        let role = player.role.to_owned();
+        if !role.targeting() {
+            return Err(InvalidActionError::InvalidRole {
+                role,
+                action: ActionKind::Target,
+            });
+        }
+        if abstain {
+            return Ok(());
+        }
+        if self.rules.on_cooldown(&role, night.number()) {
+            return Err(InvalidActionError::AbilityOnCooldown {
+                role,
+                night_no: night.number(),
+            });
+        }
+        let retarget = matches!(night.targets.get(&actor), Some(t) if *t != Target::Abstain);
+        let used = self.ability_uses.get(&player.user_id).copied().unwrap_or(0);
+        match self.rules.ability_limits.get(&role) {
+            Some(&limit) if !retarget && used >= limit => {
+                Err(InvalidActionError::AbilitySpent { role })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Who is in the game right now, for checking Choices before they are sent in
+    pub fn roster(&self) -> Roster<U> {
+        let ids = |players: &Players<U>| players.iter().map(|p| p.user_id).collect();
+        let legal_targets: HashMap<U, Vec<U>> = (0..self.players.len())
+            .map(|p| {
+                let targets = self.legal_targets(p).into_iter();
+                let targets = targets.map(|t| self.players[t].user_id).collect();
+                (self.players[p].user_id, targets)
+            })
+            .collect();
+        Roster {
+            alive: ids(&self.players),
+            dead: ids(&self.dead),
+            legal_targets,
+        }
+    }
+
+    /// The private message telling a player their role, and who their
+    /// teammates are if their team knows each other
+    fn role_pm(&self, p: Pidx) -> Event<U> {
+        let player = self.players[p].to_owned();
+        let team = player.role.team();
+        let known = match team {
+            Team::Mafia => self.rules.mafia_knowledge == MafiaKnowledge::Known,
+            _ => team.has_chat(),
         };
+        let teammates = match known {
+            true => get_players_that(&self.players, |(i, q)| i != p && q.role.team() == team)
+                .map(|(_, q)| q.to_owned())
+                .collect(),
+            false => Vec::new(),
+        };
+        Event::YourRole {
+            role: player.role.to_owned(),
+            team,
+            description: player.role.description().to_string(),
+            teammates,
+            player,
+        }
+    }
 
-        // if let SaveStrategy::PerChange(fname) = &self.comm.save {
-        //     self.save_game(fname).expect("Saving game should work");
-        // };
-        result
+    fn handle_team_chat(&mut self, from: U, text: String) -> Result<(), InvalidActionError<U>> {
+        if let Ok(dead) = self.dead.check(from) {
+            return self.handle_dead_chat(dead, text);
+        }
+        let from = self.players.check(from)?;
+        let team = self.players[from].role.team();
+        let blind = team == Team::Mafia && self.rules.mafia_knowledge == MafiaKnowledge::Blind;
+        if !team.has_chat() || blind {
+            return Err(InvalidActionError::InvalidRole {
+                role: self.players[from].role.to_owned(),
+                action: ActionKind::TeamChat,
+            });
+        }
+        // Only living players are in self.players, so the dead don't receive it
+        let to: Vec<_> = get_players_that(&self.players, |(_, p)| p.role.team() == team)
+            .map(|(_, p)| p.to_owned())
+            .collect();
+        let ids: Vec<_> = to.iter().map(|p| p.user_id).collect();
+        self.comm.send_to(
+            &ids,
+            Event::TeamMessage {
+                from: self.players[from].to_owned(),
+                to,
+                text,
+            },
+        );
+        Ok(())
+    }
+
+    /// A dead player's chat goes to every dead player and the spectators under the `dead_chat`
+    /// rule, and is rejected otherwise, telling the player why
+    fn handle_dead_chat(&mut self, dead: Pidx, text: String) -> Result<(), InvalidActionError<U>> {
+        let player = self.dead[dead].to_owned();
+        if !self.rules.dead_chat {
+            self.comm.send(Event::DeadSilence { player });
+            return Err(InvalidActionError::DeadSilence);
+        }
+        let to = self.dead.clone();
+        let ids: Vec<_> = to.iter().map(|p| p.user_id).collect();
+        self.comm.send_to_dead(
+            &ids,
+            Event::DeadChat {
+                from: player,
+                to,
+                text,
+            },
+        );
+        Ok(())
     }
 
     fn handle_vote(&mut self, v: U, c: Option<Choice<U>>) -> Result<(), InvalidActionError<U>> {
+        if self.rules.night_vote && self.phase.kind() == PhaseKind::Night {
+            return self.handle_night_vote(v, c);
+        }
+        if self.rules.ghost_vote && self.dead.check(v).is_ok() {
+            return self.handle_ghost_vote(v, c);
+        }
         let day = self.phase.is_day()?;
         let voter = self.players.check(v)?;
-        let choice = match c {
-            Some(Choice::Player(p)) => Some(Ballot::Player(self.players.check(p)?)),
-            Some(Choice::Abstain) => Some(Ballot::Abstain),
-            None => None,
-        };
+        if day.excused.contains(&voter) {
+            return Err(InvalidActionError::Excused);
+        }
+        if day.trial.is_some() {
+            return Err(InvalidActionError::TrialUnderway);
+        }
+        let choice = check_ballot(&self.players, c)?;
 
         // accept vote?
-        let day_resolution = day.resolve_vote(&self.players, voter, choice, &self.comm);
+        let day_resolution =
+            day.resolve_vote(&self.players, voter, choice, &self.rules, &self.comm);
+
+        self.handle_election(day_resolution);
+        Ok(())
+    }
+
+    /// A dead player's one vote under the ghost_vote rule. It can't be retracted, and
+    /// it stays spent even if the Day's votes are later cleared.
+    fn handle_ghost_vote(
+        &mut self,
+        v: U,
+        c: Option<Choice<U>>,
+    ) -> Result<(), InvalidActionError<U>> {
+        let day = self.phase.is_day()?;
+        let ghost = self.dead[self.dead.check(v)?].to_owned();
+        if self.ghosts_spent.contains(&v) {
+            return Err(InvalidActionError::GhostVoteFinal);
+        }
+        if day.trial.is_some() {
+            return Err(InvalidActionError::TrialUnderway);
+        }
+        let ballot = check_ballot(&self.players, c)?.ok_or(InvalidActionError::GhostVoteFinal)?;
+        self.ghosts_spent.push(v);
+
+        let day_resolution =
+            day.resolve_ghost_vote(&self.players, ghost, ballot, &self.rules, &self.comm);
+
+        self.handle_election(day_resolution);
+        Ok(())
+    }
+
+    fn handle_night_vote(
+        &mut self,
+        v: U,
+        c: Option<Choice<U>>,
+    ) -> Result<(), InvalidActionError<U>> {
+        let night = self.phase.is_night()?;
+        let voter = self.players.check(v)?;
+        let choice = check_ballot(&self.players, c)?;
+        night.resolve_vote(&self.players, voter, choice, &self.comm);
+        Ok(())
+    }
+
+    fn handle_confirm_hammer(&mut self, v: U) -> Result<(), InvalidActionError<U>> {
+        let day = self.phase.is_day()?;
+        let voter = self.players.check(v)?;
+        if !matches!(day.pending, Some((hammer, _)) if hammer == voter) {
+            return Err(InvalidActionError::NoPendingHammer);
+        }
+        let day_resolution = day.resolve_confirm(&self.players, &self.rules, &self.comm);
+
+        self.handle_election(day_resolution);
+        Ok(())
+    }
+
+    fn handle_verdict(&mut self, j: U, guilty: bool) -> Result<(), InvalidActionError<U>> {
+        let day = self.phase.is_day()?;
+        let juror = self.players.check(j)?;
+        if day.excused.contains(&juror) {
+            return Err(InvalidActionError::Excused);
+        }
+        if !matches!(&day.trial, Some(trial) if trial.defendant != juror) {
+            return Err(InvalidActionError::NoTrial);
+        }
+        let day_resolution =
+            day.resolve_verdict(&self.players, juror, guilty, &self.rules, &self.comm);
+
+        self.handle_election(day_resolution);
+        Ok(())
+    }
+
+    fn handle_skip_day(&mut self, v: U) -> Result<(), InvalidActionError<U>> {
+        let day = self.phase.is_day()?;
+        let voter = self.players.check(v)?;
+        if day.trial.is_some() {
+            return Err(InvalidActionError::TrialUnderway);
+        }
+        let skip = Some(Ballot::Skip);
+        let day_resolution = day.resolve_vote(&self.players, voter, skip, &self.rules, &self.comm);
+
+        self.handle_election(day_resolution);
+        Ok(())
+    }
+
+    /// Called when a scheduled election end time is reached during the Day.
+    pub fn resolve_day_at_deadline(&mut self) -> Result<(), InvalidActionError<U>> {
+        let day = self.phase.is_day()?;
+        let day_resolution = day.resolve_deadline(&self.players, &self.rules, &self.comm);
+
+        self.handle_election(day_resolution);
+        Ok(())
+    }
+
+    /// Called by a moderator to elect one of the choices that tied at the deadline.
+    pub fn resolve_tie(&mut self, choice: Choice<U>) -> Result<(), InvalidActionError<U>> {
+        let day = self.phase.is_day()?;
+        let invalid = InvalidActionError::InvalidChoice {
+            choice,
+            action: ActionKind::Vote,
+        };
+        let ballot = match choice {
+            Choice::Player(p) => Ballot::Player(self.players.check(p)?),
+            Choice::Abstain => Ballot::Abstain,
+            Choice::Pair(..) => return Err(invalid),
+        };
+        let day_resolution = day.resolve_tie(&self.players, ballot, &self.rules, &self.comm);
+        if day_resolution.is_none() {
+            return Err(invalid);
+        }
+
+        self.handle_election(day_resolution);
+        Ok(())
+    }
 
+    fn handle_election(&mut self, day_resolution: Option<DayResolution<U>>) {
         let next_phase: Phase<U> = match day_resolution {
-            Some(DayResolution::Elected(elected, _electors, hammer, next_phase)) => {
+            Some(DayResolution::Elected(elected, electors, hammer, next_phase)) => {
                 self.check_elect_contract(self.players[elected].user_id);
-                self.eliminate(&[elected], hammer).unwrap_or(next_phase)
+                let electors: Vec<_> = electors.into_iter().filter(|e| *e != elected).collect();
+                let day_no = next_phase.number().unwrap_or_default();
+                let actions = self.dusk_actions(elected, &electors);
+                if !actions.is_empty() {
+                    // The elected player isn't eliminated until Dusk ends
+                    Phase::new_dusk(day_no, elected, hammer, actions)
+                } else {
+                    self.eliminate_elected(elected, electors, hammer, next_phase)
+                }
             }
             Some(DayResolution::NoKill(next_phase)) => next_phase,
-            None => return Ok(()),
+            None => return,
         };
 
-        self.phase.next_phase(next_phase, &self.players, &self.comm);
+        self.next_phase(next_phase);
+    }
+
+    /// The dusk actions an election gives out, with the players each actor can pick
+    /// from. Under the Dusk IdiotElect rule, an elected IDIOT picks one of their electors
+    fn dusk_actions(&self, elected: Pidx, electors: &[Pidx]) -> BTreeMap<Pidx, Vec<Pidx>> {
+        let mut actions = BTreeMap::new();
+        if self.players[elected].role == Role::IDIOT
+            && self.rules.idiot_elect == IdiotElect::Dusk
+            && !electors.is_empty()
+        {
+            actions.insert(elected, electors.to_owned());
+        }
+        actions
+    }
+
+    /// Eliminates the elected player, and works out what comes after the election.
+    /// An elected IDIOT takes effect here under every IdiotElect rule but Dusk
+    fn eliminate_elected(
+        &mut self,
+        elected: Pidx,
+        electors: Vec<Pidx>,
+        hammer: Pidx,
+        next_phase: Phase<U>,
+    ) -> Phase<U> {
+        let idiot = self.players[elected].role == Role::IDIOT;
+        let mut deaths = vec![(hammer, elected)];
+        let mut next_phase = next_phase;
+        // Electors are kept by id, as the eliminations below move everyone's Pidx
+        let mut stunned = Vec::new();
+        match self.rules.idiot_elect {
+            IdiotElect::Cull if idiot => deaths.extend(electors.iter().map(|e| (elected, *e))),
+            IdiotElect::Day if idiot => {
+                let day_no = next_phase.number().unwrap_or_default();
+                next_phase = Phase::new_day(day_no + 1, Vec::new());
+            }
+            IdiotElect::Stun if idiot => {
+                stunned = electors.iter().map(|e| self.players[*e].user_id).collect();
+            }
+            _ => {}
+        }
+        if let Some(end) = self.eliminate_all(&deaths) {
+            return end;
+        }
+        if let Phase::Night(night) = &mut next_phase {
+            let stunned = stunned.iter().filter_map(|s| self.players.check(*s).ok());
+            night.stun(&self.players, stunned.collect());
+        }
+        // Under the hammer_grace rule, players get a moment before the Night
+        match (self.rules.hammer_grace, next_phase) {
+            (Some(length), next_phase @ Phase::Night(_)) => {
+                Phase::new_grace(next_phase.number().unwrap_or_default(), length)
+            }
+            (_, next_phase) => next_phase,
+        }
+    }
+
+    /// Called when Dusk's deadline is reached. Anyone who hasn't picked loses their
+    /// dusk action.
+    pub fn resolve_dusk_at_deadline(&mut self) -> Result<(), InvalidActionError<U>> {
+        self.phase.is_dusk()?;
+        self.resolve_dusk();
+        Ok(())
+    }
+
+    /// A player with a dusk action picks one of their options, or Abstains.
+    /// Dusk ends as soon as every dusk action is in
+    fn handle_dusk_target(&mut self, a: U, t: Choice<U>) -> Result<(), InvalidActionError<U>> {
+        let actor = self.players.check(a)?;
+        let role = self.players[actor].role.to_owned();
+        let dusk = self.phase.is_dusk()?;
+        let options = match dusk.actions.get(&actor) {
+            Some(options) => options,
+            None => {
+                return Err(InvalidActionError::InvalidRole {
+                    role,
+                    action: ActionKind::Target,
+                })
+            }
+        };
+        let invalid = InvalidActionError::InvalidChoice {
+            choice: t.to_owned(),
+            action: ActionKind::Target,
+        };
+        let pick = match t {
+            Choice::Player(p) => match self.players.check(p)? {
+                p if options.contains(&p) => Some(p),
+                _ => return Err(invalid),
+            },
+            Choice::Abstain => None,
+            Choice::Pair(..) => return Err(invalid),
+        };
+        dusk.picks.insert(actor, pick);
+        if dusk.complete() {
+            self.resolve_dusk();
+        }
         Ok(())
     }
 
+    /// Eliminates the elected player, along with whoever was picked at Dusk, and
+    /// moves on to the Night
+    fn resolve_dusk(&mut self) {
+        let dusk = match &self.phase {
+            Phase::Dusk(dusk) => dusk.to_owned(),
+            _ => return,
+        };
+        let mut deaths = vec![(dusk.hammer, dusk.elected)];
+        for (actor, pick) in &dusk.picks {
+            deaths.extend(pick.map(|p| (*actor, p)));
+        }
+        let next_phase = self
+            .eliminate_all(&deaths)
+            .unwrap_or(Phase::new_night(dusk.day_no));
+        self.next_phase(next_phase);
+    }
+
     fn check_elect_contract(&mut self, elected: U) {
         for contract in &mut self.contracts {
             if contract.get_charge() == elected {
@@ -167,105 +1908,259 @@ impl<U: RawPID> Game<U> {
         }
 
         if day.blocked.contains(&celeb) {
-            self.comm.tx(Event::Block {
+            self.comm.send(Event::Block {
                 blocked: self.players[celeb].to_owned(),
             });
             return Ok(());
         }
-        self.comm.tx(Event::Reveal {
+        self.comm.send(Event::Reveal {
             celeb: self.players[celeb].to_owned(),
         });
         Ok(())
     }
 
     fn handle_target(&mut self, a: U, t: Choice<U>) -> Result<(), InvalidActionError<U>> {
+        if let Phase::Dusk(_) = self.phase {
+            return self.handle_dusk_target(a, t);
+        }
+        if let (Phase::Day(_) | Phase::Night(_), Ok(actor)) = (&self.phase, self.players.check(a)) {
+            if self.players[actor].role == Role::AVENGER {
+                return self.handle_grudge(actor, t);
+            }
+        }
+        if let Phase::Night(_) = self.phase {
+            self.check_unlocked(a, ActionKind::Target)?;
+        }
         let night = self.phase.is_night()?;
         let actor = self.players.check(a)?;
+        if night.stunned.contains(&actor) {
+            self.comm.send(Event::Block {
+                blocked: self.players[actor].to_owned(),
+            });
+            return Ok(());
+        }
+        self.check_ability(actor, t == Choice::Abstain)?;
+        let role = self.players[actor].role.to_owned();
+        if !role.can_target(&t) {
+            return Err(InvalidActionError::InvalidChoice {
+                choice: t,
+                action: ActionKind::Target,
+            });
+        }
         let target = match t {
             Choice::Player(p) => Choice::Player(self.players.check(p)?),
+            Choice::Pair(a, b) => Choice::Pair(self.players.check(a)?, self.players.check(b)?),
             Choice::Abstain => Choice::Abstain,
         };
+        role.valid_target(actor, &target, &self.players, &self.rules)?;
 
-        let role = self.players[actor].role.to_owned();
+        // Changing a target already made tonight doesn't use the ability again,
+        // and dropping it for Abstain gives the use back
+        let night = self.phase.is_night()?;
+        let retarget = matches!(night.targets.get(&actor), Some(t) if *t != Target::Abstain);
+        if let Some(&limit) = self.rules.ability_limits.get(&role) {
+            let used = self.ability_uses.entry(a).or_insert(0);
+            let changed = match (retarget, target != Choice::Abstain) {
+                (false, true) => {
+                    *used += 1;
+                    true
+                }
+                (true, false) => {
+                    *used = used.saturating_sub(1);
+                    true
+                }
+                _ => false,
+            };
+            if changed {
+                self.comm.send(Event::AbilityUsed {
+                    player: self.players[actor].to_owned(),
+                    remaining: limit - *used,
+                });
+            }
+        }
 
-        let night_resolution = night.resolve_target(&self.players, actor, target, role, &self.comm);
+        let night_resolution = night.resolve_target(
+            &mut self.players,
+            actor,
+            target,
+            role,
+            &self.rules,
+            &self.comm,
+        );
 
         self.handle_dawn(night_resolution);
 
         Ok(())
     }
 
+    /// An AVENGER picks who they take down with them, or drops their pick with Abstain
+    fn handle_grudge(&mut self, actor: Pidx, t: Choice<U>) -> Result<(), InvalidActionError<U>> {
+        let avenger = self.players[actor].to_owned();
+        let target = match t {
+            Choice::Player(p) => Some(self.players.check(p)?),
+            Choice::Abstain => None,
+            Choice::Pair(..) => {
+                return Err(InvalidActionError::InvalidChoice {
+                    choice: t,
+                    action: ActionKind::Target,
+                })
+            }
+        };
+        match target {
+            Some(target) if target == actor => {
+                return Err(InvalidActionError::InvalidTarget { target });
+            }
+            Some(target) => self
+                .grudges
+                .insert(avenger.user_id, self.players[target].user_id),
+            None => self.grudges.remove(&avenger.user_id),
+        };
+        self.comm.send(Event::ActionConfirmed {
+            actor: avenger,
+            target: target.iter().map(|t| self.players[*t].to_owned()).collect(),
+        });
+        Ok(())
+    }
+
     fn handle_mark(&mut self, killer: U, mark: Choice<U>) -> Result<(), InvalidActionError<U>> {
+        if let Phase::Night(_) = self.phase {
+            self.check_unlocked(killer, ActionKind::Mark)?;
+        }
         let night = self.phase.is_night()?;
         let killer = self.players.check(killer)?;
-        let mut mark = match mark {
+        if night.stunned.contains(&killer) {
+            self.comm.send(Event::Block {
+                blocked: self.players[killer].to_owned(),
+            });
+            return Ok(());
+        }
+        let mark = match mark {
             Choice::Player(p) => Choice::Player(self.players.check(p)?),
-            Choice::Abstain => Choice::Abstain,
-        };
-        let role = self.players[killer].role.to_owned();
-
-        match role {
-            Role::GOON => {
-                mark = Choice::Abstain;
-            }
-            _ if role.team() == Team::Mafia => {}
-            _ => {
-                return Err(InvalidActionError::InvalidRole {
-                    role,
+            Choice::Abstain if self.rules.mafia_kill == MafiaKill::Optional => Choice::Abstain,
+            choice @ (Choice::Abstain | Choice::Pair(..)) => {
+                return Err(InvalidActionError::InvalidChoice {
+                    choice,
                     action: ActionKind::Mark,
-                });
+                })
             }
         };
+        let role = self.players[killer].role.to_owned();
+
+        // Any Mafia Aligned player can make the Mafia's kill
+        if role.team() != Team::Mafia {
+            return Err(InvalidActionError::InvalidRole {
+                role,
+                action: ActionKind::Mark,
+            });
+        }
 
-        let night_resolution = night.resolve_mark(&self.players, killer, mark, &self.comm);
+        let night_resolution =
+            night.resolve_mark(&mut self.players, killer, mark, &self.rules, &self.comm);
 
         self.handle_dawn(night_resolution);
 
         Ok(())
     }
 
+    /// Called when the Night's deadline is reached. Anyone who hasn't acted abstains.
+    pub fn resolve_night_at_deadline(&mut self) -> Result<(), InvalidActionError<U>> {
+        let night = self.phase.is_night()?;
+        let night_resolution = night.resolve_deadline(&mut self.players, &self.rules, &self.comm);
+
+        self.handle_dawn(night_resolution);
+        Ok(())
+    }
+
     fn handle_dawn(&mut self, night_resolution: Option<NightResolution<U>>) {
         let next_phase = match night_resolution {
-            Some(NightResolution::Kill(killer, mark, phase)) => {
-                self.eliminate(&[mark], killer).unwrap_or(phase)
+            Some(NightResolution::Kill(kills, phase)) => {
+                self.eliminate_all(&kills).unwrap_or(phase)
             }
-            Some(NightResolution::NoKill(phase)) => phase,
+            // Conversions may have changed the balance of the game
+            Some(NightResolution::NoKill(phase)) => self.check_win().unwrap_or(phase),
             None => return,
         };
+        let next_phase = self.check_max_days(next_phase);
 
-        self.phase.next_phase(next_phase, &self.players, &self.comm);
+        self.next_phase(next_phase);
     }
 
     pub fn eliminate(&mut self, to_die: &[Pidx], proxy: Pidx) -> Option<Phase<U>> {
-        let mut to_die = to_die.to_owned();
-        to_die.sort();
+        let deaths: Vec<_> = to_die.iter().map(|p| (proxy, *p)).collect();
+        self.eliminate_all(&deaths)
+    }
+
+    /// Eliminates every (proxy, player) pair, where the proxy caused the player's death.
+    /// A dying AVENGER adds whoever they picked, and so on, before anyone is removed,
+    /// so the win is only checked once every death is in.
+    fn eliminate_all(&mut self, deaths: &[(Pidx, Pidx)]) -> Option<Phase<U>> {
+        let mut deaths = deaths.to_owned();
+        let mut i = 0;
+        while i < deaths.len() {
+            let (_, p) = deaths[i];
+            i += 1;
+            if self.players[p].role != Role::AVENGER {
+                continue;
+            }
+            let victim = match self.grudges.get(&self.players[p].user_id) {
+                Some(&v) => self.players.check(v).ok(),
+                None => None,
+            };
+            if let Some(v) = victim.filter(|v| deaths.iter().all(|(_, d)| d != v)) {
+                self.comm.send(Event::Avenged {
+                    avenger: self.players[p].to_owned(),
+                    victim: self.players[v].to_owned(),
+                });
+                deaths.push((p, v));
+            }
+        }
+        deaths.sort_by_key(|(_, p)| *p);
+        deaths.dedup_by_key(|(_, p)| *p);
 
-        let mut to_die_ids = Vec::<U>::new();
-        let proxy_id = self.players[proxy].user_id;
+        let death_ids: Vec<(U, U)> = deaths
+            .iter()
+            .map(|(proxy, p)| (self.players[*p].user_id, self.players[*proxy].user_id))
+            .collect();
 
         // Remove from largest to smallest to avoid invalidating indices
-        for p in to_die.into_iter().rev() {
+        for (_, p) in deaths.into_iter().rev() {
+            if let Phase::Day(day) = &mut self.phase {
+                day.forget(p);
+            }
             let player = self.players[p].to_owned();
-            to_die_ids.push(player.user_id);
-            self.comm.tx(Event::Eliminate { player });
+            self.comm.send(Event::Eliminate { player });
 
-            self.players.remove(p);
+            self.dead.push(self.players.remove(p));
         }
         // all Pidxs are now invalid...
         self.phase.clear();
 
         // Check contracts
-        for p_id in to_die_ids {
+        for (p_id, proxy_id) in death_ids {
             self.check_contracts(p_id, proxy_id)
         }
 
-        let winner = check_team_numbers(&self.players);
+        self.check_win()
+    }
+
+    fn check_win(&self) -> Option<Phase<U>> {
+        let winner = check_team_numbers(&self.players)?;
+        Some(self.end(Some(winner)))
+    }
 
-        if let Some(win) = winner {
-            let contract_results: Vec<_> = self.contracts.iter().map(|c| c.check_win()).collect();
-            return Some(Phase::End(win, contract_results));
+    /// Ends the game once a Day past the MaxDays rule would start
+    fn check_max_days(&self, next_phase: Phase<U>) -> Phase<U> {
+        match (&next_phase, self.rules.max_days) {
+            (Phase::Day(day), Some(max_days)) if day.day_no > max_days => {
+                self.end(self.rules.max_days_winner)
+            }
+            _ => next_phase,
         }
-        None
+    }
+
+    fn end(&self, winner: Option<Team>) -> Phase<U> {
+        let contract_results: Vec<_> = self.contracts.iter().map(|c| c.check_win()).collect();
+        Phase::End(winner, contract_results)
     }
 
     fn check_contracts(&mut self, died: U, proxy: U) {
@@ -277,17 +2172,139 @@ impl<U: RawPID> Game<U> {
     }
 }
 
+impl<U: RawPID + DeserializeOwned> Game<U> {
+    /// Loads a game written by `save_game()` in the given format. Its Events are sent
+    /// through the given Comm, and it isn't paused or changed in any other way.
+    pub fn load_game(fname: &str, format: SaveFormat, comm: Comm<U>) -> Result<Self, LoadError> {
+        Self::load_game_with(fname, format, comm, &[])
+    }
+
+    /// Loads a game whose players may have custom roles, giving each the behavior
+    /// with its name. Fails if one of them isn't given
+    pub fn load_game_with(
+        fname: &str,
+        format: SaveFormat,
+        comm: Comm<U>,
+        behaviors: &[Arc<dyn RoleBehavior>],
+    ) -> Result<Self, LoadError> {
+        let bytes = std::fs::read(fname).map_err(|e| LoadError::ReadFailed {
+            reason: e.to_string(),
+        })?;
+        let invalid = |reason: String| LoadError::Invalid { reason };
+
+        let save: Save<Self> = match format {
+            SaveFormat::PrettyJson => {
+                let save: serde_json::Value =
+                    serde_json::from_slice(&bytes).map_err(|e| invalid(e.to_string()))?;
+                let header: SaveHeader =
+                    serde_json::from_value(save.to_owned()).map_err(|e| invalid(e.to_string()))?;
+                let save = match header.version {
+                    SAVE_VERSION => save,
+                    version => migrate(version, save)?,
+                };
+                serde_json::from_value(save).map_err(|e| invalid(e.to_string()))?
+            }
+            SaveFormat::Binary => {
+                // Binary saves don't describe their own layout, so they can't be migrated
+                let header: SaveHeader =
+                    bincode::deserialize(&bytes).map_err(|e| invalid(e.to_string()))?;
+                if header.version != SAVE_VERSION {
+                    return Err(LoadError::IncompatibleVersion {
+                        found: header.version,
+                        expected: SAVE_VERSION,
+                    });
+                }
+                bincode::deserialize(&bytes).map_err(|e| invalid(e.to_string()))?
+            }
+        };
+        let mut game = save.game;
+        let bind = |role: &mut Role| {
+            if let Role::Custom(custom) = role {
+                if !custom.bind(behaviors) {
+                    return Err(LoadError::UnknownRole {
+                        name: custom.name().to_owned(),
+                    });
+                }
+            }
+            Ok(())
+        };
+        for player in game.players.iter_mut().chain(game.dead.iter_mut()) {
+            bind(&mut player.role)?;
+        }
+        // Rules are kept by role, so their roles have to be bound as well
+        let rules = &mut game.rules;
+        rules.ability_limits = std::mem::take(&mut rules.ability_limits)
+            .into_iter()
+            .map(|(mut role, limit)| bind(&mut role).map(|_| (role, limit)))
+            .collect::<Result<_, _>>()?;
+        rules.ability_nights = std::mem::take(&mut rules.ability_nights)
+            .into_iter()
+            .map(|(mut role, nights)| bind(&mut role).map(|_| (role, nights)))
+            .collect::<Result<_, _>>()?;
+        game.check_state()?;
+        let log = game.comm.log();
+        game.comm = comm;
+        game.comm.resume(log);
+        Ok(game)
+    }
+
+    /// Checks that a loaded game's phase makes sense for its players, so a corrupted
+    /// save can't be resumed into a broken state
+    fn check_state(&self) -> Result<(), LoadError> {
+        let inconsistent = |reason: String| Err(LoadError::InconsistentState { reason });
+        // Numbers start at 1, and the phase after this one needs a number too
+        match (self.phase.number(), self.rules.max_days) {
+            (Some(number), _) if number == 0 || number == usize::MAX => {
+                return inconsistent(format!("{} {} is out of range", self.phase.kind(), number))
+            }
+            (Some(number), Some(max_days)) if number > max_days => {
+                return inconsistent(format!(
+                    "{} {} is past the max_days rule of {}",
+                    self.phase.kind(),
+                    number,
+                    max_days
+                ))
+            }
+            _ => {}
+        }
+        match self.phase {
+            Phase::Init if !self.dead.is_empty() => {
+                return inconsistent("players died before the game started".to_string())
+            }
+            Phase::Init | Phase::End(..) => {}
+            _ if self.players.is_empty() => {
+                return inconsistent(format!("no one is alive during {}", self.phase.kind()))
+            }
+            _ => {}
+        }
+        match self
+            .phase
+            .pidxs()
+            .into_iter()
+            .find(|p| *p >= self.players.len())
+        {
+            Some(p) => inconsistent(format!(
+                "{} refers to player {} of {}",
+                self.phase.kind(),
+                p,
+                self.players.len()
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
 fn check_team_numbers<U: RawPID>(players: &Players<U>) -> Option<Team> {
     let n_players = players.len();
-    let n_mafia = players
-        .iter()
-        .filter(|p| p.role.team() == Team::Mafia)
-        .count();
+    let n_team = |team| players.iter().filter(|p| p.role.team() == team).count();
+    let (n_mafia, n_cult) = (n_team(Team::Mafia), n_team(Team::Cult));
 
-    if n_mafia == 0 {
+    if n_mafia == 0 && n_cult == 0 {
         Some(Team::Town)
     } else if n_mafia > (n_players - 1) / 2 {
         Some(Team::Mafia)
+    } else if n_cult > (n_players - 1) / 2 {
+        Some(Team::Cult)
     } else {
         None
     }
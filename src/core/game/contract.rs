@@ -1,23 +1,23 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 
 use super::*;
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize /*Deserialize*/)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ChargeStatus {
     #[default]
     Alive,
     Dead,
 }
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize /*Deserialize*/)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum IdiotStatus {
     #[default]
     Unelected,
     Elected,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize /*Deserialize*/)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Contract<U: RawPID> {
     Protect {
         holder: U,
@@ -33,6 +33,13 @@ pub enum Contract<U: RawPID> {
         holder: U,
         status: IdiotStatus,
     },
+    /// Won by getting the charge elected. If the charge dies any other way, the
+    /// holder refocuses on getting themself Elected
+    Execute {
+        holder: U,
+        charge: U,
+        status: IdiotStatus,
+    },
     Survive {
         holder: U,
         status: ChargeStatus,
@@ -67,6 +74,7 @@ impl<U: RawPID> Contract<U> {
             Contract::Protect { holder, .. } => *holder,
             Contract::Assassinate { holder, .. } => *holder,
             Contract::Elect { holder, .. } => *holder,
+            Contract::Execute { holder, .. } => *holder,
             Contract::Survive { holder, .. } => *holder,
         }
     }
@@ -75,6 +83,7 @@ impl<U: RawPID> Contract<U> {
             Contract::Protect { charge, .. } => *charge,
             Contract::Assassinate { charge, .. } => *charge,
             Contract::Elect { holder, .. } => *holder,
+            Contract::Execute { charge, .. } => *charge,
             Contract::Survive { holder, .. } => *holder,
         }
     }
@@ -96,6 +105,12 @@ impl<U: RawPID> Contract<U> {
             Contract::Elect { .. } => {
                 format!("Your contract is.. to be Elected! 🙃 Win an election! ")
             }
+            Contract::Execute { charge, .. } => {
+                format!(
+                    "Your contract is to Execute. Your charge is {}. Get them elected!",
+                    charge
+                )
+            }
             Contract::Survive { .. } => {
                 format!("Your contract is to Survive. Stay alive!")
             }
@@ -103,7 +118,7 @@ impl<U: RawPID> Contract<U> {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize /*Deserialize*/)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ContractResult<U: RawPID> {
     Success { holder: U },
     Failure { holder: U },
@@ -124,7 +139,7 @@ impl<U: RawPID> Contract<U> {
                     } else {
                         *self = Contract::new(*holder, *holder, false)
                     }
-                    comm.tx(Event::Refocus {
+                    comm.send(Event::Refocus {
                         new_contract: self.clone(),
                     })
                 } else {
@@ -144,7 +159,7 @@ impl<U: RawPID> Contract<U> {
                     } else {
                         *self = Contract::new(*holder, *holder, true)
                     }
-                    comm.tx(Event::Refocus {
+                    comm.send(Event::Refocus {
                         new_contract: self.clone(),
                     })
                 } else {
@@ -152,6 +167,17 @@ impl<U: RawPID> Contract<U> {
                     *status = ChargeStatus::Dead;
                 }
             }
+            Contract::Execute {
+                holder,
+                status: IdiotStatus::Unelected,
+                ..
+            } if players.check(*holder).is_ok() => {
+                // Refocus
+                *self = Contract::new(*holder, *holder, true);
+                comm.send(Event::Refocus {
+                    new_contract: *self,
+                })
+            }
             Contract::Survive { holder, status } => {
                 *status = ChargeStatus::Dead;
             }
@@ -161,7 +187,7 @@ impl<U: RawPID> Contract<U> {
 
     pub fn charge_elected(&mut self, comm: &Comm<U>) {
         match self {
-            Contract::Elect { holder, status } => {
+            Contract::Elect { status, .. } | Contract::Execute { status, .. } => {
                 // Update
                 *status = IdiotStatus::Elected;
             }
@@ -177,7 +203,9 @@ impl<U: RawPID> Contract<U> {
             Contract::Protect { holder, status, .. } if *status == ChargeStatus::Alive => {
                 ContractResult::Success { holder: *holder }
             }
-            Contract::Elect { holder, status } if *status == IdiotStatus::Elected => {
+            Contract::Elect { holder, status } | Contract::Execute { holder, status, .. }
+                if *status == IdiotStatus::Elected =>
+            {
                 ContractResult::Success { holder: *holder }
             }
             Contract::Survive { holder, status } if *status == ChargeStatus::Alive => {
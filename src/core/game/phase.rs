@@ -1,47 +1,122 @@
-use serde::Serialize;
-use std::collections::hash_map::Entry;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::{Debug, Display};
 
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
 use super::*;
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Ballot {
     Player(Pidx),
     Abstain,
+    /// A vote to end the day right away, with no one elected
+    Skip,
 }
 
 impl Ballot {
+    fn to_cast<U: RawPID>(&self, players: &Players<U>) -> CastBallot<U> {
+        match self {
+            Ballot::Player(p) => CastBallot::Player(players[*p].to_owned()),
+            Ballot::Abstain => CastBallot::Abstain,
+            Ballot::Skip => CastBallot::Skip,
+        }
+    }
+
     fn to_p<U: RawPID>(&self, players: &Players<U>) -> Option<Player<U>> {
         match self {
             Ballot::Player(p) => Some(players[*p].clone()),
-            Ballot::Abstain => None,
+            Ballot::Abstain | Ballot::Skip => None,
         }
     }
+
+    /// Number of votes needed for this ballot to be elected
+    pub fn threshold(&self, n_players: usize) -> usize {
+        match self {
+            Ballot::Player(_) => majority(n_players),
+            Ballot::Abstain | Ballot::Skip => (n_players + 1) / 2,
+        }
+    }
+}
+
+/// Number of votes needed to elect a player
+pub fn majority(n_players: usize) -> usize {
+    n_players / 2 + 1
 }
 
 pub type Vote = (Pidx, Ballot);
 pub type Votes = Vec<Vote>;
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Target {
     Strip(Pidx),
     Save(Pidx),
     Investigate(Pidx),
+    Swap(Pidx, Pidx),
+    Convert(Pidx),
+    Shoot(Pidx),
+    Guard(Pidx),
+    /// A custom role's night action
+    Custom(Pidx),
     Abstain,
 }
-pub type Targets = HashMap<Pidx, Target>;
+/// Each actor's night action, kept in turn order so the night resolves the same way every time
+pub type Targets = BTreeMap<Pidx, Target>;
+
+impl Target {
+    /// The player visited by this target, if there is a single one
+    fn visited(&self) -> Option<Pidx> {
+        match self {
+            Target::Strip(p)
+            | Target::Save(p)
+            | Target::Investigate(p)
+            | Target::Convert(p)
+            | Target::Shoot(p)
+            | Target::Guard(p)
+            | Target::Custom(p) => Some(*p),
+            Target::Swap(..) | Target::Abstain => None,
+        }
+    }
+
+    /// Redirects this target if it is either of the swapped players
+    fn swap(&mut self, a: Pidx, b: Pidx) {
+        match self {
+            Target::Strip(p)
+            | Target::Save(p)
+            | Target::Investigate(p)
+            | Target::Convert(p)
+            | Target::Shoot(p)
+            | Target::Guard(p)
+            | Target::Custom(p) => *p = swapped(*p, a, b),
+            Target::Swap(..) | Target::Abstain => {}
+        }
+    }
+}
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+fn swapped(p: Pidx, a: Pidx, b: Pidx) -> Pidx {
+    if p == a {
+        b
+    } else if p == b {
+        a
+    } else {
+        p
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Mark {
     Kill(Pidx, Pidx),
     Abstain,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PhaseKind {
     Init,
     Day,
+    Dusk,
+    Grace,
     Night,
     End,
 }
@@ -51,6 +126,8 @@ impl Display for PhaseKind {
         match self {
             Self::Init => write!(f, "Init"),
             Self::Day => write!(f, "Day"),
+            Self::Dusk => write!(f, "Dusk"),
+            Self::Grace => write!(f, "Grace"),
             Self::Night => write!(f, "Night"),
             Self::End => write!(f, "End"),
         }
@@ -62,21 +139,96 @@ pub enum DayResolution<U: RawPID> {
     NoKill(Phase<U>),
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Day {
     pub day_no: usize,
     pub votes: Votes,
     pub blocked: Vec<Pidx>,
+    /// Ballots that tied at the deadline, waiting for a moderator to choose
+    pub tied: Vec<Ballot>,
+    /// Players a moderator excused from voting, who don't count towards thresholds
+    pub excused: Vec<Pidx>,
+    /// Ballots dead players voted for under the ghost_vote rule
+    pub ghosts: Vec<Ballot>,
+    /// The hammer and the ballot they voted for, when that vote reached the threshold
+    /// under the confirm_hammer rule and is waiting for them to confirm it
+    pub pending: Option<(Pidx, Ballot)>,
+    /// The player on trial under the NominateTrial day style
+    pub trial: Option<Trial>,
+}
+
+/// A player nominated under the NominateTrial day style, waiting on the other
+/// players' verdicts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Trial {
+    pub defendant: Pidx,
+    /// The players whose votes nominated the defendant, the hammer last
+    pub nominators: Vec<Pidx>,
+    /// Each verdict given so far, true for Guilty
+    pub verdicts: Vec<(Pidx, bool)>,
 }
 
 impl Day {
+    /// The number of players who count towards the day's thresholds
+    pub fn voters<U: RawPID>(&self, players: &[Player<U>]) -> usize {
+        players.len() - self.excused.len()
+    }
+
+    /// The votes for a ballot: its living electors, along with any ghost votes
+    fn count(&self, ballot: &Ballot, electors: &[Pidx]) -> usize {
+        electors.len() + self.ghosts.iter().filter(|b| *b == ballot).count()
+    }
+
+    /// Excuses a player from voting, dropping their standing vote
+    pub fn excuse(&mut self, player: Pidx) {
+        self.votes.retain(|(voter, _)| *voter != player);
+        if !self.excused.contains(&player) {
+            self.excused.push(player);
+        }
+    }
+
+    /// Keeps the excused players' Pidxs valid as a player is removed
+    pub fn forget(&mut self, player: Pidx) {
+        self.excused.retain(|e| *e != player);
+        for e in self.excused.iter_mut().filter(|e| **e > player) {
+            *e -= 1;
+        }
+    }
+
+    /// The living players without a standing vote, other than those excused
+    pub fn not_voted<U: RawPID>(&self, players: &[Player<U>]) -> Vec<Pidx> {
+        (0..players.len())
+            .filter(|p| !self.votes.iter().any(|(voter, _)| voter == p))
+            .filter(|p| !self.excused.contains(p))
+            .collect()
+    }
+
     pub fn resolve_vote<U: RawPID>(
         &mut self,
         players: &Vec<Player<U>>,
         voter: Pidx,
         choice: Option<Ballot>,
+        rules: &GameRules,
         comm: &Comm<U>,
     ) -> Option<DayResolution<U>> {
+        // Voting again for the same ballot changes nothing
+        if let Some(ballot) = &choice {
+            if self.votes.contains(&(voter, ballot.clone())) {
+                comm.send(Event::AlreadyVoted {
+                    voter: players[voter].to_owned(),
+                    ballot: ballot.to_p(players),
+                });
+                return None;
+            }
+        }
+
+        // Any new vote breaks a standing tie
+        self.tied.clear();
+        let voter_p = match rules.election_info {
+            ElectionInfo::Public => Some(players[voter].to_owned()),
+            _ => None,
+        };
+
         let former = self
             .votes
             .iter()
@@ -84,85 +236,656 @@ impl Day {
             .map(|i| self.votes.remove(i))
             .map(|(_, b)| b);
 
+        comm.send(Event::VoteCast {
+            voter: players[voter].to_owned(),
+            ballot: choice.as_ref().map(|b| b.to_cast(players)),
+            ghost: false,
+        });
         let ballot = match choice {
             Some(b) => {
                 self.votes.push((voter, b.clone()));
                 b
             }
             None => {
-                comm.tx(Event::Retract {
-                    voter: players[voter].to_owned(),
+                comm.send(Event::Retract {
+                    voter: voter_p,
                     former: former.map(|b| b.to_p(players)),
                 });
+                self.check_pending(players, comm);
                 return None; // Vote retraction can't cause election
             }
         };
 
-        let n_players = players.len();
-        let threshold = match ballot {
-            Ballot::Player(_) => n_players / 2 + 1,
-            _ => (n_players + 1) / 2,
-        };
+        let threshold = ballot.threshold(self.voters(players));
+        let count = self.count(&ballot, &self.electors(&ballot));
+
+        match ballot {
+            Ballot::Skip => comm.send(Event::SkipVote {
+                voter: voter_p,
+                count,
+                threshold,
+            }),
+            _ => comm.send(Event::Vote {
+                voter: voter_p,
+                ballot: ballot.to_p(players),
+                former: former.map(|f| f.to_p(&players)),
+                count: match rules.vote_counts {
+                    VoteCounts::NearThreshold if count + 1 < threshold => None,
+                    _ => Some(count),
+                },
+                threshold,
+            }),
+        }
+        self.check_pending(players, comm);
 
-        let electors = self
+        // Under the confirm_hammer rule, the hammer has to confirm a vote that would
+        // elect a player before anyone is elected
+        let dynamic = rules.election_process == ElectionProcess::Dynamic;
+        if let (Ballot::Player(elected), true) = (&ballot, rules.confirm_hammer && dynamic) {
+            if count >= threshold {
+                // Votes piling on don't take over from the hammer already pending
+                if self.pending.is_none() {
+                    comm.send(Event::HammerPending {
+                        hammer: players[voter].to_owned(),
+                        elected: players[*elected].to_owned(),
+                    });
+                    self.pending = Some((voter, ballot));
+                }
+                return None;
+            }
+        }
+        self.check_election(
+            players,
+            ballot,
+            count >= threshold,
+            Some(voter),
+            rules,
+            comm,
+        )
+    }
+
+    /// Whether the pending hammer still stands: the hammer is still voting for the
+    /// ballot, and it still holds enough votes
+    fn pending_holds<U: RawPID>(&self, players: &[Player<U>]) -> bool {
+        match &self.pending {
+            Some((hammer, ballot)) => {
+                self.votes.contains(&(*hammer, ballot.clone()))
+                    && self.count(ballot, &self.electors(ballot))
+                        >= ballot.threshold(self.voters(players))
+            }
+            None => false,
+        }
+    }
+
+    /// Cancels the pending hammer if a change in the votes means it no longer stands
+    fn check_pending<U: RawPID>(&mut self, players: &Vec<Player<U>>, comm: &Comm<U>) {
+        if self.pending.is_some() && !self.pending_holds(players) {
+            self.cancel_pending(players, comm);
+            self.repend(players, comm);
+        }
+    }
+
+    /// Votes that reach the threshold while a hammer is pending wait on it. Once it is
+    /// cancelled, the latest of them to still hold enough votes becomes pending in its
+    /// place, with its latest voter as the hammer
+    fn repend<U: RawPID>(&mut self, players: &Vec<Player<U>>, comm: &Comm<U>) {
+        let threshold = |ballot: &Ballot| ballot.threshold(self.voters(players));
+        let holds =
+            |ballot: &Ballot| self.count(ballot, &self.electors(ballot)) >= threshold(ballot);
+        let pending = self
             .votes
             .iter()
-            .filter(|(_, b)| b == &ballot)
-            .map(|(v, _)| *v)
-            .collect::<Vec<_>>();
-        let count = electors.len();
+            .rev()
+            .find(|(_, ballot)| matches!(ballot, Ballot::Player(_)) && holds(ballot))
+            .cloned();
+        if let Some((hammer, ballot @ Ballot::Player(elected))) = pending {
+            comm.send(Event::HammerPending {
+                hammer: players[hammer].to_owned(),
+                elected: players[elected].to_owned(),
+            });
+            self.pending = Some((hammer, ballot));
+        }
+    }
 
-        comm.tx(Event::Vote {
-            voter: players[voter].to_owned(),
+    fn cancel_pending<U: RawPID>(&mut self, players: &Vec<Player<U>>, comm: &Comm<U>) {
+        if let Some((hammer, ballot)) = self.pending.take() {
+            comm.send(Event::HammerCancelled {
+                hammer: players[hammer].to_owned(),
+                ballot: ballot.to_p(players),
+            });
+        }
+    }
+
+    /// The hammer confirms their vote under the confirm_hammer rule, electing the
+    /// ballot if it still holds enough votes
+    pub fn resolve_confirm<U: RawPID>(
+        &mut self,
+        players: &Vec<Player<U>>,
+        rules: &GameRules,
+        comm: &Comm<U>,
+    ) -> Option<DayResolution<U>> {
+        if !self.pending_holds(players) {
+            self.cancel_pending(players, comm);
+            self.repend(players, comm);
+            return None;
+        }
+        let (hammer, ballot) = self.pending.take()?;
+        self.check_election(players, ballot, true, Some(hammer), rules, comm)
+    }
+
+    /// Counts a dead player's one ghost vote under the ghost_vote rule, which can
+    /// decide a Dynamic election like any other vote
+    pub fn resolve_ghost_vote<U: RawPID>(
+        &mut self,
+        players: &Vec<Player<U>>,
+        ghost: Player<U>,
+        ballot: Ballot,
+        rules: &GameRules,
+        comm: &Comm<U>,
+    ) -> Option<DayResolution<U>> {
+        // Any new vote breaks a standing tie
+        self.tied.clear();
+        self.ghosts.push(ballot.clone());
+
+        comm.send(Event::VoteCast {
+            voter: ghost.to_owned(),
+            ballot: Some(ballot.to_cast(players)),
+            ghost: true,
+        });
+        let threshold = ballot.threshold(self.voters(players));
+        let count = self.count(&ballot, &self.electors(&ballot));
+        comm.send(Event::GhostVote {
+            ghost: match rules.election_info {
+                ElectionInfo::Public => Some(ghost),
+                _ => None,
+            },
             ballot: ballot.to_p(players),
-            former: former.map(|f| f.to_p(&players)),
             count,
             threshold,
         });
+        // A ghost isn't among the living players, so isn't named as the hammer
+        self.check_election(players, ballot, count >= threshold, None, rules, comm)
+    }
 
-        if count < threshold {
+    /// Elects the ballot just voted for once it reaches its threshold, if the
+    /// election process allows it before the deadline. The hammer is the voter
+    /// whose vote reached the threshold
+    fn check_election<U: RawPID>(
+        &mut self,
+        players: &Vec<Player<U>>,
+        ballot: Ballot,
+        reached: bool,
+        hammer: Option<Pidx>,
+        rules: &GameRules,
+        comm: &Comm<U>,
+    ) -> Option<DayResolution<U>> {
+        // Only a Dynamic election can be decided before the deadline
+        if !reached || rules.election_process != ElectionProcess::Dynamic {
             return None;
         }
-        // Election has occured!
-        let &hammer = electors.last().expect("At least one elector");
 
-        let electors_p: Vec<Player<U>> = electors.iter().map(|e| players[*e].to_owned()).collect();
+        // Under the NominateTrial day style, the player goes on trial instead
+        if let (Ballot::Player(defendant), DayStyle::NominateTrial) = (&ballot, rules.day_style) {
+            let nominators = self.electors(&ballot);
+            comm.send(Event::Trial {
+                defendant: players[*defendant].to_owned(),
+            });
+            self.trial = Some(Trial {
+                defendant: *defendant,
+                nominators,
+                verdicts: Vec::new(),
+            });
+            return None;
+        }
+
+        // Another ballot may already hold enough votes, such as after a Modkill
+        // lowered the threshold, so the hammer ties with it
+        let n_players = self.voters(players);
+        let majorities: Vec<_> = self
+            .tally()
+            .into_iter()
+            .filter(|(b, electors)| self.count(b, electors) >= b.threshold(n_players))
+            .collect();
+        // Whichever wins the tie, no single vote decided it
+        if majorities.len() > 1 {
+            return match self.break_tie(players, majorities, rules, comm) {
+                Some(ballot) => {
+                    let electors = self.electors(&ballot);
+                    Some(self.elect(players, ballot, electors, None, rules.election_info, comm))
+                }
+                None if !self.tied.is_empty() => None,
+                None => Some(self.no_election(players, rules, comm)),
+            };
+        }
+        let electors = self.electors(&ballot);
+        Some(self.elect(players, ballot, electors, hammer, rules.election_info, comm))
+    }
+
+    /// The players who give a verdict at the trial: everyone who can vote, other than
+    /// the defendant
+    fn jurors<U: RawPID>(&self, players: &[Player<U>]) -> Vec<Pidx> {
+        let defendant = self.trial.as_ref().map(|t| t.defendant);
+        (0..players.len())
+            .filter(|p| Some(*p) != defendant && !self.excused.contains(p))
+            .collect()
+    }
 
-        comm.tx(Event::Election {
-            electors: electors_p,
-            ballot: ballot.to_p(&players),
+    /// Takes a juror's verdict at the trial, replacing any they gave before. Once
+    /// every juror has given one, the trial is decided
+    pub fn resolve_verdict<U: RawPID>(
+        &mut self,
+        players: &Vec<Player<U>>,
+        juror: Pidx,
+        guilty: bool,
+        rules: &GameRules,
+        comm: &Comm<U>,
+    ) -> Option<DayResolution<U>> {
+        let jurors = self.jurors(players);
+        let trial = self.trial.as_mut()?;
+        trial.verdicts.retain(|(j, _)| *j != juror);
+        trial.verdicts.push((juror, guilty));
+        comm.send(Event::Verdict {
+            juror: players[juror].to_owned(),
+            guilty,
         });
+        if jurors
+            .iter()
+            .all(|j| trial.verdicts.iter().any(|(v, _)| v == j))
+        {
+            return self.decide_trial(players, rules, comm);
+        }
+        None
+    }
+
+    /// Ends the trial. More Guilty than Innocent verdicts elects the defendant, and
+    /// otherwise they're acquitted, their votes and ghost votes are cleared, and the
+    /// Day goes on
+    fn decide_trial<U: RawPID>(
+        &mut self,
+        players: &Vec<Player<U>>,
+        rules: &GameRules,
+        comm: &Comm<U>,
+    ) -> Option<DayResolution<U>> {
+        let trial = self.trial.take()?;
+        let guilty = trial.verdicts.iter().filter(|(_, g)| *g).count();
+        let innocent = trial.verdicts.len() - guilty;
+        let ballot = Ballot::Player(trial.defendant);
+        if guilty > innocent {
+            let nominators = trial.nominators;
+            return Some(self.elect(players, ballot, nominators, None, rules.election_info, comm));
+        }
+        self.votes.retain(|(_, b)| *b != ballot);
+        self.ghosts.retain(|b| *b != ballot);
+        comm.send(Event::Acquitted {
+            defendant: players[trial.defendant].to_owned(),
+            guilty,
+            innocent,
+        });
+        None
+    }
+
+    /// Resolves the day at a scheduled election end time. If a ballot holds a majority,
+    /// it is elected. Otherwise, the day ends with no election, unless the process
+    /// allows for further scheduled end times, or elects the plurality.
+    pub fn resolve_deadline<U: RawPID>(
+        &mut self,
+        players: &Vec<Player<U>>,
+        rules: &GameRules,
+        comm: &Comm<U>,
+    ) -> Option<DayResolution<U>> {
+        // A hammer that wasn't confirmed in time doesn't count
+        if let Some((hammer, ballot)) = self.pending.to_owned() {
+            self.votes.retain(|vote| *vote != (hammer, ballot.clone()));
+            self.cancel_pending(players, comm);
+        }
+        // A trial is decided on the verdicts given in time
+        if self.trial.is_some() {
+            if let Some(resolution) = self.decide_trial(players, rules, comm) {
+                return Some(resolution);
+            }
+        }
+        let n_players = self.voters(players);
+        let tally = self.tally();
+        // With an even number of players, both Abstain and Skip can hold enough votes
+        let (majorities, tally): (Vec<_>, Vec<_>) = tally
+            .into_iter()
+            .partition(|(b, electors)| self.count(b, electors) >= b.threshold(n_players));
+
+        let elected = match (majorities.len(), rules.deadline_behavior()) {
+            (1, _) => majorities.into_iter().next().map(|(b, _)| b),
+            (0, DeadlineBehavior::CarryOver) => return None,
+            (0, DeadlineBehavior::Plurality) => {
+                let count = |(b, e): &(Ballot, Vec<Pidx>)| self.count(b, e);
+                let most = tally.iter().map(count).max().unwrap_or(0);
+                let leaders: Vec<_> = tally.into_iter().filter(|t| count(t) == most).collect();
+                self.break_tie(players, leaders, rules, comm)
+            }
+            (0, DeadlineBehavior::NoLynch) => None,
+            _ => self.break_tie(players, majorities, rules, comm),
+        };
+        if !self.tied.is_empty() {
+            return None;
+        }
+
+        match elected {
+            Some(ballot) => {
+                let electors = self.electors(&ballot);
+                Some(self.elect(players, ballot, electors, None, rules.election_info, comm))
+            }
+            None => Some(self.no_election(players, rules, comm)),
+        }
+    }
+
+    /// Ends the day without electing anyone
+    fn no_election<U: RawPID>(
+        &self,
+        players: &Vec<Player<U>>,
+        rules: &GameRules,
+        comm: &Comm<U>,
+    ) -> DayResolution<U> {
+        self.reveal_votes(players, rules.election_info, comm);
+        comm.send(Event::Election {
+            electors: Vec::new(),
+            ballot: None,
+            hammer: None,
+            standings: self.standings(players, rules.election_info),
+            threshold: majority(self.voters(players)),
+        });
+        DayResolution::NoKill(Phase::new_night(self.day_no))
+    }
+
+    /// Picks one of the tied ballots using the TieBreak rule, if the rule picks one.
+    /// Under ModChoice, the tied ballots are kept for a moderator to choose from.
+    fn break_tie<U: RawPID>(
+        &mut self,
+        players: &Vec<Player<U>>,
+        tied: Vec<(Ballot, Vec<Pidx>)>,
+        rules: &GameRules,
+        comm: &Comm<U>,
+    ) -> Option<Ballot> {
+        match tied.len() {
+            0 => return None,
+            1 => return tied.into_iter().next().map(|(b, _)| b),
+            _ => {}
+        }
+        comm.send(Event::Tie {
+            tied: tied.iter().map(|(b, _)| b.to_p(players)).collect(),
+        });
+        match rules.tie_break {
+            TieBreak::NoElection => None,
+            TieBreak::Random(seed) => tied
+                .choose(&mut StdRng::seed_from_u64(seed))
+                .map(|(b, _)| b.to_owned()),
+            // Whichever reached its count first has the earliest final vote
+            TieBreak::FirstVoted => tied
+                .into_iter()
+                .min_by_key(|(_, e)| e.iter().map(|v| self.vote_index(*v)).max())
+                .map(|(b, _)| b),
+            TieBreak::ModChoice => {
+                self.tied = tied.into_iter().map(|(b, _)| b).collect();
+                None
+            }
+        }
+    }
+
+    /// Elects one of the ballots that tied at the deadline
+    pub fn resolve_tie<U: RawPID>(
+        &mut self,
+        players: &Vec<Player<U>>,
+        ballot: Ballot,
+        rules: &GameRules,
+        comm: &Comm<U>,
+    ) -> Option<DayResolution<U>> {
+        if !self.tied.contains(&ballot) {
+            return None;
+        }
+        self.tied.clear();
+        let electors = self.electors(&ballot);
+        Some(self.elect(players, ballot, electors, None, rules.election_info, comm))
+    }
+
+    /// Every ballot that has been voted for along with its electors, ordered by
+    /// when each ballot was first voted for
+    pub fn tally(&self) -> Vec<(Ballot, Vec<Pidx>)> {
+        let mut tally: Vec<(Ballot, Vec<Pidx>)> = Vec::new();
+        for (voter, ballot) in &self.votes {
+            match tally.iter_mut().find(|(b, _)| b == ballot) {
+                Some((_, electors)) => electors.push(*voter),
+                None => tally.push((ballot.to_owned(), vec![*voter])),
+            }
+        }
+        // A ballot may only have ghost votes
+        for ballot in &self.ghosts {
+            if !tally.iter().any(|(b, _)| b == ballot) {
+                tally.push((ballot.to_owned(), Vec::new()));
+            }
+        }
+        tally
+    }
+
+    fn vote_index(&self, voter: Pidx) -> usize {
+        self.votes
+            .iter()
+            .position(|(v, _)| *v == voter)
+            .unwrap_or(0)
+    }
+
+    fn electors(&self, ballot: &Ballot) -> Vec<Pidx> {
+        self.votes
+            .iter()
+            .filter(|(_, b)| b == ballot)
+            .map(|(v, _)| *v)
+            .collect()
+    }
+
+    /// Reveals every vote of the day, if the ElectionInfo rule hid them until now.
+    /// Each POLLWATCHER still alive as the day ends is sent every vote regardless of
+    /// the rule, even one about to be elected; one who died during the day learns nothing.
+    fn reveal_votes<U: RawPID>(
+        &self,
+        players: &Vec<Player<U>>,
+        info: ElectionInfo,
+        comm: &Comm<U>,
+    ) {
+        let tally: Vec<_> = self
+            .tally()
+            .into_iter()
+            .map(|(b, electors)| {
+                let electors = electors.iter().map(|e| players[*e].to_owned()).collect();
+                (b.to_p(players), electors)
+            })
+            .collect();
+        if info == ElectionInfo::Revealed {
+            comm.send(Event::VoteTally {
+                tally: tally.clone(),
+            });
+        }
+        for watcher in players.iter().filter(|p| p.role == Role::POLLWATCHER) {
+            comm.send(Event::PollResult {
+                watcher: watcher.to_owned(),
+                tally: tally.clone(),
+            });
+        }
+    }
+
+    /// How many votes each ballot has, in the order they were first voted for.
+    /// Empty if the ElectionInfo rule never reveals votes.
+    fn standings<U: RawPID>(
+        &self,
+        players: &Players<U>,
+        info: ElectionInfo,
+    ) -> Vec<(Option<Player<U>>, usize)> {
+        if info == ElectionInfo::Secret {
+            return Vec::new();
+        }
+        self.tally()
+            .into_iter()
+            .map(|(b, electors)| (b.to_p(players), self.count(&b, &electors)))
+            .collect()
+    }
+
+    /// Elects a ballot. The hammer is the voter whose vote reached the threshold, and
+    /// None when no single vote decided the election, such as at a deadline, a
+    /// tie-break, or a trial
+    fn elect<U: RawPID>(
+        &self,
+        players: &Vec<Player<U>>,
+        ballot: Ballot,
+        electors: Vec<Pidx>,
+        hammer: Option<Pidx>,
+        info: ElectionInfo,
+        comm: &Comm<U>,
+    ) -> DayResolution<U> {
+        // Election has occured!
+        self.reveal_votes(players, info, comm);
+        let (electors_p, hammer_p): (Vec<Player<U>>, _) = match info {
+            ElectionInfo::Public | ElectionInfo::Revealed => (
+                electors.iter().map(|e| players[*e].to_owned()).collect(),
+                hammer.map(|h| players[h].to_owned()),
+            ),
+            ElectionInfo::Count | ElectionInfo::Secret => (Vec::new(), None),
+        };
+
+        match ballot {
+            Ballot::Skip => comm.send(Event::DaySkipped {
+                electors: electors_p,
+            }),
+            _ => comm.send(Event::Election {
+                electors: electors_p,
+                ballot: ballot.to_p(&players),
+                hammer: hammer_p,
+                standings: self.standings(players, info),
+                threshold: majority(self.voters(players)),
+            }),
+        }
 
         let next_phase = Phase::new_night(self.day_no);
         if let Ballot::Player(elected) = ballot {
-            Some(DayResolution::Elected(
-                elected, electors, hammer, next_phase,
-            ))
+            // The last elector is the proxy for the elimination, whether or not their
+            // vote was the hammer. Elected by ghost votes alone, the player is their own
+            let proxy = electors.last().copied().unwrap_or(elected);
+            DayResolution::Elected(elected, electors, proxy, next_phase)
         } else {
-            Some(DayResolution::NoKill(next_phase))
+            DayResolution::NoKill(next_phase)
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Night {
     night_no: usize,
     pub targets: Targets,
     pub scheme: Option<Mark>,
+    /// Votes cast at night under the night_vote rule, separate from the Mafia's kill
+    pub votes: Votes,
+    /// Players who can't act tonight, for voting for an IDIOT under the Stun IdiotElect rule
+    pub stunned: Vec<Pidx>,
 }
 
 pub enum NightResolution<U: RawPID> {
     NoKill(Phase<U>),
-    Kill(Pidx, Pidx, Phase<U>),
+    /// Every (killer, mark) pair where the mark was killed
+    Kill(Vec<(Pidx, Pidx)>, Phase<U>),
 }
 
 impl Night {
-    pub fn resolve_target<U: RawPID>(
+    pub fn number(&self) -> usize {
+        self.night_no
+    }
+
+    /// The players who can use their night action tonight: those with one that
+    /// isn't resting under the ability_nights rule, and who weren't stunned
+    pub fn actors<U: RawPID>(&self, players: &Vec<Player<U>>, rules: &GameRules) -> Vec<Pidx> {
+        let can_act = |(p, player): (Pidx, &Player<U>)| {
+            player.role.targeting()
+                && !rules.on_cooldown(&player.role, self.night_no)
+                && !self.stunned.contains(&p)
+        };
+        get_players_that(players, can_act).map(|(p, _)| p).collect()
+    }
+
+    /// Stops the given players from acting tonight. Their night actions are made
+    /// for them as Abstain, and if no Mafia member can act, neither is the Mark
+    pub fn stun<U: RawPID>(&mut self, players: &Players<U>, stunned: Vec<Pidx>) {
+        for &p in &stunned {
+            if players[p].role.targeting() {
+                self.targets.insert(p, Target::Abstain);
+            }
+        }
+        let mut mafia = get_players_that(players, |(_, p)| p.role.team() == Team::Mafia);
+        if mafia.all(|(p, _)| stunned.contains(&p)) {
+            self.scheme = Some(Mark::Abstain);
+        }
+        self.stunned = stunned;
+    }
+
+    /// Records a night vote. Unlike a Day vote, it is only resolved at dawn
+    pub fn resolve_vote<U: RawPID>(
         &mut self,
         players: &Vec<Player<U>>,
+        voter: Pidx,
+        choice: Option<Ballot>,
+        comm: &Comm<U>,
+    ) {
+        let former = self
+            .votes
+            .iter()
+            .position(|(v, _)| v == &voter)
+            .map(|i| self.votes.remove(i))
+            .map(|(_, b)| b);
+
+        let ballot = match choice {
+            Some(b) => b,
+            None => {
+                comm.send(Event::Retract {
+                    voter: Some(players[voter].to_owned()),
+                    former: former.map(|b| b.to_p(players)),
+                });
+                return;
+            }
+        };
+        self.votes.push((voter, ballot.clone()));
+
+        comm.send(Event::NightVote {
+            voter: players[voter].to_owned(),
+            ballot: ballot.to_p(players),
+            count: self.votes.iter().filter(|(_, b)| b == &ballot).count(),
+            threshold: majority(players.len()),
+        });
+    }
+
+    /// The (hammer, player) pair for a player with a majority of night votes, if any.
+    /// The hammer is the last player to vote for them.
+    fn night_election<U: RawPID>(
+        &self,
+        players: &[Player<U>],
+        comm: &Comm<U>,
+    ) -> Option<(Pidx, Pidx)> {
+        let threshold = majority(players.len());
+        let (elected, electors) = players.iter().enumerate().find_map(|(p, _)| {
+            let electors: Vec<Pidx> = self
+                .votes
+                .iter()
+                .filter(|(_, b)| *b == Ballot::Player(p))
+                .map(|(v, _)| *v)
+                .collect();
+            (electors.len() >= threshold).then_some((p, electors))
+        })?;
+
+        comm.send(Event::NightElection {
+            electors: electors.iter().map(|e| players[*e].to_owned()).collect(),
+            elected: players[elected].to_owned(),
+        });
+        electors.last().map(|hammer| (*hammer, elected))
+    }
+
+    pub fn resolve_target<U: RawPID>(
+        &mut self,
+        players: &mut Vec<Player<U>>,
         actor: Pidx,
         choice: Choice<Pidx>,
         role: Role,
+        rules: &GameRules,
         comm: &Comm<U>,
     ) -> Option<NightResolution<U>> {
         // If actor has already targeted tonight, retract that target.
@@ -171,71 +894,233 @@ impl Night {
                 self.scheme = Some(Mark::Abstain);
             }
         }
-        comm.tx(Event::Target {
+        comm.send(Event::ActionConfirmed {
             actor: players[actor].to_owned(),
-            target: choice.to_p(&players),
+            target: choice.to_ps(players),
         });
 
         let target = match (role, choice) {
             (_, Choice::Abstain) => Target::Abstain,
-            (Role::COP, Choice::Player(p)) => Target::Investigate(p),
+            (Role::COP | Role::ROLECOP, Choice::Player(p)) => Target::Investigate(p),
             (Role::DOCTOR, Choice::Player(p)) => Target::Save(p),
             (Role::STRIPPER, Choice::Player(p)) => Target::Strip(p),
+            (Role::BUSDRIVER, Choice::Pair(a, b)) => Target::Swap(a, b),
+            (Role::CULTLEADER, Choice::Player(p)) => Target::Convert(p),
+            (Role::VIGILANTE, Choice::Player(p)) => Target::Shoot(p),
+            (Role::BODYGUARD, Choice::Player(p)) => Target::Guard(p),
+            (Role::Custom(_), Choice::Player(p)) => Target::Custom(p),
             _ => panic!("Shouldn't be able to target with this role"),
         };
         self.targets.insert(actor, target);
 
-        self.resolve_dawn(players, comm)
+        self.resolve_dawn(players, rules, comm)
     }
 
     pub fn resolve_mark<U: RawPID>(
         &mut self,
-        players: &Vec<Player<U>>,
+        players: &mut Vec<Player<U>>,
         killer: Pidx,
         mark: Choice<Pidx>,
+        rules: &GameRules,
         comm: &Comm<U>,
     ) -> Option<NightResolution<U>> {
         // If killer has already targeted tonight, retract that target.
-        if let Entry::Occupied(mut e) = self.targets.entry(killer) {
-            *e.get_mut() = Target::Abstain;
+        if let Some(target) = self.targets.get_mut(&killer) {
+            *target = Target::Abstain;
         }
 
         self.scheme = match mark {
             Choice::Player(p) => Some(Mark::Kill(killer, p)),
-            Choice::Abstain => Some(Mark::Abstain),
+            Choice::Pair(..) | Choice::Abstain => Some(Mark::Abstain),
         };
 
-        comm.tx(Event::Mark {
-            killer: players[killer].to_owned(),
-            mark: mark.to_p(players),
-        });
-        self.resolve_dawn(players, comm)
+        let mafia = get_players_that(players, |(_, p)| p.role.team() == Team::Mafia);
+        let mafia: Vec<_> = mafia.map(|(_, p)| p.user_id).collect();
+        comm.send_to(
+            &mafia,
+            Event::Mark {
+                killer: players[killer].to_owned(),
+                mark: mark.to_p(players),
+            },
+        );
+        self.resolve_dawn(players, rules, comm)
     }
 
-    pub fn resolve_dawn<U: RawPID>(
+    /// The players with a night action who haven't made it yet
+    pub fn pending_actions<U: RawPID>(&self, players: &Vec<Player<U>>) -> Vec<Pidx> {
+        get_players_that(players, |(_, p)| p.role.targeting())
+            .filter(|(actor, _)| !self.targets.contains_key(actor))
+            .map(|(actor, _)| actor)
+            .collect()
+    }
+
+    /// Resolves the night with an Abstain for every action that hasn't been made
+    pub fn resolve_deadline<U: RawPID>(
         &mut self,
-        players: &Vec<Player<U>>,
+        players: &mut Vec<Player<U>>,
+        rules: &GameRules,
         comm: &Comm<U>,
     ) -> Option<NightResolution<U>> {
-        type T = Targets;
+        for (actor, _) in get_players_that(players, |(_, p)| p.role.targeting()) {
+            self.targets.entry(actor).or_insert(Target::Abstain);
+        }
+        self.scheme.get_or_insert(Mark::Abstain);
+
+        Some(self.dawn(players, rules, comm))
+    }
 
-        let night_action_players = get_players_that(players, |(_, p)| p.role.targeting()).count();
-        let night_actions = self.targets.len();
-        if night_actions < night_action_players || self.scheme.is_none() {
+    /// Resolves the night once every night action is in. The events of a dawn are
+    /// always sent in this order, with ties broken by turn order (player index):
+    /// 1. NightComplete (not sent when the deadline passes), then Dawn
+    /// 2. VisitFizzled, for swaps, then other night actions, then the Mafia's kill
+    /// 3. Block and Strip, for each stripped player, then for a stripped Mafia killer
+    /// 4. Converted
+    /// 5. For the Mafia's kill, then each VIGILANTE shot, then custom attacks: Intercept,
+    ///    Block and Save (as the SaveAgainst rule allows), or Survived
+    /// 6. Investigate, then InvestigateBlocked for each stripped investigator, then
+    ///    CustomResult for custom night actions in order of their night priority
+    /// 7. NightElection, under the night_vote rule
+    /// 8. NoKill if no one was killed or elected, or a Kill for each player killed
+    /// 9. NightReport
+    /// 10. ResolutionTrace, recording steps 2, 3, 5 and 8 in the order they were taken
+    pub fn resolve_dawn<U: RawPID>(
+        &mut self,
+        players: &mut Vec<Player<U>>,
+        rules: &GameRules,
+        comm: &Comm<U>,
+    ) -> Option<NightResolution<U>> {
+        // Counted afresh from the living players every time, so a player who dies
+        // mid-night is never waited on, nor is one whose ability rests tonight
+        let must_act = |(_, p): (Pidx, &Player<U>)| {
+            p.role.targeting() && !rules.on_cooldown(&p.role, self.night_no)
+        };
+        let waiting =
+            get_players_that(players, must_act).any(|(p, _)| !self.targets.contains_key(&p));
+        if waiting || self.scheme.is_none() {
             return None;
         }
 
-        comm.tx(Event::Dawn);
+        // The last night action just came in
+        comm.send(Event::NightComplete);
+        Some(self.dawn(players, rules, comm))
+    }
+
+    /// Tells spectators what every night action was, and how it turned out,
+    /// including the Mafia's kill first
+    fn night_report<U: RawPID>(
+        &self,
+        players: &[Player<U>],
+        outcomes: HashMap<Pidx, ActionOutcome>,
+        mark_outcome: ActionOutcome,
+        comm: &Comm<U>,
+    ) {
+        let mut actions = Vec::new();
+        if let Some(Mark::Kill(killer, mark)) = &self.scheme {
+            actions.push(NightAction {
+                actor: players[*killer].to_owned(),
+                targets: vec![players[*mark].to_owned()],
+                outcome: mark_outcome,
+            });
+        }
+        for (actor, target) in &self.targets {
+            let visited = match target {
+                Target::Swap(a, b) => vec![*a, *b],
+                target => target.visited().into_iter().collect(),
+            };
+            actions.push(NightAction {
+                actor: players[*actor].to_owned(),
+                targets: visited.into_iter().map(|p| players[p].to_owned()).collect(),
+                outcome: outcomes[actor],
+            });
+        }
+        comm.send(Event::NightReport {
+            night_no: self.night_no,
+            actions,
+        });
+    }
+
+    fn dawn<U: RawPID>(
+        &mut self,
+        players: &mut Vec<Player<U>>,
+        rules: &GameRules,
+        comm: &Comm<U>,
+    ) -> NightResolution<U> {
+        type T = Targets;
+
+        comm.send(Event::Dawn);
 
         let targets = self.targets.to_owned();
 
+        // Each step of the resolution, for the ResolutionTrace
+        let mut trace = Vec::new();
+
+        // How each action turned out, for the NightReport
+        let mut outcomes: HashMap<Pidx, ActionOutcome> = targets
+            .iter()
+            .map(|(actor, target)| match target {
+                Target::Abstain => (*actor, ActionOutcome::Abstained),
+                _ => (*actor, ActionOutcome::Targeted),
+            })
+            .collect();
+        let mut mark_outcome = match self.scheme {
+            Some(Mark::Kill(..)) => ActionOutcome::Targeted,
+            _ => ActionOutcome::Abstained,
+        };
+
+        // Take swaps. These resolve first, so a BUSDRIVER can't be stripped
+        let (swaps, mut targets): (T, T) = targets
+            .into_iter()
+            .partition(|(_, t)| matches!(t, Target::Swap(..)));
+
+        // Enact swaps. Swapping an Ascetic player fizzles
+        let mut scheme = self.scheme.to_owned();
+        for (driver, swap) in swaps {
+            if let Target::Swap(a, b) = swap {
+                if let Some(&p) = [a, b].iter().find(|p| players[**p].has(Modifier::Ascetic)) {
+                    fizzle_event(comm, &mut trace, driver, p, players);
+                    outcomes.insert(driver, ActionOutcome::Fizzled);
+                    continue;
+                }
+                trace.push(ResolutionStep::Redirected {
+                    driver: players[driver].to_owned(),
+                    swapped: (players[a].to_owned(), players[b].to_owned()),
+                });
+                for target in targets.values_mut() {
+                    target.swap(a, b);
+                }
+                if let Some(Mark::Kill(_, mark)) = &mut scheme {
+                    *mark = swapped(*mark, a, b);
+                }
+            }
+        }
+
+        // Every visit to an Ascetic player fizzles
+        for (actor, target) in &mut targets {
+            if let Some(p) = target
+                .visited()
+                .filter(|p| players[*p].has(Modifier::Ascetic))
+            {
+                fizzle_event(comm, &mut trace, *actor, p, players);
+                outcomes.insert(*actor, ActionOutcome::Fizzled);
+                *target = Target::Abstain;
+            }
+        }
+        if let Some(Mark::Kill(killer, mark)) = scheme {
+            if players[mark].has(Modifier::Ascetic) {
+                fizzle_event(comm, &mut trace, killer, mark, players);
+                mark_outcome = ActionOutcome::Fizzled;
+                scheme = Some(Mark::Abstain);
+            }
+        }
+
         // Take strips
         let (strips, mut targets): (T, T) = targets
             .into_iter()
             .partition(|(_, t)| matches!(t, Target::Strip(_)));
 
         // Collect Strips
-        let mut block_map = HashMap::new();
+        let mut block_map = BTreeMap::new();
+        let mut blocked_searches = Vec::new();
         for (stripper, target) in strips {
             if let Target::Strip(stripped) = target {
                 // RULE StripNotify Always
@@ -246,17 +1131,49 @@ impl Night {
             }
         }
         for (actor, target) in &mut targets {
-            if let Entry::Occupied(e) = block_map.entry(*actor) {
+            if let Some(strippers) = block_map.get(actor) {
                 match target {
-                    Target::Save(_) | Target::Investigate(_) => {
+                    Target::Save(_)
+                    | Target::Investigate(_)
+                    | Target::Convert(_)
+                    | Target::Shoot(_)
+                    | Target::Guard(_)
+                    | Target::Custom(_) => {
                         // RULE StripNotify Useful
-                        strip_events(&comm, e.get(), *actor, &players);
+                        strip_events(comm, &mut trace, strippers, *actor, players);
+                        outcomes.insert(*actor, ActionOutcome::Blocked);
+                        if matches!(target, Target::Investigate(_)) {
+                            blocked_searches.push(*actor);
+                        }
                         *target = Target::Abstain;
                     }
                     _ => {}
                 }
             }
         }
+        // Stripping whichever Mafia member made the Mark blocks the Mafia's kill
+        if let Some(Mark::Kill(killer, _)) = scheme {
+            if let Some(strippers) = block_map.get(&killer) {
+                if outcomes.get(&killer) != Some(&ActionOutcome::Blocked) {
+                    strip_events(comm, &mut trace, strippers, killer, players);
+                }
+                mark_outcome = ActionOutcome::Blocked;
+                scheme = Some(Mark::Abstain);
+            }
+        }
+
+        // Take guards
+        let (guards, targets): (T, T) = targets
+            .into_iter()
+            .partition(|(_, t)| matches!(t, Target::Guard(_)));
+
+        // Collect guards
+        let mut guard_map: HashMap<Pidx, Vec<Pidx>> = HashMap::new();
+        for (bodyguard, target) in guards {
+            if let Target::Guard(guarded) = target {
+                guard_map.entry(guarded).or_default().push(bodyguard);
+            }
+        }
 
         // Take saves
         let (saves, targets): (T, T) = targets
@@ -268,143 +1185,481 @@ impl Night {
         for (doctor, target) in saves {
             if let Target::Save(saved) = target {
                 // RULE SaveSelf
-                save_map.entry(saved).or_insert_with(Vec::new).push(doctor);
+                if !players[saved].has(Modifier::Macho) {
+                    save_map.entry(saved).or_insert_with(Vec::new).push(doctor);
+                }
             }
         }
 
-        // Take Investigations
-        let (searches, _): (T, T) = targets
+        // Take conversions
+        let (conversions, targets): (T, T) = targets
             .into_iter()
-            .partition(|(_, t)| matches!(t, Target::Investigate(_)));
+            .partition(|(_, t)| matches!(t, Target::Convert(_)));
 
-        // Enact Investigations
-        for (cop, target) in searches {
-            if let Target::Investigate(suspect) = target {
-                let (cop, suspect, role) = (
-                    players[cop].to_owned(),
-                    players[suspect].to_owned(),
-                    players[suspect].role.to_owned(),
-                );
-                comm.tx(Event::Investigate { cop, suspect, role })
+        // Enact conversions
+        for (cult_leader, target) in conversions {
+            if let Target::Convert(convert) = target {
+                if players[convert].role.convertible() {
+                    players[convert].role = Role::CULTIST;
+                    comm.send(Event::Converted {
+                        cult_leader: players[cult_leader].to_owned(),
+                        convert: players[convert].to_owned(),
+                    });
+                }
             }
         }
 
+        // Take custom night actions and VIGILANTE shots
+        let (customs, targets): (T, T) = targets
+            .into_iter()
+            .partition(|(_, t)| matches!(t, Target::Custom(_) | Target::Shoot(_)));
+
+        // Enact them through their RoleBehavior, lowest night priority first. Ties in
+        // priority go in turn order. Their attacks are protected against like any
+        // other, and their results are sent after the investigations
+        let roles: Vec<Role> = players.iter().map(|p| p.role.to_owned()).collect();
+        let mut customs: Vec<_> = customs
+            .into_iter()
+            .filter_map(|(actor, t)| match t {
+                Target::Custom(target) | Target::Shoot(target) => Some((actor, target)),
+                _ => None,
+            })
+            .collect();
+        customs.sort_by_key(|(actor, _)| roles[*actor].behavior().night_priority());
+        let mut custom_attacks = Vec::new();
+        let mut custom_results = Vec::new();
+        for (actor, target) in customs {
+            let mut ctx = RoleContext {
+                actor,
+                target,
+                roles: &roles,
+                attacks: Vec::new(),
+                messages: Vec::new(),
+            };
+            roles[actor].behavior().resolve(&mut ctx);
+            custom_results.extend(ctx.messages.into_iter().map(|text| (actor, text)));
+            custom_attacks.extend(ctx.attacks.into_iter().map(|mark| (actor, mark)));
+        }
+
+        // Take Investigations, the last of the night actions
+        let searches: T = targets
+            .into_iter()
+            .filter(|(_, t)| matches!(t, Target::Investigate(_)))
+            .collect();
+
         let next_phase = Phase::new_day(
             self.night_no + 1,
             block_map.keys().into_iter().copied().collect(),
         );
 
-        // Enact Kill
-        let night_resolution = match self.scheme {
-            Some(Mark::Kill(killer, mark)) => {
-                if let Entry::Occupied(e) = save_map.entry(mark) {
-                    save_events(comm, e.get(), killer, mark, players);
+        // Take Shots. The Mafia's kill comes first, then VIGILANTE shots and custom
+        // attacks in the order they resolved. Every kill lands at once, so a killer
+        // who is killed the same night still makes their kill
+        let mut attempts: Vec<(Pidx, Pidx)> = Vec::new();
+        if let Some(Mark::Kill(killer, mark)) = scheme {
+            attempts.push((killer, mark));
+        }
+        attempts.extend(custom_attacks);
 
-                    NightResolution::NoKill(next_phase)
-                } else {
-                    NightResolution::Kill(killer, mark, next_phase)
+        // Enact Kills. Protection is layered: swaps have already redirected every
+        // attack, then BODYGUARDS intercept, then DOCTORS save whoever would be hit,
+        // then Bulletproof absorbs the attack. Once Bulletproof is used up, it
+        // protects against every other attack that night
+        let mafia_kill = matches!(scheme, Some(Mark::Kill(..)));
+        let mut kills: Vec<(Pidx, Pidx)> = Vec::new();
+        let mut survivors: Vec<Pidx> = Vec::new();
+        for (i, (killer, mark)) in attempts.into_iter().enumerate() {
+            let mark = intercept(comm, &mut trace, &guard_map, mark, players);
+            // A save protects against every kill it covers that night, not just the first
+            let savable = match rules.save_against {
+                SaveAgainst::AnyKill => true,
+                SaveAgainst::MafiaKill => i == 0 && mafia_kill,
+            };
+            let outcome = if let (Some(doctors), true) = (save_map.get(&mark), savable) {
+                save_events(comm, &mut trace, doctors, killer, mark, players);
+                ActionOutcome::Saved
+            } else if survivors.contains(&mark) {
+                trace.push(ResolutionStep::Survived {
+                    killer: players[killer].to_owned(),
+                    victim: players[mark].to_owned(),
+                });
+                ActionOutcome::Saved
+            } else if players[mark].consume(Modifier::Bulletproof) {
+                survivors.push(mark);
+                trace.push(ResolutionStep::Survived {
+                    killer: players[killer].to_owned(),
+                    victim: players[mark].to_owned(),
+                });
+                comm.send(Event::Survived {
+                    victim: players[mark].to_owned(),
+                });
+                ActionOutcome::Saved
+            } else {
+                if kills.iter().all(|(_, m)| *m != mark) {
+                    kills.push((killer, mark));
+                }
+                trace.push(ResolutionStep::Killed {
+                    killer: players[killer].to_owned(),
+                    mark: players[mark].to_owned(),
+                });
+                ActionOutcome::Killed
+            };
+            match i == 0 && mafia_kill {
+                true => mark_outcome = outcome,
+                false => {
+                    outcomes.insert(killer, outcome);
                 }
             }
-            _ => NightResolution::NoKill(next_phase),
-        };
-        match night_resolution {
-            NightResolution::NoKill(_) => {
-                comm.tx(Event::NoKill);
-            }
-            NightResolution::Kill(killer, mark, _) => {
-                let (killer, mark) = (players[killer].to_owned(), players[mark].to_owned());
-                comm.tx(Event::Kill { killer, mark });
+        }
+
+        // Enact Investigations
+        for (cop, target) in searches {
+            if let Target::Investigate(suspect) = target {
+                let (cop, suspect) = (players[cop].to_owned(), players[suspect].to_owned());
+                let result = cop.role.investigation(&suspect.role);
+                comm.send(Event::Investigate {
+                    cop,
+                    suspect,
+                    result,
+                })
             }
         }
-        Some(night_resolution)
+        // A blocked investigation still gets an answer, so it isn't mistaken for no target
+        for cop in blocked_searches {
+            comm.send(Event::InvestigateBlocked {
+                cop: players[cop].to_owned(),
+            });
+        }
+
+        for (actor, text) in custom_results {
+            comm.send(Event::CustomResult {
+                actor: players[actor].to_owned(),
+                text,
+            });
+        }
+
+        // A player elected by night vote dies alongside any kills
+        let elected = self.night_election(players, comm);
+        if kills.is_empty() && elected.is_none() {
+            comm.send(Event::NoKill);
+        }
+        for (killer, mark) in &kills {
+            let (killer, mark) = (players[*killer].to_owned(), players[*mark].to_owned());
+            comm.send(Event::Kill { killer, mark });
+        }
+        self.night_report(players, outcomes, mark_outcome, comm);
+        comm.send(Event::ResolutionTrace {
+            night_no: self.night_no,
+            steps: trace,
+        });
+
+        kills.extend(elected);
+        if kills.is_empty() {
+            return NightResolution::NoKill(next_phase);
+        }
+        NightResolution::Kill(kills, next_phase)
     }
 }
 
 fn strip_events<U: RawPID>(
     comm: &Comm<U>,
+    trace: &mut Vec<ResolutionStep<U>>,
     strippers: &Vec<Pidx>,
     blocked: Pidx,
     players: &Vec<Player<U>>,
 ) {
-    comm.tx(Event::Block {
+    trace.push(ResolutionStep::Blocked {
+        blocked: players[blocked].to_owned(),
+        strippers: strippers.iter().map(|s| players[*s].to_owned()).collect(),
+    });
+    comm.send(Event::Block {
         blocked: players[blocked].to_owned(),
     });
     for stripper in strippers {
-        comm.tx(Event::Strip {
+        comm.send(Event::Strip {
             stripper: players[*stripper].to_owned(),
             blocked: players[blocked].to_owned(),
         });
     }
 }
 
+/// Follows the chain of BODYGUARDS protecting the mark to whoever is actually attacked.
+/// When several BODYGUARDS protect the same player, the first in turn order steps in.
+fn intercept<U: RawPID>(
+    comm: &Comm<U>,
+    trace: &mut Vec<ResolutionStep<U>>,
+    guard_map: &HashMap<Pidx, Vec<Pidx>>,
+    mark: Pidx,
+    players: &Vec<Player<U>>,
+) -> Pidx {
+    let mut chain = vec![mark];
+    let mut victim = mark;
+    while let Some(&bodyguard) = guard_map.get(&victim).and_then(|g| g.iter().min()) {
+        if chain.contains(&bodyguard) {
+            break;
+        }
+        trace.push(ResolutionStep::Intercepted {
+            bodyguard: players[bodyguard].to_owned(),
+            guarded: players[victim].to_owned(),
+        });
+        comm.send(Event::Intercept {
+            bodyguard: players[bodyguard].to_owned(),
+            guarded: players[victim].to_owned(),
+        });
+        chain.push(bodyguard);
+        victim = bodyguard;
+    }
+    victim
+}
+
+fn fizzle_event<U: RawPID>(
+    comm: &Comm<U>,
+    trace: &mut Vec<ResolutionStep<U>>,
+    actor: Pidx,
+    target: Pidx,
+    players: &Vec<Player<U>>,
+) {
+    trace.push(ResolutionStep::Fizzled {
+        actor: players[actor].to_owned(),
+        target: players[target].to_owned(),
+    });
+    comm.send(Event::VisitFizzled {
+        actor: players[actor].to_owned(),
+        target: players[target].to_owned(),
+    });
+}
+
 fn save_events<U: RawPID>(
     comm: &Comm<U>,
+    trace: &mut Vec<ResolutionStep<U>>,
     doctors: &Vec<Pidx>,
     killer: Pidx,
     saved: Pidx,
     players: &Vec<Player<U>>,
 ) {
-    comm.tx(Event::Block {
+    trace.push(ResolutionStep::Saved {
+        killer: players[killer].to_owned(),
+        saved: players[saved].to_owned(),
+        doctors: doctors.iter().map(|d| players[*d].to_owned()).collect(),
+    });
+    comm.send(Event::Block {
         blocked: players[killer].to_owned(),
     });
     for doctor in doctors {
-        comm.tx(Event::Save {
+        comm.send(Event::Save {
             doctor: players[*doctor].to_owned(),
             saved: players[saved].to_owned(),
         });
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize /*Deserialize*/)]
+/// Between the Day and the Night, after an election that gives someone a dusk
+/// action, such as an IDIOT under the Dusk IdiotElect rule. Dusk ends once every
+/// dusk action is in, or at its deadline, and the Night begins.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Dusk {
+    pub day_no: usize,
+    /// The player elected at the end of the Day, who is eliminated as Dusk ends
+    pub elected: Pidx,
+    /// The final vote for the elected player, who caused their death
+    pub hammer: Pidx,
+    /// Each player with a dusk action, and the players they can pick from
+    pub actions: BTreeMap<Pidx, Vec<Pidx>>,
+    /// Who each of them picked, or None if they abstained
+    pub picks: BTreeMap<Pidx, Option<Pidx>>,
+}
+
+impl Dusk {
+    /// Whether every dusk action is in
+    pub fn complete(&self) -> bool {
+        self.actions
+            .keys()
+            .all(|actor| self.picks.contains_key(actor))
+    }
+}
+
+/// A pause between an election and the Night under the hammer_grace rule, so
+/// players can react before the Night begins
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Grace {
+    pub day_no: usize,
+    /// How long until the Night begins
+    pub length: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Phase<U: RawPID> {
     Init,
     Day(Day),
+    Dusk(Dusk),
+    Grace(Grace),
     Night(Night),
-    End(Team, Vec<ContractResult<U>>),
+    /// The winning team, or None for a draw
+    End(Option<Team>, Vec<ContractResult<U>>),
 }
 
 impl<U: RawPID> Phase<U> {
     pub fn clear(&mut self) {
         match self {
-            Phase::Day(Day { votes, .. }) => votes.clear(),
+            Phase::Day(Day {
+                votes,
+                tied,
+                ghosts,
+                pending,
+                trial,
+                ..
+            }) => {
+                votes.clear();
+                tied.clear();
+                ghosts.clear();
+                *pending = None;
+                *trial = None;
+            }
             Phase::Night(Night {
-                targets, scheme, ..
+                targets,
+                scheme,
+                votes,
+                stunned,
+                ..
             }) => {
                 targets.clear();
                 *scheme = None;
+                votes.clear();
+                stunned.clear();
             }
             _ => {}
         }
     }
+    /// Every player the phase refers to by index
+    pub fn pidxs(&self) -> Vec<Pidx> {
+        let player = |b: &Ballot| match b {
+            Ballot::Player(p) => Some(*p),
+            Ballot::Abstain | Ballot::Skip => None,
+        };
+        let votes = |votes: &Votes| {
+            votes
+                .iter()
+                .flat_map(|(voter, b)| std::iter::once(*voter).chain(player(b)))
+                .collect::<Vec<_>>()
+        };
+        let mut pidxs = Vec::new();
+        match self {
+            Phase::Day(day) => {
+                pidxs.extend(votes(&day.votes));
+                pidxs.extend(&day.blocked);
+                pidxs.extend(&day.excused);
+                pidxs.extend(day.tied.iter().chain(&day.ghosts).filter_map(player));
+                if let Some((hammer, b)) = &day.pending {
+                    pidxs.push(*hammer);
+                    pidxs.extend(player(b));
+                }
+                if let Some(trial) = &day.trial {
+                    pidxs.push(trial.defendant);
+                    pidxs.extend(&trial.nominators);
+                    pidxs.extend(trial.verdicts.iter().map(|(juror, _)| *juror));
+                }
+            }
+            Phase::Dusk(dusk) => {
+                pidxs.extend([dusk.elected, dusk.hammer]);
+                for (actor, options) in &dusk.actions {
+                    pidxs.push(*actor);
+                    pidxs.extend(options);
+                }
+                pidxs.extend(dusk.picks.values().flatten());
+            }
+            Phase::Night(night) => {
+                for (actor, target) in &night.targets {
+                    pidxs.push(*actor);
+                    match target {
+                        Target::Swap(a, b) => pidxs.extend([*a, *b]),
+                        target => pidxs.extend(target.visited()),
+                    }
+                }
+                if let Some(Mark::Kill(killer, mark)) = night.scheme {
+                    pidxs.extend([killer, mark]);
+                }
+                pidxs.extend(votes(&night.votes));
+                pidxs.extend(&night.stunned);
+            }
+            Phase::Init | Phase::Grace(_) | Phase::End(..) => {}
+        }
+        pidxs
+    }
     pub fn new_day(day_no: usize, blocked: Vec<Pidx>) -> Self {
         Self::Day(Day {
             day_no,
             votes: Vec::new(),
             blocked,
+            tied: Vec::new(),
+            excused: Vec::new(),
+            ghosts: Vec::new(),
+            pending: None,
+            trial: None,
+        })
+    }
+    pub fn new_dusk(
+        day_no: usize,
+        elected: Pidx,
+        hammer: Pidx,
+        actions: BTreeMap<Pidx, Vec<Pidx>>,
+    ) -> Self {
+        Self::Dusk(Dusk {
+            day_no,
+            elected,
+            hammer,
+            actions,
+            picks: BTreeMap::new(),
         })
     }
+    pub fn new_grace(day_no: usize, length: Duration) -> Self {
+        Self::Grace(Grace { day_no, length })
+    }
     pub fn new_night(night_no: usize) -> Self {
         Self::Night(Night {
             night_no,
-            targets: HashMap::new(),
+            targets: BTreeMap::new(),
             scheme: None,
+            votes: Vec::new(),
+            stunned: Vec::new(),
         })
     }
     pub fn kind(&self) -> PhaseKind {
         match self {
             Phase::Init => PhaseKind::Init,
             Phase::Day { .. } => PhaseKind::Day,
+            Phase::Dusk { .. } => PhaseKind::Dusk,
+            Phase::Grace { .. } => PhaseKind::Grace,
             Phase::Night { .. } => PhaseKind::Night,
             Phase::End(..) => PhaseKind::End,
         }
     }
+    /// The Day or Night number, if the phase has one. Dusk and Grace share their Day's number.
+    pub fn number(&self) -> Option<usize> {
+        match self {
+            Phase::Day(day) => Some(day.day_no),
+            Phase::Dusk(dusk) => Some(dusk.day_no),
+            Phase::Grace(grace) => Some(grace.day_no),
+            Phase::Night(night) => Some(night.night_no),
+            Phase::Init | Phase::End(..) => None,
+        }
+    }
     pub fn is_day(&mut self) -> Result<&mut Day, InvalidActionError<U>> {
         if let Phase::Day(day) = self {
             Ok(day)
         } else {
             Err(InvalidActionError::InvalidPhase {
                 expected: PhaseKind::Day,
-                found: self.to_owned(),
+                found: Box::new(self.to_owned()),
+            })
+        }
+    }
+    pub fn is_dusk(&mut self) -> Result<&mut Dusk, InvalidActionError<U>> {
+        if let Phase::Dusk(dusk) = self {
+            Ok(dusk)
+        } else {
+            Err(InvalidActionError::InvalidPhase {
+                expected: PhaseKind::Dusk,
+                found: Box::new(self.to_owned()),
             })
         }
     }
@@ -414,27 +1669,65 @@ impl<U: RawPID> Phase<U> {
         } else {
             Err(InvalidActionError::InvalidPhase {
                 expected: PhaseKind::Night,
-                found: self.to_owned(),
+                found: Box::new(self.to_owned()),
             })
         }
     }
 
-    pub fn next_phase(&mut self, next_phase: Phase<U>, players: &Vec<Player<U>>, comm: &Comm<U>) {
+    pub fn next_phase(
+        &mut self,
+        next_phase: Phase<U>,
+        players: &Vec<Player<U>>,
+        rules: &GameRules,
+        comm: &Comm<U>,
+    ) {
         *self = next_phase;
 
         match self {
-            Phase::Day(Day { day_no, .. }) => comm.tx(Event::Day {
-                day_no: *day_no,
-                players: players.clone(),
-            }),
-            Phase::Night(Night { night_no, .. }) => comm.tx(Event::Night {
-                night_no: *night_no,
-                players: players.clone(),
-            }),
-            Phase::End(winner, contract_results) => comm.tx(Event::End {
-                winner: *winner,
-                contract_results: contract_results.to_owned(),
-            }),
+            Phase::Day(Day { day_no, .. }) => {
+                comm.send(Event::Day {
+                    day_no: *day_no,
+                    players: players.clone(),
+                });
+                comm.send(Event::DayStart {
+                    day_no: *day_no,
+                    alive: players.clone(),
+                    threshold: majority(players.len()),
+                });
+            }
+            Phase::Night(night) => {
+                comm.send(Event::Night {
+                    night_no: night.night_no,
+                    players: players.clone(),
+                });
+                // Privately prompt each player who can use their night action tonight
+                for actor in night.actors(players, rules) {
+                    comm.send(Event::NightStart {
+                        night_no: night.night_no,
+                        actor: players[actor].to_owned(),
+                    });
+                }
+            }
+            Phase::Dusk(Dusk { actions, .. }) => {
+                for (actor, options) in actions.iter() {
+                    comm.send(Event::Dusk {
+                        actor: players[*actor].to_owned(),
+                        options: options.iter().map(|o| players[*o].to_owned()).collect(),
+                    });
+                }
+            }
+            Phase::Grace(Grace { length, .. }) => {
+                comm.send(Event::HammerGrace { remaining: *length });
+            }
+            Phase::End(winner, contract_results) => {
+                comm.send(Event::End {
+                    winner: *winner,
+                    contract_results: contract_results.to_owned(),
+                });
+                comm.send(Event::GameStats {
+                    stats: comm.log().stats.sorted(),
+                });
+            }
             _ => panic!("Should never go to Init Phase!"),
         }
     }
@@ -447,20 +1740,37 @@ impl<U: RawPID> Display for Phase<U> {
                 day_no,
                 votes,
                 blocked,
+                ..
             }) => write!(
                 f,
                 "Day {} (votes: {:?}, blocked: {:?})",
                 day_no, votes, blocked
             ),
+            Phase::Dusk(Dusk {
+                day_no,
+                elected,
+                actions,
+                picks,
+                ..
+            }) => write!(
+                f,
+                "Dusk {} (elected: {}, actions: {:?}, picks: {:?})",
+                day_no, elected, actions, picks
+            ),
+            Phase::Grace(Grace { day_no, length }) => {
+                write!(f, "Grace {} ({:?})", day_no, length)
+            }
             Phase::Night(Night {
                 night_no,
                 targets,
                 scheme,
+                votes,
+                ..
             }) => {
                 write!(
                     f,
-                    "Night {} (targets: {:?}, scheme: {:?})",
-                    night_no, targets, scheme
+                    "Night {} (targets: {:?}, scheme: {:?}, votes: {:?})",
+                    night_no, targets, scheme, votes
                 )
             }
             Phase::End(winner, contracts) => {
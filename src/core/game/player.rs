@@ -1,17 +1,24 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::fmt::{Debug, Display};
+use std::hash::Hash;
 
-use super::roles::{Role, Team};
+use super::roles::{Modifier, Role, Team};
 
-pub trait RawPID: Debug + Display + Clone + Copy + PartialEq + Eq + Send + Serialize {}
+pub trait RawPID:
+    Debug + Display + Clone + Copy + PartialEq + Eq + Hash + Send + Serialize + 'static
+{
+}
 
 pub type Pidx = usize;
 impl RawPID for Pidx {}
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize /*Deserialize*/)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Player<U: RawPID> {
     pub user_id: U,
     pub role: Role,
+    pub modifiers: Vec<Modifier>,
+    /// The name other players know this player by, if there is one
+    pub name: Option<String>,
 }
 
 impl<U: RawPID> Player<U> {
@@ -19,8 +26,31 @@ impl<U: RawPID> Player<U> {
         Self {
             user_id: raw_pid,
             role,
+            modifiers: Vec::new(),
+            name: None,
         }
     }
+
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub fn with_modifiers(mut self, modifiers: Vec<Modifier>) -> Self {
+        self.modifiers = modifiers;
+        self
+    }
+
+    pub fn has(&self, modifier: Modifier) -> bool {
+        self.modifiers.contains(&modifier)
+    }
+
+    /// Removes a modifier that has been used up. Returns whether the player had it
+    pub fn consume(&mut self, modifier: Modifier) -> bool {
+        let had = self.has(modifier);
+        self.modifiers.retain(|m| *m != modifier);
+        had
+    }
 }
 impl<U: RawPID> Display for Player<U> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -28,9 +58,10 @@ impl<U: RawPID> Display for Player<U> {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize /*Deserialize*/)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Choice<U: RawPID> {
     Player(U),
+    Pair(U, U),
     Abstain,
 }
 
@@ -63,7 +94,14 @@ impl Choice<Pidx> {
     pub fn to_p<U: RawPID>(&self, players: &Vec<Player<U>>) -> Option<Player<U>> {
         match self {
             Choice::Player(p) => Some(players[*p].clone()),
-            Choice::Abstain => None,
+            Choice::Pair(..) | Choice::Abstain => None,
+        }
+    }
+    pub fn to_ps<U: RawPID>(&self, players: &Vec<Player<U>>) -> Vec<Player<U>> {
+        match self {
+            Choice::Player(p) => vec![players[*p].clone()],
+            Choice::Pair(a, b) => vec![players[*a].clone(), players[*b].clone()],
+            Choice::Abstain => Vec::new(),
         }
     }
 }
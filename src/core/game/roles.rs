@@ -1,11 +1,17 @@
-use std::fmt::Display;
+use std::fmt::{Debug, Display};
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 
-use serde::Serialize;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize /*Deserialize*/)]
+use super::player::{Choice, Pidx, Player, RawPID};
+use crate::core::{GameRules, InvalidActionError, SaveSelf};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Role {
     TOWN,
     COP,
+    ROLECOP,
     DOCTOR,
     CELEB,
     MILLER,
@@ -13,28 +19,208 @@ pub enum Role {
     MAFIA,
     GODFATHER,
     STRIPPER,
+    BUSDRIVER,
+    VIGILANTE,
+    BODYGUARD,
     GOON,
     IDIOT,
     SURVIVOR,
     GUARD,
     AGENT,
+    CULTLEADER,
+    CULTIST,
+    POLLWATCHER,
+    AVENGER,
+    /// A player who has joined but hasn't been given a role yet. A game can't
+    /// start while any player is UNASSIGNED
+    UNASSIGNED,
+    /// A role added without editing `Role`, made with `Role::custom`
+    Custom(CustomRole),
+}
+
+/// How a role behaves, so that roles can be added without editing `Role`. A custom
+/// role is given to players as `Role::custom(behavior)`. The built-in roles implement
+/// it too: their night priorities decide which of them have a night action, and
+/// order the VIGILANTE's shots among the custom night actions.
+pub trait RoleBehavior: Debug + Send + Sync {
+    fn name(&self) -> String;
+    fn team(&self) -> Team;
+    fn description(&self) -> &'static str;
+    /// The step of the dawn where this role's night action resolves. No night action
+    /// if None. Custom night actions resolve after conversions, along with the
+    /// VIGILANTE's shots, lowest first
+    fn night_priority(&self) -> Option<usize>;
+    /// Resolves this role's night action on its target
+    fn resolve(&self, ctx: &mut RoleContext);
+}
+
+/// A custom role and its behavior. Saves only keep its name, so `Game::load_game_with`
+/// gives it its behavior again. Roles are equal when their names are
+#[derive(Clone)]
+pub struct CustomRole {
+    name: String,
+    behavior: Option<Arc<dyn RoleBehavior>>,
+}
+
+impl CustomRole {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether the role has its behavior, which it only lacks when loaded without it
+    pub fn bound(&self) -> bool {
+        self.behavior.is_some()
+    }
+
+    /// Gives the role the behavior with its name, if there is one
+    pub fn bind(&mut self, behaviors: &[Arc<dyn RoleBehavior>]) -> bool {
+        if let Some(behavior) = behaviors.iter().find(|b| b.name() == self.name) {
+            self.behavior = Some(behavior.to_owned());
+        }
+        self.bound()
+    }
+}
+
+impl Debug for CustomRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Custom({:?})", self.name)
+    }
+}
+
+impl PartialEq for CustomRole {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+impl Eq for CustomRole {}
+
+impl Hash for CustomRole {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize /*Deserialize*/)]
+impl Serialize for CustomRole {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.name)
+    }
+}
+
+impl<'de> Deserialize<'de> for CustomRole {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(CustomRole {
+            name: String::deserialize(deserializer)?,
+            behavior: None,
+        })
+    }
+}
+
+/// What a custom role's night action can see, and what it does
+#[derive(Debug)]
+pub struct RoleContext<'a> {
+    pub actor: Pidx,
+    pub target: Pidx,
+    /// Every living player's role, by player index
+    pub roles: &'a [Role],
+    /// Players attacked, each resolving like a VIGILANTE's shot
+    pub attacks: Vec<Pidx>,
+    /// What the actor is told, each sent to them as a CustomResult
+    pub messages: Vec<String>,
+}
+
+impl RoleContext<'_> {
+    pub fn attack(&mut self, player: Pidx) {
+        self.attacks.push(player);
+    }
+    pub fn tell(&mut self, text: impl Into<String>) {
+        self.messages.push(text.into());
+    }
+}
+
+/// What a COP or ROLECOP learns about a suspect
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InvestigationResult {
+    /// A COP learns whether the suspect appears Mafia Aligned
+    Guilty(bool),
+    /// A ROLECOP learns the suspect's exact role
+    Role(Role),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Team {
     Town,
     Mafia,
     Rogue,
+    Cult,
+}
+
+/// Modifies a player's role, changing how night actions affect them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Modifier {
+    /// Survives the first kill against them
+    Bulletproof,
+    /// Can't be saved by a DOCTOR
+    Macho,
+    /// Every night action that visits them has no effect
+    Ascetic,
+}
+
+impl Team {
+    /// Whether members of this team can talk privately with each other
+    pub fn has_chat(&self) -> bool {
+        matches!(self, Team::Mafia | Team::Cult)
+    }
 }
+
 impl Role {
+    /// A custom role, known by the name of its behavior
+    pub fn custom(behavior: impl RoleBehavior + 'static) -> Role {
+        Role::Custom(CustomRole {
+            name: behavior.name(),
+            behavior: Some(Arc::new(behavior)),
+        })
+    }
+
+    /// How this role behaves: the behavior of a custom role, and the role itself otherwise
+    pub fn behavior(&self) -> Arc<dyn RoleBehavior> {
+        match self.custom_behavior() {
+            Some(behavior) => behavior,
+            None => Arc::new(self.to_owned()),
+        }
+    }
+
+    fn custom_behavior(&self) -> Option<Arc<dyn RoleBehavior>> {
+        match self {
+            Role::Custom(custom) => custom.behavior.to_owned(),
+            _ => None,
+        }
+    }
+
     pub fn team(&self) -> Team {
         match self {
-            Role::TOWN | Role::COP | Role::DOCTOR | Role::CELEB => Team::Town,
-            Role::MILLER | Role::MASON => Team::Town,
+            Role::TOWN | Role::COP | Role::ROLECOP | Role::DOCTOR | Role::CELEB => Team::Town,
+            Role::MILLER | Role::MASON | Role::BUSDRIVER => Team::Town,
+            Role::VIGILANTE | Role::BODYGUARD | Role::POLLWATCHER | Role::AVENGER => Team::Town,
             Role::MAFIA | Role::GODFATHER | Role::GOON | Role::STRIPPER => Team::Mafia,
             Role::IDIOT | Role::SURVIVOR | Role::GUARD | Role::AGENT => Team::Rogue,
+            Role::CULTLEADER | Role::CULTIST => Team::Cult,
+            Role::UNASSIGNED => Team::Town,
+            // A custom role without its behavior is rejected by load_game_with and
+            // validate_setup, so it never plays as Town
+            Role::Custom(_) => self.custom_behavior().map_or(Team::Town, |b| b.team()),
         }
     }
+
+    /// Whether at most one player in a game can have this role
+    pub fn unique(&self) -> bool {
+        matches!(self, Role::GODFATHER | Role::CULTLEADER)
+    }
+
+    /// Whether a CULTLEADER can convert a player with this role.
+    /// Town power roles, Mafia, and Rogues resist conversion.
+    pub fn convertible(&self) -> bool {
+        matches!(self, Role::TOWN | Role::MILLER | Role::MASON)
+    }
     pub fn investigate(&self) -> Team {
         match self {
             Role::GODFATHER => Team::Town,
@@ -43,6 +229,14 @@ impl Role {
         }
     }
 
+    /// What this role learns by investigating a suspect with the given role
+    pub fn investigation(&self, suspect: &Role) -> InvestigationResult {
+        match self {
+            Role::ROLECOP => InvestigationResult::Role(suspect.to_owned()),
+            _ => InvestigationResult::Guilty(suspect.investigate_mafia()),
+        }
+    }
+
     pub fn investigate_mafia(&self) -> bool {
         match self {
             Role::GODFATHER => false,
@@ -51,8 +245,54 @@ impl Role {
         }
     }
 
+    /// Whether this role has its own night action. Vanilla roles like TOWN, MAFIA,
+    /// and GOON don't, so they are never waited on as night actors. The Mafia's
+    /// kill is a team action, made with a Mark rather than a Target.
     pub fn targeting(&self) -> bool {
-        matches!(self, Role::COP | Role::DOCTOR | Role::STRIPPER)
+        RoleBehavior::night_priority(self).is_some()
+    }
+
+    /// Whether this role's night action can be made with the given choice
+    pub fn can_target<U: RawPID>(&self, choice: &Choice<U>) -> bool {
+        match (self, choice) {
+            (_, Choice::Abstain) => self.targeting(),
+            (Role::BUSDRIVER, Choice::Pair(..)) => true,
+            (Role::BUSDRIVER, Choice::Player(_)) => false,
+            (_, Choice::Player(_)) => self.targeting(),
+            (_, Choice::Pair(..)) => false,
+        }
+    }
+
+    /// Checks the constraints this role puts on who it can target
+    pub fn valid_target<U: RawPID>(
+        &self,
+        actor: Pidx,
+        choice: &Choice<Pidx>,
+        players: &[Player<U>],
+        rules: &GameRules,
+    ) -> Result<(), InvalidActionError<U>> {
+        let invalid = |target| Err(InvalidActionError::InvalidTarget { target });
+        match (self, *choice) {
+            (_, Choice::Abstain) => Ok(()),
+            (Role::BUSDRIVER, Choice::Pair(a, b)) if a == b => invalid(b),
+            (Role::DOCTOR, Choice::Player(p)) if p == actor => match rules.save_self {
+                SaveSelf::Always | SaveSelf::Stun => Ok(()),
+                SaveSelf::Never => invalid(p),
+            },
+            (
+                Role::COP
+                | Role::ROLECOP
+                | Role::STRIPPER
+                | Role::VIGILANTE
+                | Role::BODYGUARD
+                | Role::CULTLEADER,
+                Choice::Player(p),
+            ) if p == actor => invalid(p),
+            (Role::CULTLEADER, Choice::Player(p)) if players[p].role.team() == Team::Cult => {
+                invalid(p)
+            }
+            _ => Ok(()),
+        }
     }
 }
 
@@ -61,6 +301,7 @@ impl Display for Role {
         match self {
             Role::TOWN => write!(f, "TOWN"),
             Role::COP => write!(f, "COP"),
+            Role::ROLECOP => write!(f, "ROLECOP"),
             Role::DOCTOR => write!(f, "DOCTOR"),
             Role::CELEB => write!(f, "CELEB"),
             Role::MILLER => write!(f, "MILLER"),
@@ -68,11 +309,40 @@ impl Display for Role {
             Role::MAFIA => write!(f, "MAFIA"),
             Role::GODFATHER => write!(f, "GODFATHER"),
             Role::STRIPPER => write!(f, "STRIPPER"),
+            Role::BUSDRIVER => write!(f, "BUSDRIVER"),
+            Role::VIGILANTE => write!(f, "VIGILANTE"),
+            Role::BODYGUARD => write!(f, "BODYGUARD"),
             Role::GOON => write!(f, "GOON"),
             Role::IDIOT => write!(f, "IDIOT"),
             Role::SURVIVOR => write!(f, "SURVIVOR"),
             Role::GUARD => write!(f, "GUARD"),
             Role::AGENT => write!(f, "AGENT"),
+            Role::CULTLEADER => write!(f, "CULTLEADER"),
+            Role::CULTIST => write!(f, "CULTIST"),
+            Role::POLLWATCHER => write!(f, "POLLWATCHER"),
+            Role::AVENGER => write!(f, "AVENGER"),
+            Role::UNASSIGNED => write!(f, "UNASSIGNED"),
+            Role::Custom(custom) => write!(f, "{}", custom.name),
+        }
+    }
+}
+
+impl Display for Modifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Modifier::Bulletproof => write!(f, "Bulletproof"),
+            Modifier::Macho => write!(f, "Macho"),
+            Modifier::Ascetic => write!(f, "Ascetic"),
+        }
+    }
+}
+
+impl Modifier {
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Bulletproof => "You will survive the first attempt to kill you.",
+            Self::Macho => "You refuse help, so a DOCTOR can't save you.",
+            Self::Ascetic => "No one can visit you at night, whether to help or to harm you.",
         }
     }
 }
@@ -83,6 +353,7 @@ impl Display for Team {
             Team::Town => write!(f, "Town Aligned"),
             Team::Mafia => write!(f, "Mafia Aligned"),
             Team::Rogue => write!(f, "Rogue (Unaligned)"),
+            Team::Cult => write!(f, "Cult Aligned"),
         }
     }
 }
@@ -92,6 +363,7 @@ impl Role {
         match self {
             Self::TOWN => "Figure out who the Mafia are and kill them!",
             Self::COP => "You can investigate a player each night to see if they are Mafia or not.",
+            Self::ROLECOP => "You can investigate a player each night to learn their exact role.",
             Self::DOCTOR => "You can save a player each night from being killed by the Mafia.",
             Self::CELEB => "During the Day, you can reveal yourself publicly as CELEB.",
             Self::MILLER => "But if a COP investigates you, they see you as Mafia Aligned!",
@@ -101,10 +373,61 @@ impl Role {
             }
             Self::GODFATHER => "But if a COP investigates you, they see you as Not Mafia Aligned!",
             Self::STRIPPER => "You can visit a player at night to block their action!",
-            Self::GOON => "But you cannot mark a player to be killed during the Night!",
+            Self::BUSDRIVER => {
+                "You can pick two players each night. Any action targeting one will target the other!"
+            }
+            Self::VIGILANTE => "You can shoot a player at night, killing them!",
+            Self::BODYGUARD => {
+                "You can guard a player each night. If they are attacked, you die in their place!"
+            }
+            Self::GOON => "You have no night action of your own, but you can still make the Mafia's kill!",
             Self::IDIOT | Self::SURVIVOR | Self::GUARD | Self::AGENT => {
                 "You have been given a contract. Try to fulfill it!"
             }
+            Self::CULTLEADER => {
+                "You can convert a player to the Cult each night. Outnumber everyone else!"
+            }
+            Self::CULTIST => "You have joined the Cult. Help it outnumber everyone else!",
+            Self::POLLWATCHER => "At the end of each Day, you learn exactly who voted for whom.",
+            Self::AVENGER => "Pick a player at any time. If you die, they die with you!",
+            Self::UNASSIGNED => "You haven't been given a role yet.",
+            Self::Custom(_) => self.custom_behavior().map_or("", |b| b.description()),
+        }
+    }
+}
+
+impl RoleBehavior for Role {
+    fn name(&self) -> String {
+        self.to_string()
+    }
+    fn team(&self) -> Team {
+        Role::team(self)
+    }
+    fn description(&self) -> &'static str {
+        Role::description(self)
+    }
+    fn night_priority(&self) -> Option<usize> {
+        match self {
+            Role::BUSDRIVER => Some(2),
+            Role::STRIPPER => Some(3),
+            Role::CULTLEADER => Some(4),
+            Role::COP | Role::ROLECOP => Some(5),
+            Role::VIGILANTE | Role::BODYGUARD | Role::DOCTOR => Some(6),
+            Role::Custom(_) => self.custom_behavior().and_then(|b| b.night_priority()),
+            _ => None,
+        }
+    }
+    /// Only a VIGILANTE's shot resolves here. The other built-in night actions do more
+    /// than attack or tell, so they resolve in their own steps of `Night::resolve_dawn`
+    fn resolve(&self, ctx: &mut RoleContext) {
+        match self {
+            Role::VIGILANTE => ctx.attack(ctx.target),
+            Role::Custom(_) => {
+                if let Some(behavior) = self.custom_behavior() {
+                    behavior.resolve(ctx);
+                }
+            }
+            _ => {}
         }
     }
 }
@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+/// A summary of a finished game, counted from every Event it sent
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameStats<U: RawPID> {
+    /// Every vote cast, including changed votes
+    pub votes_cast: usize,
+    /// How many votes each player received, most first
+    pub votes_received: Vec<(U, usize)>,
+    /// How many nights each player lived through
+    pub nights_survived: Vec<(U, usize)>,
+    pub kills: usize,
+    pub saves: usize,
+    pub investigations: usize,
+    /// Investigations that showed the suspect's true team
+    pub correct_investigations: usize,
+}
+
+impl<U: RawPID> Default for GameStats<U> {
+    fn default() -> Self {
+        Self {
+            votes_cast: 0,
+            votes_received: Vec::new(),
+            nights_survived: Vec::new(),
+            kills: 0,
+            saves: 0,
+            investigations: 0,
+            correct_investigations: 0,
+        }
+    }
+}
+
+impl<U: RawPID> GameStats<U> {
+    /// Counts an Event the game sent. `after_night` is whether a Night has passed
+    /// since the last Day started, so the next Day counts a night survived
+    pub fn record(&mut self, event: &Event<U>, after_night: &mut bool) {
+        match event {
+            Event::Start { players, .. } => {
+                self.nights_survived = players.iter().map(|p| (p.user_id, 0)).collect();
+            }
+            Event::Vote { ballot, .. } => {
+                self.votes_cast += 1;
+                if let Some(player) = ballot {
+                    increment(&mut self.votes_received, player.user_id);
+                }
+            }
+            Event::SkipVote { .. } => self.votes_cast += 1,
+            Event::Night { .. } => *after_night = true,
+            Event::DayStart { alive, .. } if *after_night => {
+                *after_night = false;
+                for player in alive {
+                    increment(&mut self.nights_survived, player.user_id);
+                }
+            }
+            Event::Kill { .. } => self.kills += 1,
+            Event::Save { .. } => self.saves += 1,
+            Event::Investigate {
+                suspect, result, ..
+            } => {
+                self.investigations += 1;
+                let correct = match result {
+                    InvestigationResult::Guilty(guilty) => {
+                        *guilty == (suspect.role.team() == Team::Mafia)
+                    }
+                    InvestigationResult::Role(role) => *role == suspect.role,
+                };
+                if correct {
+                    self.correct_investigations += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The stats with the players who received the most votes first
+    pub fn sorted(mut self) -> Self {
+        self.votes_received
+            .sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        self
+    }
+}
+
+fn increment<U: RawPID>(counts: &mut Vec<(U, usize)>, user_id: U) {
+    match counts.iter_mut().find(|(u, _)| *u == user_id) {
+        Some((_, count)) => *count += 1,
+        None => counts.push((user_id, 1)),
+    }
+}
@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Keeps track of the deadline for the current phase, and the warnings to send
+/// before it is reached.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PhaseTimer {
+    pub deadline: SystemTime,
+    last_tick: SystemTime,
+    /// Warnings yet to be sent, as the time remaining before the deadline.
+    /// Sorted so the earliest warning is first.
+    warnings: Vec<Duration>,
+    /// When the game was paused, if it is, by the clock ticking the timer
+    #[serde(default)]
+    paused_at: Option<SystemTime>,
+    /// When the last tick happened by this machine's clock
+    #[serde(skip)]
+    ticked_at: Option<Instant>,
+}
+
+impl PhaseTimer {
+    pub fn new(now: SystemTime, length: Duration, warnings: &[Duration]) -> Self {
+        let mut warnings = warnings.to_owned();
+        warnings.sort_by(|a, b| b.cmp(a));
+        Self {
+            deadline: now + length,
+            last_tick: now,
+            warnings,
+            paused_at: None,
+            ticked_at: Some(Instant::now()),
+        }
+    }
+
+    pub fn remaining(&self, now: SystemTime) -> Duration {
+        self.deadline.duration_since(now).unwrap_or(Duration::ZERO)
+    }
+
+    /// Whether the deadline was within the given lock window as of the last tick
+    pub fn locked(&self, lock: Duration) -> bool {
+        self.remaining(self.last_tick) <= lock
+    }
+
+    pub fn expired(&self, now: SystemTime) -> bool {
+        now >= self.deadline
+    }
+
+    /// The time now by the clock ticking the timer: the last tick, plus however long
+    /// it has been since, to the second
+    pub fn now(&self) -> SystemTime {
+        let since = self.ticked_at.map_or(Duration::ZERO, |t| t.elapsed());
+        self.last_tick + Duration::from_secs(since.as_secs())
+    }
+
+    /// Stops the timer counting down from the given time
+    pub fn pause(&mut self, now: SystemTime) {
+        self.paused_at.get_or_insert(now);
+    }
+
+    /// Starts the timer counting down again, pushing back the deadline by however
+    /// long it was paused
+    pub fn resume(&mut self, now: SystemTime) {
+        if let Some(paused) = self
+            .paused_at
+            .take()
+            .and_then(|at| now.duration_since(at).ok())
+        {
+            self.deadline += paused;
+        }
+    }
+
+    /// Keeps the timer's clock while the game is paused, without counting down
+    pub fn hold(&mut self, now: SystemTime) {
+        self.last_tick = now;
+        self.ticked_at = Some(Instant::now());
+    }
+
+    /// Pushes back the deadline, returning the time now remaining as of the last tick.
+    /// Warnings that are ahead again are re-armed.
+    pub fn extend(&mut self, by: Duration, warnings: &[Duration]) -> Duration {
+        self.deadline += by;
+        let remaining = self.remaining(self.last_tick);
+        self.warnings = warnings
+            .iter()
+            .filter(|w| **w < remaining)
+            .copied()
+            .collect();
+        self.warnings.sort_by(|a, b| b.cmp(a));
+        remaining
+    }
+
+    /// Moves the timer forward, taking the warnings that should have been sent by now,
+    /// in order
+    pub fn advance(&mut self, now: SystemTime) -> Vec<Duration> {
+        self.last_tick = now;
+        self.ticked_at = Some(Instant::now());
+        let remaining = self.remaining(now);
+        let n_due = self
+            .warnings
+            .iter()
+            .take_while(|w| **w >= remaining)
+            .count();
+        self.warnings.drain(..n_due).collect()
+    }
+}
@@ -0,0 +1,154 @@
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+/// A record of a finished game for write-ups, built from every Event it sent.
+/// Nothing is hidden, so it holds every role and night action.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transcript<U: RawPID> {
+    pub game_id: usize,
+    /// Every player as they started, with their roles
+    pub setup: Vec<Player<U>>,
+    pub contracts: Vec<Contract<U>>,
+    /// Every Day and Night, in the order they were played
+    pub phases: Vec<PhaseRecord<U>>,
+    pub winner: Option<Team>,
+    pub contract_results: Vec<ContractResult<U>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PhaseRecord<U: RawPID> {
+    Day {
+        day_no: usize,
+        /// Every vote cast, in order, including changed and retracted votes and
+        /// ghost votes, with its voter even where the election_info rule hid them
+        votes: Vec<DayVote<U>>,
+        /// The player elected, if the election went through
+        elected: Option<U>,
+        eliminated: Vec<U>,
+    },
+    Night {
+        night_no: usize,
+        actions: Vec<NightAction<U>>,
+        eliminated: Vec<U>,
+    },
+}
+
+/// A Day vote as it was cast
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DayVote<U: RawPID> {
+    pub voter: U,
+    /// None for a retraction
+    pub ballot: Option<CastBallot<U>>,
+    /// Whether it was a dead player's ghost vote
+    pub ghost: bool,
+}
+
+impl<U: RawPID> Default for Transcript<U> {
+    fn default() -> Self {
+        Self {
+            game_id: 0,
+            setup: Vec::new(),
+            contracts: Vec::new(),
+            phases: Vec::new(),
+            winner: None,
+            contract_results: Vec::new(),
+        }
+    }
+}
+
+impl<U: RawPID> Transcript<U> {
+    /// Adds an Event the game sent to the record
+    pub fn record(&mut self, event: &Event<U>) {
+        match (event, self.phases.last_mut()) {
+            (Event::Init { game_id }, _) => self.game_id = *game_id,
+            (
+                Event::Start {
+                    players, contracts, ..
+                },
+                _,
+            ) => {
+                self.setup = players.to_owned();
+                self.contracts = contracts.to_owned();
+            }
+            (Event::Day { day_no, .. }, _) => self.phases.push(PhaseRecord::Day {
+                day_no: *day_no,
+                votes: Vec::new(),
+                elected: None,
+                eliminated: Vec::new(),
+            }),
+            (Event::Night { night_no, .. }, _) => self.phases.push(PhaseRecord::Night {
+                night_no: *night_no,
+                actions: Vec::new(),
+                eliminated: Vec::new(),
+            }),
+            (
+                Event::VoteCast {
+                    voter,
+                    ballot,
+                    ghost,
+                },
+                Some(PhaseRecord::Day { votes, .. }),
+            ) => votes.push(DayVote {
+                voter: voter.user_id,
+                ballot: ballot.to_owned(),
+                ghost: *ghost,
+            }),
+            (Event::Election { ballot, .. }, Some(PhaseRecord::Day { elected, .. })) => {
+                *elected = ballot.as_ref().map(|p| p.user_id);
+            }
+            (Event::NightReport { actions, .. }, Some(PhaseRecord::Night { actions: a, .. })) => {
+                *a = actions.to_owned();
+            }
+            (
+                Event::Eliminate { player },
+                Some(PhaseRecord::Day { eliminated, .. } | PhaseRecord::Night { eliminated, .. }),
+            ) => eliminated.push(player.user_id),
+            (
+                Event::End {
+                    winner,
+                    contract_results,
+                },
+                _,
+            ) => {
+                self.winner = *winner;
+                self.contract_results = contract_results.to_owned();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// What a Comm keeps of every Event it has sent, for the game's transcript and stats
+/// and to carry on numbering Events from the last one. Unlike
+/// its history, this is saved with the game, so a game loaded from a save still has
+/// the record of everything before it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameLog<U: RawPID> {
+    /// The sequence number of the last Event sent
+    pub last_seq: u64,
+    pub transcript: Transcript<U>,
+    pub stats: GameStats<U>,
+    /// Whether a Night has passed since the last Day started, for the stats
+    after_night: bool,
+}
+
+impl<U: RawPID> Default for GameLog<U> {
+    fn default() -> Self {
+        Self {
+            last_seq: 0,
+            transcript: Transcript::default(),
+            stats: GameStats::default(),
+            after_night: false,
+        }
+    }
+}
+
+impl<U: RawPID> GameLog<U> {
+    /// Adds an Event sent under the given sequence number
+    pub fn record(&mut self, seq: u64, event: &Event<U>) {
+        self.last_seq = seq;
+        self.transcript.record(event);
+        self.stats.record(event, &mut self.after_night);
+    }
+}
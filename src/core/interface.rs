@@ -1,32 +1,342 @@
 pub mod action;
 pub mod error;
 pub mod event;
+pub mod locale;
+pub mod roster;
 
+use std::collections::VecDeque;
 use std::fmt::{Debug, Display};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::mpsc::Sender;
+use std::sync::Mutex;
 
 use super::*;
 
 type EventOutput<U> = Sender<Event<U>>;
 
+/// How many Events in a row the main Transport can fail to send
+/// before its receiver is considered gone for good
+pub const DISCONNECT_AFTER: usize = 3;
+
+/// How many of the most recent Events a Comm keeps to send again in a Resync,
+/// unless set with `with_buffer_size`
+pub const EVENT_BUFFER_SIZE: usize = 1000;
+
+/// How many Events a Comm keeps in its history, unless set with `with_history_size`.
+/// The oldest are dropped first.
+pub const EVENT_HISTORY_SIZE: usize = 100_000;
+
+/// An Event along with its sequence number. Every Event a Comm sends gets the next
+/// number, starting from 1, so a consumer can tell which Events it has already seen.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Envelope<U: RawPID> {
+    pub seq: u64,
+    pub event: Event<U>,
+}
+
+/// A way for the core to send Events out of the game. A Game only ever sees its
+/// Transports as trait objects through its Comm, so it doesn't depend on what
+/// carries its Events. There is no receiving half: Actions come in through
+/// `Game::handle()`, from whatever owns the game, such as the GameRegistry's threads.
+pub trait Transport<U: RawPID>: Debug + Send {
+    /// Sends an Event, failing if it couldn't be delivered
+    fn tx(&self, event: Event<U>) -> Result<(), SendError>;
+
+    /// Sends an Event with its sequence number. Transports that don't
+    /// need the sequence number just send the Event.
+    fn tx_envelope(&self, envelope: Envelope<U>) -> Result<(), SendError> {
+        self.tx(envelope.event)
+    }
+}
+
+impl<U: RawPID> Transport<U> for EventOutput<U> {
+    fn tx(&self, event: Event<U>) -> Result<(), SendError> {
+        self.send(event).map_err(|_| SendError)
+    }
+}
+
+type EventFilter<U> = Box<dyn Fn(&Event<U>) -> bool + Send>;
+
+/// A Transport that only receives the Events that pass its filter
+struct Subscriber<U: RawPID> {
+    transport: Box<dyn Transport<U>>,
+    filter: EventFilter<U>,
+    /// The Audience this subscriber was added for, if it was added with one
+    audience: Option<Audience<U>>,
+}
+
+impl<U: RawPID> Debug for Subscriber<U> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Subscriber")
+            .field("transport", &self.transport)
+            .field("audience", &self.audience)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A Transport that drops every Event, for a Comm that hasn't been connected
+#[derive(Debug)]
+struct Disconnected;
+
+impl<U: RawPID> Transport<U> for Disconnected {
+    fn tx(&self, _event: Event<U>) -> Result<(), SendError> {
+        Ok(())
+    }
+}
+
 #[derive(Debug)]
 pub struct Comm<U: RawPID> {
-    pub tx: EventOutput<U>,
+    transport: Box<dyn Transport<U>>,
+    subscribers: Vec<Subscriber<U>>,
+    history: Mutex<VecDeque<Event<U>>>,
+    history_size: usize,
+    /// The sequence number of the last Event sent
+    seq: AtomicU64,
+    /// The most recent Events sent, oldest first, to send again in a Resync
+    buffer: Mutex<VecDeque<Envelope<U>>>,
+    buffer_size: usize,
+    /// What is saved with the game of every Event sent
+    log: Mutex<GameLog<U>>,
+    /// How many Events in a row the main Transport has failed to send
+    failures: AtomicUsize,
+}
+
+impl<U: RawPID> Default for Comm<U> {
+    fn default() -> Self {
+        Self::with_transport(Disconnected)
+    }
 }
 
 impl<U: RawPID> Comm<U> {
     pub fn new(tx: &EventOutput<U>) -> Self {
-        Self { tx: tx.to_owned() }
+        Self::with_transport(tx.to_owned())
+    }
+
+    pub fn with_transport(transport: impl Transport<U> + 'static) -> Self {
+        Self {
+            transport: Box::new(transport),
+            subscribers: Vec::new(),
+            history: Mutex::new(VecDeque::new()),
+            history_size: EVENT_HISTORY_SIZE,
+            seq: AtomicU64::new(0),
+            buffer: Mutex::new(VecDeque::new()),
+            buffer_size: EVENT_BUFFER_SIZE,
+            log: Mutex::new(GameLog::default()),
+            failures: AtomicUsize::new(0),
+        }
+    }
+
+    /// Sets how many of the most recent Events are kept to send again in a Resync
+    pub fn with_buffer_size(mut self, size: usize) -> Self {
+        self.buffer_size = size;
+        self
+    }
+
+    /// Sets how many Events are kept in the history
+    pub fn with_history_size(mut self, size: usize) -> Self {
+        self.history_size = size;
+        self
+    }
+
+    /// Replaces the main Transport, forgetting any failures of the old one
+    pub fn reconnect(&mut self, transport: impl Transport<U> + 'static) {
+        self.transport = Box::new(transport);
+        self.failures.store(0, Ordering::Relaxed);
+    }
+
+    /// Adds a Transport that is sent every Event matching the filter,
+    /// alongside the main Transport that is sent every Event. The game sends Events
+    /// meant for players with `tx_to`, so only subscribers for their Audience::Player
+    /// receive those
+    pub fn subscribe(
+        &mut self,
+        transport: impl Transport<U> + 'static,
+        filter: impl Fn(&Event<U>) -> bool + Send + 'static,
+    ) {
+        self.subscribers.push(Subscriber {
+            transport: Box::new(transport),
+            filter: Box::new(filter),
+            audience: None,
+        });
     }
 
-    pub fn tx(&self, event: Event<U>) {
-        if let Err(e) = self.tx.send(event) {
-            // TODO: Handle this better?
-            // Do we need Complete propogation in Game.handle()?
-            // It would be difficult to add propogation to every function
-            // in Phase, Game, etc?
-            println!("Error: {:?}", e);
+    /// Adds a Transport that is sent every Event meant for the given Audience
+    pub fn subscribe_audience(
+        &mut self,
+        transport: impl Transport<U> + 'static,
+        audience: Audience<U>,
+    ) {
+        self.subscribers.push(Subscriber {
+            transport: Box::new(transport),
+            filter: Box::new(move |event| event.audience() == audience),
+            audience: Some(audience),
+        });
+    }
+
+    /// Every Event sent so far, or the most recent ones once there are more than
+    /// the history keeps
+    pub fn history(&self) -> Vec<Event<U>> {
+        self.history.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// What has been kept of every Event sent, to save with the game
+    pub fn log(&self) -> GameLog<U> {
+        self.log.lock().unwrap().to_owned()
+    }
+
+    /// Carries on from the log of a loaded game, in place of this Comm's own,
+    /// numbering Events after the last one the game sent
+    pub fn resume(&mut self, log: GameLog<U>) {
+        *self.seq.get_mut() = log.last_seq;
+        *self.log.get_mut().unwrap() = log;
+    }
+
+    /// The sequence number of the last Event sent, or 0 if none have been
+    pub fn last_seq(&self) -> u64 {
+        self.seq.load(Ordering::SeqCst)
+    }
+
+    /// Sends an Event to the main Transport and every matching subscriber,
+    /// under the next sequence number. Only a failure of the main Transport
+    /// is returned; a subscriber that has gone away just misses the Event.
+    pub fn tx(&self, event: Event<U>) -> Result<(), SendError> {
+        let envelope = self.record(event);
+        for subscriber in &self.subscribers {
+            if (subscriber.filter)(&envelope.event) {
+                let _ = subscriber.transport.tx_envelope(envelope.clone());
+            }
+        }
+        self.deliver(envelope)
+    }
+
+    /// Sends an Event to the main Transport, and only to the subscribers added for
+    /// one of the given players' `Audience::Player`, whatever the Event's own Audience.
+    /// Like `tx`, only a failure of the main Transport is returned.
+    pub fn tx_to(&self, players: &[U], event: Event<U>) -> Result<(), SendError> {
+        let envelope = self.record(event);
+        for subscriber in &self.subscribers {
+            if let Some(Audience::Player(p)) = subscriber.audience {
+                if players.contains(&p) {
+                    let _ = subscriber.transport.tx_envelope(envelope.clone());
+                }
+            }
+        }
+        self.deliver(envelope)
+    }
+
+    /// Sends an Event meant for the dead to the main Transport, to the subscribers added
+    /// for one of the given dead players' `Audience::Player`, and to every other subscriber
+    /// whose filter passes it, such as spectators with the full view.
+    /// Like `tx`, only a failure of the main Transport is returned.
+    pub fn tx_dead(&self, dead: &[U], event: Event<U>) -> Result<(), SendError> {
+        let envelope = self.record(event);
+        for subscriber in &self.subscribers {
+            let sends = match subscriber.audience {
+                Some(Audience::Player(p)) => dead.contains(&p),
+                _ => (subscriber.filter)(&envelope.event),
+            };
+            if sends {
+                let _ = subscriber.transport.tx_envelope(envelope.clone());
+            }
+        }
+        self.deliver(envelope)
+    }
+
+    /// Keeps an Event in the history and buffer under the next sequence number
+    fn record(&self, event: Event<U>) -> Envelope<U> {
+        // Numbered while holding the history, so Events are numbered in the order they're kept
+        let mut history = self.history.lock().unwrap();
+        history.push_back(event.clone());
+        while history.len() > self.history_size {
+            history.pop_front();
         }
+        let seq = self.seq.fetch_add(1, Ordering::SeqCst) + 1;
+        self.log.lock().unwrap().record(seq, &event);
+        let envelope = Envelope { seq, event };
+        let mut buffer = self.buffer.lock().unwrap();
+        buffer.push_back(envelope.clone());
+        while buffer.len() > self.buffer_size {
+            buffer.pop_front();
+        }
+        envelope
+    }
+
+    /// Sends every buffered Event numbered after `after_seq` to the main Transport
+    /// again, with its original sequence number. Fails without sending anything
+    /// if some of those Events have already been dropped from the buffer.
+    pub fn resync(&self, after_seq: u64) -> Result<(), InvalidActionError<U>> {
+        let missed: Vec<Envelope<U>> = {
+            let buffer = self.buffer.lock().unwrap();
+            let oldest = buffer.front().map_or(self.last_seq() + 1, |e| e.seq);
+            if after_seq + 1 < oldest {
+                return Err(InvalidActionError::ResyncTooOld { oldest });
+            }
+            buffer
+                .iter()
+                .filter(|e| e.seq > after_seq)
+                .cloned()
+                .collect()
+        };
+        for envelope in missed {
+            let _ = self.deliver(envelope);
+        }
+        Ok(())
+    }
+
+    /// Sends an Event to the main Transport, keeping track of whether it keeps failing
+    fn deliver(&self, envelope: Envelope<U>) -> Result<(), SendError> {
+        let seq = envelope.seq;
+        let result = self.transport.tx_envelope(envelope);
+        match &result {
+            Ok(()) => self.failures.store(0, Ordering::Relaxed),
+            Err(e) => {
+                self.failures.fetch_add(1, Ordering::Relaxed);
+                log::warn!("Event {} wasn't delivered: {}", seq, e);
+            }
+        }
+        result
+    }
+
+    /// Sends an Event for the game, which carries on whether or not it was delivered.
+    /// A failure is logged, and counts towards the Comm being disconnected.
+    /// An Event meant for a single player goes out with `tx_to`, so it only reaches
+    /// the subscribers for that player.
+    pub fn send(&self, event: Event<U>) {
+        let result = match event.audience() {
+            Audience::Player(p) => self.tx_to(&[p], event),
+            _ => self.tx(event),
+        };
+        self.sent(result);
+    }
+
+    /// Sends an Event for the game to a set of players, such as the living members
+    /// of a team, with `tx_to`. Like `send`, the game carries on regardless.
+    pub fn send_to(&self, players: &[U], event: Event<U>) {
+        let result = self.tx_to(players, event);
+        self.sent(result);
+    }
+
+    /// Sends an Event for the game to the dead players and spectators with `tx_dead`.
+    /// Like `send`, the game carries on regardless.
+    pub fn send_to_dead(&self, dead: &[U], event: Event<U>) {
+        let result = self.tx_dead(dead, event);
+        self.sent(result);
+    }
+
+    /// Logs when a failed send has just disconnected the Comm
+    fn sent(&self, result: Result<(), SendError>) {
+        if result.is_err() && self.failures.load(Ordering::Relaxed) == DISCONNECT_AFTER {
+            log::error!(
+                "Disconnected after {} Events weren't delivered",
+                DISCONNECT_AFTER
+            );
+        }
+    }
+
+    /// Whether the main Transport has failed to send the last
+    /// DISCONNECT_AFTER Events, so no one is listening to the game anymore
+    pub fn disconnected(&self) -> bool {
+        self.failures.load(Ordering::Relaxed) >= DISCONNECT_AFTER
     }
 }
 
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use crate::{controller::GameCommand, discord::UserID};
 
 use super::*;
@@ -5,26 +7,208 @@ use super::*;
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ActionKind {
     Vote,
+    SkipDay,
+    ConfirmHammer,
+    Verdict,
     Retract,
     Reveal,
     Target,
     Mark,
+    Pause,
+    Resume,
+    ExtendPhase,
+    EndGrace,
+    MyRole,
+    LegalTargets,
+    TeamChat,
+    Phase,
+    Resync,
+    ModInspect,
+    PendingActions,
+    NotVoted,
+    SetupPreview,
+    Join,
+    Leave,
+    Remove,
+    Modkill,
+    Excuse,
+    Revive,
+    Undo,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Action<U: RawPID> {
-    Vote { voter: U, ballot: Option<Choice<U>> },
-    Reveal { celeb: U },
-    Target { actor: U, target: Choice<U> },
-    Mark { killer: U, mark: Choice<U> },
+    /// `Some(Choice::Player)` votes for a player, `Some(Choice::Abstain)` votes for
+    /// no election, and `None` retracts the voter's current vote
+    Vote {
+        voter: U,
+        ballot: Option<Choice<U>>,
+    },
+    SkipDay {
+        voter: U,
+    },
+    /// Confirms a vote that is waiting to elect a player under the confirm_hammer rule
+    ConfirmHammer {
+        voter: U,
+    },
+    /// A verdict on the player on trial under the NominateTrial day style
+    Verdict {
+        juror: U,
+        guilty: bool,
+    },
+    Reveal {
+        celeb: U,
+    },
+    Target {
+        actor: U,
+        target: Choice<U>,
+    },
+    Mark {
+        killer: U,
+        mark: Choice<U>,
+    },
+    Pause {
+        moderator: U,
+    },
+    Resume {
+        moderator: U,
+    },
+    /// Starts the Night without waiting out the hammer_grace period
+    EndGrace {
+        moderator: U,
+    },
+    ExtendPhase {
+        moderator: U,
+        by: Duration,
+    },
+    MyRole {
+        player: U,
+    },
+    LegalTargets {
+        player: U,
+    },
+    TeamChat {
+        from: U,
+        text: String,
+    },
+    Phase {
+        from: U,
+    },
+    /// Sends again every buffered Event numbered after `after_seq`
+    Resync {
+        from: U,
+        after_seq: u64,
+    },
+    ModInspect {
+        moderator: U,
+        player: U,
+    },
+    PendingActions {
+        moderator: U,
+    },
+    NotVoted {
+        moderator: U,
+    },
+    SetupPreview {
+        moderator: U,
+    },
+    Join {
+        player: U,
+    },
+    Leave {
+        player: U,
+    },
+    Remove {
+        moderator: U,
+        player: U,
+    },
+    Modkill {
+        moderator: U,
+        player: U,
+    },
+    Excuse {
+        moderator: U,
+        player: U,
+    },
+    Revive {
+        moderator: U,
+        player: U,
+    },
+    Undo {
+        moderator: U,
+    },
 }
 impl<U: RawPID> Action<U> {
     pub fn kind(&self) -> ActionKind {
         match self {
             Action::Vote { .. } => ActionKind::Vote,
+            Action::SkipDay { .. } => ActionKind::SkipDay,
+            Action::ConfirmHammer { .. } => ActionKind::ConfirmHammer,
+            Action::Verdict { .. } => ActionKind::Verdict,
             Action::Reveal { .. } => ActionKind::Reveal,
             Action::Target { .. } => ActionKind::Target,
             Action::Mark { .. } => ActionKind::Mark,
+            Action::Pause { .. } => ActionKind::Pause,
+            Action::Resume { .. } => ActionKind::Resume,
+            Action::ExtendPhase { .. } => ActionKind::ExtendPhase,
+            Action::EndGrace { .. } => ActionKind::EndGrace,
+            Action::MyRole { .. } => ActionKind::MyRole,
+            Action::LegalTargets { .. } => ActionKind::LegalTargets,
+            Action::TeamChat { .. } => ActionKind::TeamChat,
+            Action::Phase { .. } => ActionKind::Phase,
+            Action::Resync { .. } => ActionKind::Resync,
+            Action::ModInspect { .. } => ActionKind::ModInspect,
+            Action::PendingActions { .. } => ActionKind::PendingActions,
+            Action::NotVoted { .. } => ActionKind::NotVoted,
+            Action::SetupPreview { .. } => ActionKind::SetupPreview,
+            Action::Join { .. } => ActionKind::Join,
+            Action::Leave { .. } => ActionKind::Leave,
+            Action::Remove { .. } => ActionKind::Remove,
+            Action::Modkill { .. } => ActionKind::Modkill,
+            Action::Excuse { .. } => ActionKind::Excuse,
+            Action::Revive { .. } => ActionKind::Revive,
+            Action::Undo { .. } => ActionKind::Undo,
+        }
+    }
+
+    /// The user taking this action
+    pub fn actor(&self) -> U {
+        match self {
+            Action::Vote { voter, .. } | Action::SkipDay { voter } => *voter,
+            Action::ConfirmHammer { voter } => *voter,
+            Action::Verdict { juror, .. } => *juror,
+            Action::Reveal { celeb } => *celeb,
+            Action::Target { actor, .. } => *actor,
+            Action::Mark { killer, .. } => *killer,
+            Action::Pause { moderator } | Action::Resume { moderator } => *moderator,
+            Action::ExtendPhase { moderator, .. } => *moderator,
+            Action::Undo { moderator }
+            | Action::EndGrace { moderator }
+            | Action::PendingActions { moderator }
+            | Action::NotVoted { moderator }
+            | Action::SetupPreview { moderator } => *moderator,
+            Action::ModInspect { moderator, .. }
+            | Action::Remove { moderator, .. }
+            | Action::Modkill { moderator, .. }
+            | Action::Excuse { moderator, .. }
+            | Action::Revive { moderator, .. } => *moderator,
+            Action::MyRole { player } | Action::Join { player } | Action::Leave { player } => {
+                *player
+            }
+            Action::LegalTargets { player } => *player,
+            Action::TeamChat { from, .. } | Action::Phase { from } => *from,
+            Action::Resync { from, .. } => *from,
+        }
+    }
+}
+
+impl From<GameCommand> for Action<UserID> {
+    fn from(cmd: GameCommand) -> Self {
+        match cmd {
+            GameCommand::Vote { voter, ballot } => Action::Vote { voter, ballot },
+            GameCommand::Reveal { celeb } => Action::Reveal { celeb },
+            GameCommand::Target { actor, target } => Action::Target { actor, target },
+            GameCommand::Mark { killer, mark } => Action::Mark { killer, mark },
         }
     }
 }
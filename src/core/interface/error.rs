@@ -6,7 +6,7 @@ use super::*;
 pub enum InvalidActionError<U: RawPID> {
     InvalidPhase {
         expected: PhaseKind,
-        found: Phase<U>,
+        found: Box<Phase<U>>,
     },
     InvalidAction {
         action: ActionKind,
@@ -26,6 +26,53 @@ pub enum InvalidActionError<U: RawPID> {
     InvalidTarget {
         target: Pidx,
     },
+    InvalidChoice {
+        choice: Choice<U>,
+        action: ActionKind,
+    },
+    NotModerator {
+        pid: U,
+    },
+    Paused,
+    AbilitySpent {
+        role: Role,
+    },
+    /// The ability_nights rule rests this role's night action tonight
+    AbilityOnCooldown {
+        role: Role,
+        night_no: usize,
+    },
+    NothingToUndo,
+    /// The player has no vote waiting to be confirmed under the confirm_hammer rule
+    NoPendingHammer,
+    /// Votes can't change while a player is on trial
+    TrialUnderway,
+    /// No one is on trial, or the player is the defendant
+    NoTrial,
+    /// A dead player's ghost vote is cast only once, and can't be changed or retracted
+    GhostVoteFinal,
+    /// A moderator excused this player from voting for the rest of the Day
+    Excused,
+    /// The current phase has no deadline
+    NoTimer,
+    /// The night's deadline is within the `action_lock` rule, so night actions can't change
+    ActionsLocked,
+    /// No one is receiving the game's Events anymore, so it stops taking Actions
+    Disconnected,
+    /// Dead players can't chat without the `dead_chat` rule
+    DeadSilence,
+    /// Spectators watch the game without taking part in it
+    Spectating {
+        pid: U,
+    },
+    /// Some of the Events asked for in a Resync are no longer buffered
+    ResyncTooOld {
+        oldest: u64,
+    },
+    /// The game's setup couldn't be changed
+    Setup {
+        error: GameError<U>,
+    },
 }
 
 impl<U: RawPID> Display for InvalidActionError<U> {
@@ -57,7 +104,254 @@ impl<U: RawPID> Display for InvalidActionError<U> {
             Self::InvalidTarget { target } => {
                 write!(f, "Invalid Target: {}", target)
             }
+            Self::InvalidChoice { choice, action } => {
+                write!(f, "Invalid Choice ({:?}) for Action ({:?})", choice, action)
+            }
+            Self::NotModerator { pid } => {
+                write!(f, "Player with UserID {:?} is not a moderator", pid)
+            }
+            Self::Paused => {
+                write!(f, "Game is paused")
+            }
+            Self::AbilitySpent { role } => {
+                write!(f, "No uses of {} ability remaining", role)
+            }
+            Self::AbilityOnCooldown { role, night_no } => {
+                write!(f, "{} ability can't be used on night {}", role, night_no)
+            }
+            Self::NothingToUndo => {
+                write!(f, "Nothing to undo")
+            }
+            Self::NoPendingHammer => {
+                write!(f, "You have no vote waiting to be confirmed")
+            }
+            Self::TrialUnderway => {
+                write!(f, "Votes can't change while someone is on trial")
+            }
+            Self::NoTrial => {
+                write!(f, "There is no trial for you to give a verdict in")
+            }
+            Self::GhostVoteFinal => {
+                write!(f, "Your ghost vote has already been cast")
+            }
+            Self::Excused => {
+                write!(f, "You have been excused from voting today")
+            }
+            Self::NoTimer => {
+                write!(f, "The phase has no deadline")
+            }
+            Self::ActionsLocked => {
+                write!(f, "Night actions are locked until the deadline")
+            }
+            Self::Disconnected => {
+                write!(f, "The game's events are no longer being received")
+            }
+            Self::DeadSilence => {
+                write!(f, "Dead players can't chat in this game")
+            }
+            Self::Spectating { pid } => {
+                write!(f, "{} is spectating, and can't take part in the game", pid)
+            }
+            Self::ResyncTooOld { oldest } => {
+                write!(f, "Only events from {} on can be sent again", oldest)
+            }
+            Self::Setup { error } => {
+                write!(f, "{}", error)
+            }
         }
     }
 }
 impl<U: RawPID> std::error::Error for InvalidActionError<U> {}
+
+/// Why a Choice was rejected by a Roster
+#[derive(Debug, PartialEq, Eq)]
+pub enum ChoiceError<U: RawPID> {
+    NoSuchPlayer {
+        pid: U,
+    },
+    PlayerDead {
+        pid: U,
+    },
+    /// The player is alive, but the actor's role can't Target them
+    IllegalTarget {
+        pid: U,
+    },
+}
+
+impl<U: RawPID> Display for ChoiceError<U> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoSuchPlayer { pid } => write!(f, "{} isn't playing in this game", pid),
+            Self::PlayerDead { pid } => write!(f, "{} is dead", pid),
+            Self::IllegalTarget { pid } => write!(f, "You can't target {}", pid),
+        }
+    }
+}
+impl<U: RawPID> std::error::Error for ChoiceError<U> {}
+
+/// Why a game couldn't be set up, started, or saved
+#[derive(Debug, PartialEq, Eq)]
+pub enum GameError<U: RawPID> {
+    GameStarted,
+    PlayerExists {
+        pid: U,
+    },
+    PlayerNotFound {
+        pid: U,
+    },
+    NameNotFound {
+        name: String,
+    },
+    /// More than one player's name matches
+    AmbiguousName {
+        name: String,
+        candidates: Vec<U>,
+    },
+    /// The game already has as many players as can sign up
+    GameFull {
+        max: usize,
+    },
+    NotEnoughPlayers {
+        found: usize,
+    },
+    /// A player hasn't been given a role yet
+    Unassigned {
+        pid: U,
+    },
+    /// The roles given already decide the game for this team
+    GameDecided {
+        winner: Team,
+    },
+    /// A player has a custom role without its behavior
+    UnknownRole {
+        name: String,
+    },
+    /// More than one player has a role only one player can have
+    DuplicateRole {
+        role: Role,
+        count: usize,
+    },
+    /// A rule only has an effect alongside another rule
+    IncompatibleRule {
+        rule: &'static str,
+        requires: &'static str,
+    },
+    SaveFailed {
+        reason: String,
+    },
+}
+
+impl<U: RawPID> Display for GameError<U> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Error with game: ")?;
+        match self {
+            Self::GameStarted => {
+                write!(f, "Game has already started")
+            }
+            Self::PlayerExists { pid } => {
+                write!(f, "Player with UserID {:?} is already in the game", pid)
+            }
+            Self::PlayerNotFound { pid } => {
+                write!(f, "Player with UserID {:?} is not in the game", pid)
+            }
+            Self::NameNotFound { name } => {
+                write!(f, "No player is named \"{}\"", name)
+            }
+            Self::AmbiguousName { name, candidates } => {
+                write!(
+                    f,
+                    "More than one player matches \"{}\": {:?}",
+                    name, candidates
+                )
+            }
+            Self::GameFull { max } => {
+                write!(f, "Game is full ({} players)", max)
+            }
+            Self::NotEnoughPlayers { found } => {
+                write!(f, "Not enough players ({}, need at least 3)", found)
+            }
+            Self::Unassigned { pid } => {
+                write!(f, "Player with UserID {:?} has no role yet", pid)
+            }
+            Self::GameDecided { winner } => {
+                write!(f, "Roles already decide the game for {}", winner)
+            }
+            Self::UnknownRole { name } => {
+                write!(f, "No behavior was given for the custom role {}", name)
+            }
+            Self::DuplicateRole { role, count } => {
+                write!(f, "Only one {} is allowed, found {}", role, count)
+            }
+            Self::IncompatibleRule { rule, requires } => {
+                write!(f, "The {} rule requires {}", rule, requires)
+            }
+            Self::SaveFailed { reason } => {
+                write!(f, "Saving failed: {}", reason)
+            }
+        }
+    }
+}
+impl<U: RawPID> std::error::Error for GameError<U> {}
+
+/// Why a saved game couldn't be loaded
+#[derive(Debug, PartialEq, Eq)]
+pub enum LoadError {
+    ReadFailed {
+        reason: String,
+    },
+    /// The save was written by a different version of the save format
+    IncompatibleVersion {
+        found: u32,
+        expected: u32,
+    },
+    Invalid {
+        reason: String,
+    },
+    /// The save could be read, but the game it holds can't be resumed
+    InconsistentState {
+        reason: String,
+    },
+    /// A player has a custom role whose behavior wasn't given to `load_game_with`
+    UnknownRole {
+        name: String,
+    },
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Error loading game: ")?;
+        match self {
+            Self::ReadFailed { reason } => {
+                write!(f, "Reading failed: {}", reason)
+            }
+            Self::IncompatibleVersion { found, expected } => {
+                write!(
+                    f,
+                    "Save has version {}, but only version {} can be loaded",
+                    found, expected
+                )
+            }
+            Self::Invalid { reason } => {
+                write!(f, "Invalid save: {}", reason)
+            }
+            Self::InconsistentState { reason } => {
+                write!(f, "Inconsistent game: {}", reason)
+            }
+            Self::UnknownRole { name } => {
+                write!(f, "No behavior was given for the custom role {}", name)
+            }
+        }
+    }
+}
+impl std::error::Error for LoadError {}
+
+/// An Event couldn't be sent, because its receiver has gone away
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SendError;
+
+impl Display for SendError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Event receiver has disconnected")
+    }
+}
+impl std::error::Error for SendError {}
@@ -1,57 +1,233 @@
 use super::*;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Event<U: RawPID> {
     Init {
         game_id: usize,
     },
+    PlayerJoined {
+        player: Player<U>,
+    },
+    PlayerLeft {
+        player: Player<U>,
+    },
     Start {
         players: Vec<Player<U>>,
         contracts: Vec<Contract<U>>,
         phase: PhaseKind,
     },
+    /// How many players are Mafia Aligned, and how many aren't, under `StartInfo::Mafia`
+    MafiaAnnounce {
+        mafia: usize,
+        others: usize,
+    },
+    /// How many players are on each team, without saying who, under `StartInfo::Team`
+    TeamAnnounce {
+        team_counts: Vec<(Team, usize)>,
+    },
+    /// How many players have each role, without saying who, under `StartInfo::Role`
+    SetupAnnounce {
+        role_counts: Vec<(Role, usize)>,
+    },
+    YourRole {
+        player: Player<U>,
+        role: Role,
+        team: Team,
+        description: String,
+        /// Teammates this player knows about
+        teammates: Vec<Player<U>>,
+    },
+    LegalTargets {
+        player: Player<U>,
+        /// Every player this player's Target could name right now
+        targets: Vec<Player<U>>,
+    },
+    Phase {
+        to: U,
+        phase: PhaseKind,
+        number: Option<usize>,
+    },
+    ModInspect {
+        moderator: U,
+        player: Player<U>,
+        role: Role,
+        alive: bool,
+    },
+    PendingActions {
+        moderator: U,
+        /// Living players with a night action who haven't made it yet
+        missing: Vec<Player<U>>,
+    },
+    NotVoted {
+        moderator: U,
+        /// Living players without a standing vote
+        missing: Vec<Player<U>>,
+    },
+    SetupPreview {
+        moderator: U,
+        /// Every signed up player with the role they'll start with
+        roles: Vec<(U, Role)>,
+    },
+    WrongPhase {
+        player: Player<U>,
+        action: ActionKind,
+        expected: PhaseKind,
+        got: PhaseKind,
+    },
+    /// A night action came in too close to the deadline under the `action_lock` rule
+    ActionsLocked {
+        player: Player<U>,
+        action: ActionKind,
+    },
     Day {
         day_no: usize,
         players: Vec<Player<U>>,
     },
+    DayStart {
+        day_no: usize,
+        alive: Vec<Player<U>>,
+        threshold: usize,
+    },
     Vote {
-        voter: Player<U>,
+        voter: Option<Player<U>>,
         ballot: Option<Player<U>>,
         former: Option<Option<Player<U>>>,
         threshold: usize,
+        /// None while the VoteCounts rule hides it
+        count: Option<usize>,
+    },
+    NightVote {
+        voter: Player<U>,
+        ballot: Option<Player<U>>,
         count: usize,
+        threshold: usize,
     },
-    Retract {
+    NightElection {
+        electors: Vec<Player<U>>,
+        elected: Player<U>,
+    },
+    /// A Day vote as it was cast, with its voter whatever the ElectionInfo rule, for
+    /// spectators and the transcript. The ballot is None for a retraction
+    VoteCast {
+        voter: Player<U>,
+        ballot: Option<CastBallot<U>>,
+        /// Whether it was a dead player's ghost vote
+        ghost: bool,
+    },
+    SkipVote {
+        voter: Option<Player<U>>,
+        threshold: usize,
+        count: usize,
+    },
+    /// A dead player's one vote under the `ghost_vote` rule
+    GhostVote {
+        ghost: Option<Player<U>>,
+        ballot: Option<Player<U>>,
+        count: usize,
+        threshold: usize,
+    },
+    AlreadyVoted {
         voter: Player<U>,
+        ballot: Option<Player<U>>,
+    },
+    Retract {
+        voter: Option<Player<U>>,
         former: Option<Option<Player<U>>>,
     },
     Reveal {
         celeb: Player<U>,
     },
+    Tie {
+        tied: Vec<Option<Player<U>>>,
+    },
+    VoteTally {
+        tally: Tally<U>,
+    },
+    /// Every vote of the day, sent only to a living POLLWATCHER whatever the ElectionInfo rule
+    PollResult {
+        watcher: Player<U>,
+        tally: Tally<U>,
+    },
+    /// The end of the day's election. `standings` holds how many votes every
+    /// ballot had at the time, unless the ElectionInfo rule keeps votes Secret,
+    /// and `threshold` is how many votes it took to elect a player. `hammer` is
+    /// the elector whose vote crossed the threshold, even if they were elected
+    /// themself. None if no single vote decided the election, such as at a
+    /// deadline, a tie-break, or a trial, and None if votes are hidden.
     Election {
         electors: Vec<Player<U>>,
         ballot: Option<Player<U>>,
+        hammer: Option<Player<U>>,
+        standings: Vec<(Option<Player<U>>, usize)>,
+        threshold: usize,
+    },
+    DaySkipped {
+        electors: Vec<Player<U>>,
+    },
+    /// Dusk has begun, and the actor can pick one of the options with their dusk
+    /// action, such as an elected IDIOT picking one of their electors
+    Dusk {
+        actor: Player<U>,
+        options: Vec<Player<U>>,
     },
     Night {
         night_no: usize,
         players: Vec<Player<U>>,
     },
-    Target {
+    /// Sent to each player with a night action, prompting them to make it
+    NightStart {
+        night_no: usize,
+        actor: Player<U>,
+    },
+    ActionConfirmed {
         actor: Player<U>,
-        target: Option<Player<U>>,
+        target: Vec<Player<U>>,
+    },
+    /// A dying AVENGER takes the player they picked down with them
+    Avenged {
+        avenger: Player<U>,
+        victim: Player<U>,
+    },
+    AbilityUsed {
+        player: Player<U>,
+        remaining: usize,
     },
     Mark {
         killer: Player<U>,
         mark: Option<Player<U>>,
     },
+    TeamMessage {
+        from: Player<U>,
+        to: Vec<Player<U>>,
+        text: String,
+    },
+    /// A dead player's TeamChat under the `dead_chat` rule, for the dead and spectators
+    DeadChat {
+        from: Player<U>,
+        to: Vec<Player<U>>,
+        text: String,
+    },
+    /// A dead player tried to TeamChat without the `dead_chat` rule
+    DeadSilence {
+        player: Player<U>,
+    },
+    NightComplete,
     Dawn,
+    /// Sent only to the STRIPPER, whose visit blocked a night action
     Strip {
         stripper: Player<U>,
         blocked: Player<U>,
     },
+    /// Sent only to the blocked player, whose night action didn't go through
     Block {
         blocked: Player<U>,
     },
+    Intercept {
+        bodyguard: Player<U>,
+        guarded: Player<U>,
+    },
     Save {
         doctor: Player<U>,
         saved: Player<U>,
@@ -59,35 +235,195 @@ pub enum Event<U: RawPID> {
     Investigate {
         cop: Player<U>,
         suspect: Player<U>,
-        role: Role,
+        result: InvestigationResult,
+    },
+    /// A STRIPPER blocked the investigation, so there's no result
+    InvestigateBlocked {
+        cop: Player<U>,
+    },
+    /// What a custom role's night action told the player who made it
+    CustomResult {
+        actor: Player<U>,
+        text: String,
+    },
+    VisitFizzled {
+        actor: Player<U>,
+        target: Player<U>,
+    },
+    Converted {
+        cult_leader: Player<U>,
+        convert: Player<U>,
+    },
+    Survived {
+        victim: Player<U>,
     },
     Kill {
         killer: Player<U>,
         mark: Player<U>,
     },
     NoKill,
+    /// A moderator is eliminating a player, ahead of their Eliminate
+    Modkill {
+        player: Player<U>,
+        role: Role,
+    },
+    /// A player who made no action for `phases` phases in a row is being eliminated
+    /// under the idle_modkill rule, ahead of their Eliminate
+    IdleModkill {
+        player: Player<U>,
+        phases: usize,
+    },
+    /// The Night begins once this grace period after an election is over
+    HammerGrace {
+        remaining: Duration,
+    },
+    /// A player reached the threshold under the NominateTrial day style, and is on
+    /// trial until every other player gives a verdict
+    Trial {
+        defendant: Player<U>,
+    },
+    Verdict {
+        juror: Player<U>,
+        guilty: bool,
+    },
+    /// The trial ended without enough Guilty verdicts, and the Day goes on
+    Acquitted {
+        defendant: Player<U>,
+        guilty: usize,
+        innocent: usize,
+    },
+    /// The hammer's vote would elect a player under the confirm_hammer rule, and
+    /// is waiting for them to confirm it
+    HammerPending {
+        hammer: Player<U>,
+        elected: Player<U>,
+    },
+    /// The pending hammer was retracted, lost its majority, or wasn't confirmed
+    /// before the deadline
+    HammerCancelled {
+        hammer: Player<U>,
+        ballot: Option<Player<U>>,
+    },
+    /// A moderator excused a player from voting for the rest of the Day
+    PlayerExcused {
+        player: Player<U>,
+        /// The votes now needed to elect a player
+        threshold: usize,
+    },
     Eliminate {
         player: Player<U>,
     },
+    Revived {
+        player: Player<U>,
+    },
+    Undone {
+        phase: PhaseKind,
+        number: Option<usize>,
+        /// Players who are alive again
+        restored: Vec<Player<U>>,
+    },
     Refocus {
         new_contract: Contract<U>,
     },
+    /// Every night action and how it turned out, for spectators
+    NightReport {
+        night_no: usize,
+        actions: Vec<NightAction<U>>,
+    },
+    /// Each step of resolving the night in the order it was taken, for spectators
+    /// and moderators settling disputes over how the night turned out
+    ResolutionTrace {
+        night_no: usize,
+        steps: Vec<ResolutionStep<U>>,
+    },
+    TimeWarning {
+        remaining: Duration,
+    },
+    PhaseExtended {
+        new_remaining: Duration,
+    },
+    Paused,
+    Resumed,
     End {
-        winner: Team,
+        winner: Option<Team>,
         contract_results: Vec<ContractResult<U>>,
     },
+    GameStats {
+        stats: GameStats<U>,
+    },
 }
 
 impl<U: RawPID> Display for Event<U> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Event::Init{game_id} => write!(f, "Init"),
+            Event::Init { game_id } => write!(f, "Init"),
+            Event::PlayerJoined { player } => write!(f, "PlayerJoined: {:?}", player),
+            Event::PlayerLeft { player } => write!(f, "PlayerLeft: {:?}", player),
             Event::Start {
                 players,
                 contracts,
                 phase,
             } => write!(f, "Start: {:?} {:?} {:?}", players, contracts, phase),
+            Event::MafiaAnnounce { mafia, others } => {
+                write!(f, "MafiaAnnounce: {} vs {}", mafia, others)
+            }
+            Event::TeamAnnounce { team_counts } => write!(f, "TeamAnnounce: {:?}", team_counts),
+            Event::SetupAnnounce { role_counts } => write!(f, "SetupAnnounce: {:?}", role_counts),
+            Event::YourRole {
+                player,
+                role,
+                team,
+                description,
+                teammates,
+            } => write!(
+                f,
+                "YourRole: {:?} {:?} {:?} {} {:?}",
+                player, role, team, description, teammates
+            ),
+            Event::LegalTargets { player, targets } => {
+                write!(f, "LegalTargets: {:?} {:?}", player, targets)
+            }
+            Event::Phase { to, phase, number } => {
+                write!(f, "Phase: {} {} {:?}", to, phase, number)
+            }
+            Event::ModInspect {
+                moderator,
+                player,
+                role,
+                alive,
+            } => write!(
+                f,
+                "ModInspect: {} {:?} {:?} {}",
+                moderator, player, role, alive
+            ),
+            Event::PendingActions { moderator, missing } => {
+                write!(f, "PendingActions: {} {:?}", moderator, missing)
+            }
+            Event::NotVoted { moderator, missing } => {
+                write!(f, "NotVoted: {} {:?}", moderator, missing)
+            }
+            Event::SetupPreview { moderator, roles } => {
+                write!(f, "SetupPreview: {} {:?}", moderator, roles)
+            }
+            Event::WrongPhase {
+                player,
+                action,
+                expected,
+                got,
+            } => write!(
+                f,
+                "WrongPhase: {:?} {:?} {} {}",
+                player, action, expected, got
+            ),
+            Event::ActionsLocked { player, action } => {
+                write!(f, "ActionsLocked: {:?} {:?}", player, action)
+            }
             Event::Day { day_no, players } => write!(f, "Day {}: {:?}", day_no, players),
+            Event::DayStart {
+                day_no,
+                alive,
+                threshold,
+            } => write!(f, "DayStart {}: {:?} {}", day_no, alive, threshold),
             Event::Vote {
                 voter,
                 ballot,
@@ -96,85 +432,471 @@ impl<U: RawPID> Display for Event<U> {
                 count,
             } => write!(
                 f,
-                "Vote: {:?} {:?} {:?} {} {}",
+                "Vote: {:?} {:?} {:?} {} {:?}",
                 voter, ballot, former, threshold, count
             ),
+            Event::NightVote {
+                voter,
+                ballot,
+                count,
+                threshold,
+            } => write!(
+                f,
+                "NightVote: {:?} {:?} {} {}",
+                voter, ballot, count, threshold
+            ),
+            Event::NightElection { electors, elected } => {
+                write!(f, "NightElection: {:?} {:?}", electors, elected)
+            }
+            Event::VoteCast {
+                voter,
+                ballot,
+                ghost,
+            } => write!(f, "VoteCast: {:?} {:?} {}", voter, ballot, ghost),
+            Event::SkipVote {
+                voter,
+                threshold,
+                count,
+            } => write!(f, "SkipVote: {:?} {} {}", voter, threshold, count),
+            Event::GhostVote {
+                ghost,
+                ballot,
+                count,
+                threshold,
+            } => write!(
+                f,
+                "GhostVote: {:?} {:?} {} {}",
+                ghost, ballot, count, threshold
+            ),
+            Event::AlreadyVoted { voter, ballot } => {
+                write!(f, "AlreadyVoted: {:?} {:?}", voter, ballot)
+            }
             Event::Retract { voter, former } => write!(f, "Retract: {:?} {:?}", voter, former),
             Event::Reveal { celeb } => write!(f, "Reveal: {:?}", celeb),
-            Event::Election { electors, ballot } => {
-                write!(f, "Election: {:?} {:?}", electors, ballot)
+            Event::Tie { tied } => write!(f, "Tie: {:?}", tied),
+            Event::VoteTally { tally } => write!(f, "VoteTally: {:?}", tally),
+            Event::PollResult { watcher, tally } => {
+                write!(f, "PollResult: {:?} {:?}", watcher, tally)
             }
+            Event::Election {
+                electors,
+                ballot,
+                hammer,
+                standings,
+                threshold,
+            } => write!(
+                f,
+                "Election: {:?} {:?} {:?} {:?} {}",
+                electors, ballot, hammer, standings, threshold
+            ),
+            Event::DaySkipped { electors } => write!(f, "DaySkipped: {:?}", electors),
+            Event::Dusk { actor, options } => write!(f, "Dusk: {:?} {:?}", actor, options),
             Event::Night { night_no, players } => write!(f, "Night {}: {:?}", night_no, players),
-            Event::Target { actor, target } => write!(f, "Target: {:?} {:?}", actor, target),
+            Event::NightStart { night_no, actor } => {
+                write!(f, "NightStart {}: {:?}", night_no, actor)
+            }
+            Event::ActionConfirmed { actor, target } => {
+                write!(f, "ActionConfirmed: {:?} {:?}", actor, target)
+            }
+            Event::AbilityUsed { player, remaining } => {
+                write!(f, "AbilityUsed: {:?} {}", player, remaining)
+            }
+            Event::Avenged { avenger, victim } => {
+                write!(f, "Avenged: {:?} {:?}", avenger, victim)
+            }
             Event::Mark { killer, mark } => write!(f, "Mark: {:?} {:?}", killer, mark),
+            Event::TeamMessage { from, to, text } => {
+                write!(f, "TeamMessage: {:?} {:?} {}", from, to, text)
+            }
+            Event::DeadChat { from, to, text } => {
+                write!(f, "DeadChat: {:?} {:?} {}", from, to, text)
+            }
+            Event::DeadSilence { player } => write!(f, "DeadSilence: {:?}", player),
+            Event::NightComplete => write!(f, "NightComplete"),
             Event::Dawn => write!(f, "Dawn"),
             Event::Strip { stripper, blocked } => write!(f, "Strip: {:?} {:?}", stripper, blocked),
             Event::Block { blocked } => write!(f, "Block: {:?}", blocked),
+            Event::Intercept { bodyguard, guarded } => {
+                write!(f, "Intercept: {:?} {:?}", bodyguard, guarded)
+            }
             Event::Save { doctor, saved } => write!(f, "Save: {:?} {:?}", doctor, saved),
-            Event::Investigate { cop, suspect, role } => {
-                write!(f, "Investigate: {:?} {:?} {:?}", cop, suspect, role)
+            Event::Investigate {
+                cop,
+                suspect,
+                result,
+            } => {
+                write!(f, "Investigate: {:?} {:?} {:?}", cop, suspect, result)
+            }
+            Event::InvestigateBlocked { cop } => write!(f, "InvestigateBlocked: {:?}", cop),
+            Event::CustomResult { actor, text } => write!(f, "CustomResult: {:?} {}", actor, text),
+            Event::VisitFizzled { actor, target } => {
+                write!(f, "VisitFizzled: {:?} {:?}", actor, target)
             }
+            Event::Converted {
+                cult_leader,
+                convert,
+            } => write!(f, "Converted: {:?} {:?}", cult_leader, convert),
+            Event::Survived { victim } => write!(f, "Survived: {:?}", victim),
             Event::Kill { killer, mark } => write!(f, "Kill: {:?} {:?}", killer, mark),
             Event::NoKill => write!(f, "NoKill"),
+            Event::Modkill { player, role } => write!(f, "Modkill: {:?} {:?}", player, role),
+            Event::HammerGrace { remaining } => write!(f, "HammerGrace: {:?}", remaining),
+            Event::Trial { defendant } => write!(f, "Trial: {:?}", defendant),
+            Event::Verdict { juror, guilty } => write!(f, "Verdict: {:?} {}", juror, guilty),
+            Event::Acquitted {
+                defendant,
+                guilty,
+                innocent,
+            } => write!(f, "Acquitted: {:?} {} {}", defendant, guilty, innocent),
+            Event::HammerPending { hammer, elected } => {
+                write!(f, "HammerPending: {:?} {:?}", hammer, elected)
+            }
+            Event::HammerCancelled { hammer, ballot } => {
+                write!(f, "HammerCancelled: {:?} {:?}", hammer, ballot)
+            }
+            Event::IdleModkill { player, phases } => {
+                write!(f, "IdleModkill: {:?} {}", player, phases)
+            }
+            Event::PlayerExcused { player, threshold } => {
+                write!(f, "PlayerExcused: {:?} {}", player, threshold)
+            }
             Event::Eliminate { player } => write!(f, "Eliminate: {:?}", player),
+            Event::Revived { player } => write!(f, "Revived: {:?}", player),
+            Event::Undone {
+                phase,
+                number,
+                restored,
+            } => write!(f, "Undone: {} {:?} {:?}", phase, number, restored),
             Event::Refocus { new_contract } => write!(f, "Refocus: {:?}", new_contract),
+            Event::NightReport { night_no, actions } => {
+                write!(f, "NightReport {}: {:?}", night_no, actions)
+            }
+            Event::ResolutionTrace { night_no, steps } => {
+                write!(f, "ResolutionTrace {}: {:?}", night_no, steps)
+            }
+            Event::TimeWarning { remaining } => write!(f, "TimeWarning: {:?}", remaining),
+            Event::PhaseExtended { new_remaining } => {
+                write!(f, "PhaseExtended: {:?}", new_remaining)
+            }
+            Event::Paused => write!(f, "Paused"),
+            Event::Resumed => write!(f, "Resumed"),
             Event::End {
                 winner,
                 contract_results,
             } => {
                 write!(f, "End: {:?}, contracts: {:?}", winner, contract_results)
             }
+            Event::GameStats { stats } => write!(f, "GameStats: {:?}", stats),
         }
     }
 }
 
+/// Who an Event is meant to be shown to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Audience<U: RawPID> {
+    Public,
+    Team(Team),
+    Player(U),
+    /// Anyone watching the game with the full view, but no players
+    Spectators,
+    /// Dead players and anyone watching the game with the full view
+    Dead,
+}
+
+/// Which Events someone watching the game with `Game::spectate()` is sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SpectatorView {
+    #[default]
+    /// Only public Events, as a player would see them
+    PublicOnly,
+    /// Public Events along with the ones meant for spectators, such as every night action
+    FullInfo,
+}
+
+impl SpectatorView {
+    pub fn shows<U: RawPID>(&self, event: &Event<U>) -> bool {
+        matches!(
+            (self, event.audience()),
+            (_, Audience::Public)
+                | (Self::FullInfo, Audience::Spectators)
+                | (Self::FullInfo, Audience::Dead)
+        )
+    }
+}
+
+/// Each ballot voted for during a day, with the players who voted for it
+pub type Tally<U> = Vec<(Option<Player<U>>, Vec<Player<U>>)>;
+
+/// What a Day vote was cast for
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CastBallot<U: RawPID> {
+    Player(Player<U>),
+    Abstain,
+    Skip,
+}
+
+/// A night action as it was made, and how it turned out
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NightAction<U: RawPID> {
+    pub actor: Player<U>,
+    /// The players the action was made on, before any swaps
+    pub targets: Vec<Player<U>>,
+    pub outcome: ActionOutcome,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ActionOutcome {
+    /// The action was made on its targets
+    Targeted,
+    /// No action was made
+    Abstained,
+    /// A STRIPPER stopped the action
+    Blocked,
+    /// An Ascetic player stopped the action
+    Fizzled,
+    /// A kill that a DOCTOR, BODYGUARD, or Bulletproof stopped
+    Saved,
+    /// A kill that landed
+    Killed,
+}
+
+/// One step of resolving a night, as dawn took it
 #[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolutionStep<U: RawPID> {
+    /// A BUSDRIVER swapped two players, redirecting every visit to either one
+    Redirected {
+        driver: Player<U>,
+        swapped: (Player<U>, Player<U>),
+    },
+    /// An action on an Ascetic player fizzled
+    Fizzled { actor: Player<U>, target: Player<U> },
+    /// STRIPPERS stopped a player's action
+    Blocked {
+        blocked: Player<U>,
+        strippers: Vec<Player<U>>,
+    },
+    /// A BODYGUARD stepped in front of an attack
+    Intercepted {
+        bodyguard: Player<U>,
+        guarded: Player<U>,
+    },
+    /// DOCTORS saved a player from an attack
+    Saved {
+        killer: Player<U>,
+        saved: Player<U>,
+        doctors: Vec<Player<U>>,
+    },
+    /// A Bulletproof player survived an attack
+    Survived {
+        killer: Player<U>,
+        victim: Player<U>,
+    },
+    /// An attack landed
+    Killed { killer: Player<U>, mark: Player<U> },
+}
+
+impl<U: RawPID> Event<U> {
+    pub fn audience(&self) -> Audience<U> {
+        match self {
+            Event::YourRole { player, .. } => Audience::Player(player.user_id),
+            Event::LegalTargets { player, .. } => Audience::Player(player.user_id),
+            Event::Phase { to, .. } => Audience::Player(*to),
+            Event::ModInspect { moderator, .. } => Audience::Player(*moderator),
+            Event::PendingActions { moderator, .. } => Audience::Player(*moderator),
+            Event::NotVoted { moderator, .. } => Audience::Player(*moderator),
+            Event::SetupPreview { moderator, .. } => Audience::Player(*moderator),
+            Event::WrongPhase { player, .. } => Audience::Player(player.user_id),
+            Event::ActionsLocked { player, .. } => Audience::Player(player.user_id),
+            Event::HammerPending { hammer, .. } => Audience::Player(hammer.user_id),
+            Event::HammerCancelled { hammer, .. } => Audience::Player(hammer.user_id),
+            Event::AlreadyVoted { voter, .. } => Audience::Player(voter.user_id),
+            Event::ActionConfirmed { actor, .. } => Audience::Player(actor.user_id),
+            Event::AbilityUsed { player, .. } => Audience::Player(player.user_id),
+            Event::NightStart { actor, .. } => Audience::Player(actor.user_id),
+            Event::Mark { .. } => Audience::Team(Team::Mafia),
+            Event::VoteCast { .. } => Audience::Spectators,
+            Event::NightReport { .. } => Audience::Spectators,
+            Event::ResolutionTrace { .. } => Audience::Spectators,
+            Event::DeadChat { .. } => Audience::Dead,
+            Event::DeadSilence { player } => Audience::Player(player.user_id),
+            Event::TeamMessage { from, .. } => Audience::Team(from.role.team()),
+            Event::Strip { stripper, .. } => Audience::Player(stripper.user_id),
+            Event::Block { blocked } => Audience::Player(blocked.user_id),
+            Event::Intercept { bodyguard, .. } => Audience::Player(bodyguard.user_id),
+            Event::Save { doctor, .. } => Audience::Player(doctor.user_id),
+            Event::Investigate { cop, .. } | Event::InvestigateBlocked { cop } => {
+                Audience::Player(cop.user_id)
+            }
+            Event::CustomResult { actor, .. } => Audience::Player(actor.user_id),
+            Event::PollResult { watcher, .. } => Audience::Player(watcher.user_id),
+            Event::VisitFizzled { actor, .. } => Audience::Player(actor.user_id),
+            Event::Converted { convert, .. } => Audience::Player(convert.user_id),
+            _ => Audience::Public,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum EventKind {
     Init,
+    PlayerJoined,
+    PlayerLeft,
     Start,
+    MafiaAnnounce,
+    TeamAnnounce,
+    SetupAnnounce,
+    YourRole,
+    LegalTargets,
+    Phase,
+    ModInspect,
+    PendingActions,
+    NotVoted,
+    SetupPreview,
+    WrongPhase,
+    ActionsLocked,
     Day,
+    DayStart,
     Vote,
+    NightVote,
+    NightElection,
+    VoteCast,
+    SkipVote,
+    GhostVote,
+    AlreadyVoted,
     Retract,
     Reveal,
+    Tie,
+    VoteTally,
+    PollResult,
     Election,
+    DaySkipped,
+    Dusk,
     Night,
-    Target,
+    NightStart,
+    ActionConfirmed,
+    Avenged,
+    AbilityUsed,
     Mark,
+    TeamMessage,
+    DeadChat,
+    DeadSilence,
+    NightComplete,
     Dawn,
     Strip,
     Block,
+    Intercept,
     Save,
     Investigate,
+    InvestigateBlocked,
+    CustomResult,
+    VisitFizzled,
+    Converted,
+    Survived,
     Kill,
     NoKill,
+    Modkill,
+    IdleModkill,
+    HammerGrace,
+    Trial,
+    Verdict,
+    Acquitted,
+    HammerPending,
+    HammerCancelled,
+    PlayerExcused,
     Eliminate,
+    Revived,
+    Undone,
     Refocus,
+    NightReport,
+    ResolutionTrace,
+    TimeWarning,
+    PhaseExtended,
+    Paused,
+    Resumed,
     End,
+    GameStats,
 }
 
-impl Event<u64> {
+impl<U: RawPID> Event<U> {
     pub fn kind(&self) -> EventKind {
         match self {
-            Event::Init{ .. } => EventKind::Init,
+            Event::Init { .. } => EventKind::Init,
+            Event::PlayerJoined { .. } => EventKind::PlayerJoined,
+            Event::PlayerLeft { .. } => EventKind::PlayerLeft,
             Event::Start { .. } => EventKind::Start,
+            Event::MafiaAnnounce { .. } => EventKind::MafiaAnnounce,
+            Event::TeamAnnounce { .. } => EventKind::TeamAnnounce,
+            Event::SetupAnnounce { .. } => EventKind::SetupAnnounce,
+            Event::YourRole { .. } => EventKind::YourRole,
+            Event::LegalTargets { .. } => EventKind::LegalTargets,
+            Event::Phase { .. } => EventKind::Phase,
+            Event::ModInspect { .. } => EventKind::ModInspect,
+            Event::PendingActions { .. } => EventKind::PendingActions,
+            Event::NotVoted { .. } => EventKind::NotVoted,
+            Event::SetupPreview { .. } => EventKind::SetupPreview,
+            Event::WrongPhase { .. } => EventKind::WrongPhase,
+            Event::ActionsLocked { .. } => EventKind::ActionsLocked,
             Event::Day { .. } => EventKind::Day,
+            Event::DayStart { .. } => EventKind::DayStart,
             Event::Vote { .. } => EventKind::Vote,
+            Event::NightVote { .. } => EventKind::NightVote,
+            Event::NightElection { .. } => EventKind::NightElection,
+            Event::VoteCast { .. } => EventKind::VoteCast,
+            Event::SkipVote { .. } => EventKind::SkipVote,
+            Event::GhostVote { .. } => EventKind::GhostVote,
+            Event::AlreadyVoted { .. } => EventKind::AlreadyVoted,
             Event::Retract { .. } => EventKind::Retract,
             Event::Reveal { .. } => EventKind::Reveal,
+            Event::Tie { .. } => EventKind::Tie,
+            Event::VoteTally { .. } => EventKind::VoteTally,
+            Event::PollResult { .. } => EventKind::PollResult,
             Event::Election { .. } => EventKind::Election,
+            Event::DaySkipped { .. } => EventKind::DaySkipped,
+            Event::Dusk { .. } => EventKind::Dusk,
             Event::Night { .. } => EventKind::Night,
-            Event::Target { .. } => EventKind::Target,
+            Event::NightStart { .. } => EventKind::NightStart,
+            Event::ActionConfirmed { .. } => EventKind::ActionConfirmed,
+            Event::Avenged { .. } => EventKind::Avenged,
+            Event::AbilityUsed { .. } => EventKind::AbilityUsed,
             Event::Mark { .. } => EventKind::Mark,
+            Event::TeamMessage { .. } => EventKind::TeamMessage,
+            Event::DeadChat { .. } => EventKind::DeadChat,
+            Event::DeadSilence { .. } => EventKind::DeadSilence,
+            Event::NightComplete => EventKind::NightComplete,
             Event::Dawn => EventKind::Dawn,
             Event::Strip { .. } => EventKind::Strip,
             Event::Block { .. } => EventKind::Block,
+            Event::Intercept { .. } => EventKind::Intercept,
             Event::Save { .. } => EventKind::Save,
             Event::Investigate { .. } => EventKind::Investigate,
+            Event::InvestigateBlocked { .. } => EventKind::InvestigateBlocked,
+            Event::CustomResult { .. } => EventKind::CustomResult,
+            Event::VisitFizzled { .. } => EventKind::VisitFizzled,
+            Event::Converted { .. } => EventKind::Converted,
+            Event::Survived { .. } => EventKind::Survived,
             Event::Kill { .. } => EventKind::Kill,
             Event::NoKill => EventKind::NoKill,
+            Event::Modkill { .. } => EventKind::Modkill,
+            Event::IdleModkill { .. } => EventKind::IdleModkill,
+            Event::HammerGrace { .. } => EventKind::HammerGrace,
+            Event::Trial { .. } => EventKind::Trial,
+            Event::Verdict { .. } => EventKind::Verdict,
+            Event::Acquitted { .. } => EventKind::Acquitted,
+            Event::HammerPending { .. } => EventKind::HammerPending,
+            Event::HammerCancelled { .. } => EventKind::HammerCancelled,
+            Event::PlayerExcused { .. } => EventKind::PlayerExcused,
             Event::Eliminate { .. } => EventKind::Eliminate,
+            Event::Revived { .. } => EventKind::Revived,
+            Event::Undone { .. } => EventKind::Undone,
             Event::Refocus { .. } => EventKind::Refocus,
+            Event::NightReport { .. } => EventKind::NightReport,
+            Event::ResolutionTrace { .. } => EventKind::ResolutionTrace,
+            Event::TimeWarning { .. } => EventKind::TimeWarning,
+            Event::PhaseExtended { .. } => EventKind::PhaseExtended,
+            Event::Paused => EventKind::Paused,
+            Event::Resumed => EventKind::Resumed,
             Event::End { .. } => EventKind::End,
+            Event::GameStats { .. } => EventKind::GameStats,
         }
     }
 }
@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+
+use super::*;
+
+/// A catalog of message templates, one for each kind of Event.
+/// Templates refer to the Event's fields by name in braces, e.g. "{voter} votes for {ballot}"
+pub trait Locale {
+    fn template(&self, kind: &EventKind) -> Option<&str>;
+    fn term(&self, term: Term) -> &str {
+        match term {
+            Term::Abstain => "No Election",
+            Term::Someone => "Someone",
+            Term::NoOne => "No one",
+            Term::Hidden => "?",
+        }
+    }
+}
+
+/// Words filled in where an Event has no player to name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Term {
+    /// A vote for no one to be elected
+    Abstain,
+    /// A voter hidden by the ElectionInfo rule
+    Someone,
+    /// The winner of a draw
+    NoOne,
+    /// A number of votes hidden by the VoteCounts rule
+    Hidden,
+}
+
+/// A Locale made from templates supplied at runtime, such as a translation file
+#[derive(Debug, Clone, Default)]
+pub struct Catalog {
+    templates: HashMap<EventKind, String>,
+    terms: HashMap<Term, String>,
+}
+
+impl Catalog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn with(mut self, kind: EventKind, template: &str) -> Self {
+        self.templates.insert(kind, template.to_string());
+        self
+    }
+    pub fn with_term(mut self, term: Term, text: &str) -> Self {
+        self.terms.insert(term, text.to_string());
+        self
+    }
+}
+
+impl Locale for Catalog {
+    fn template(&self, kind: &EventKind) -> Option<&str> {
+        self.templates.get(kind).map(|t| t.as_str())
+    }
+    fn term(&self, term: Term) -> &str {
+        match self.terms.get(&term) {
+            Some(text) => text,
+            None => English.term(term),
+        }
+    }
+}
+
+/// The default English messages
+#[derive(Debug, Clone, Copy, Default)]
+pub struct English;
+
+impl Locale for English {
+    fn template(&self, kind: &EventKind) -> Option<&str> {
+        let template = match kind {
+            EventKind::PlayerJoined => "{player} has joined the game!",
+            EventKind::PlayerLeft => "{player} has left the game.",
+            EventKind::MafiaAnnounce => "This game: {mafia} Mafia Aligned, {others} not",
+            EventKind::TeamAnnounce => "This game: {teams}",
+            EventKind::SetupAnnounce => "This game: {roles}",
+            EventKind::Day => "Day {day_no} has begun!",
+            EventKind::DayStart => "It takes {threshold} votes to elect someone.",
+            EventKind::Vote => "{voter} votes for {ballot}! ({count}/{threshold})",
+            EventKind::NightVote => "{voter} votes at night for {ballot}! ({count}/{threshold})",
+            EventKind::NightElection => "{elected} was voted out during the night!",
+            EventKind::SkipVote => "{voter} votes to skip the day! ({count}/{threshold})",
+            EventKind::GhostVote => "The ghost of {ghost} votes for {ballot}! ({count}/{threshold})",
+            EventKind::Retract => "{voter} retracted their vote for {former}.",
+            EventKind::Reveal => "{celeb} is a CELEB!",
+            EventKind::Election => "The town has elected {ballot}!",
+            EventKind::DaySkipped => "The town has agreed to skip the day...",
+            EventKind::Dusk => {
+                "Dusk has come. {actor} may take one of their voters with them..."
+            }
+            EventKind::Night => "Night {night_no} has begun!",
+            EventKind::NightComplete => "All night actions are in. Resolving the night...",
+            EventKind::Dawn => "Dawn has come...",
+            EventKind::Survived => "{victim} survived an attack!",
+            EventKind::Kill => "{mark} was killed in the night!",
+            EventKind::NoKill => "No one died in the night.",
+            EventKind::Avenged => "{avenger} takes {victim} down with them!",
+            EventKind::Modkill => "{player} has been removed from the game by a moderator.",
+            EventKind::IdleModkill => {
+                "{player} has been removed from the game after {phases} phases without acting."
+            }
+            EventKind::PlayerExcused => {
+                "{player} has been excused from voting today. {threshold} votes are now needed to elect."
+            }
+            EventKind::Eliminate => "{player} has been eliminated. They were {role}.",
+            EventKind::Revived => "{player} has been brought back into the game!",
+            EventKind::HammerGrace => "Night falls in {remaining} seconds.",
+            EventKind::Trial => "{defendant} is on trial! Is the verdict Guilty or Innocent?",
+            EventKind::Verdict => "{juror} finds them {verdict}.",
+            EventKind::Acquitted => "{defendant} is acquitted, {guilty} to {innocent}.",
+            EventKind::TimeWarning => "{remaining} seconds remain!",
+            EventKind::PhaseExtended => "The phase has been extended! {remaining} seconds remain.",
+            EventKind::Paused => "The game has been paused.",
+            EventKind::Resumed => "The game has resumed!",
+            EventKind::End => "The game is over! Winner: {winner}",
+            _ => return None,
+        };
+        Some(template)
+    }
+}
+
+/// Renders an Event as text using the Locale's template for its kind, with each
+/// player written using `name`. Events the Locale has no template for fall back
+/// to their Display.
+pub fn render<U: RawPID>(
+    event: &Event<U>,
+    locale: &impl Locale,
+    name: impl Fn(U) -> String,
+) -> String {
+    let template = match locale.template(&event.kind()) {
+        Some(template) => template,
+        None => return event.to_string(),
+    };
+    fields(event, locale, &name)
+        .into_iter()
+        .fold(template.to_string(), |text, (field, value)| {
+            text.replace(&format!("{{{}}}", field), &value)
+        })
+}
+
+/// The named fields of an Event that a template can refer to
+fn fields<U: RawPID>(
+    event: &Event<U>,
+    locale: &impl Locale,
+    name: &impl Fn(U) -> String,
+) -> Vec<(&'static str, String)> {
+    let term = |term| locale.term(term).to_string();
+    let player = |p: &Player<U>| name(p.user_id);
+    let someone = |p: &Option<Player<U>>| p.as_ref().map_or_else(|| term(Term::Someone), player);
+    let ballot = |b: &Option<Player<U>>| b.as_ref().map_or_else(|| term(Term::Abstain), player);
+    match event {
+        Event::PlayerJoined { player: p } | Event::PlayerLeft { player: p } => {
+            vec![("player", player(p))]
+        }
+        Event::MafiaAnnounce { mafia, others } => {
+            vec![("mafia", mafia.to_string()), ("others", others.to_string())]
+        }
+        Event::TeamAnnounce { team_counts } => {
+            let teams: Vec<String> = team_counts
+                .iter()
+                .map(|(team, count)| format!("{} {}", count, team))
+                .collect();
+            vec![("teams", teams.join(", "))]
+        }
+        Event::SetupAnnounce { role_counts } => {
+            let roles: Vec<String> = role_counts
+                .iter()
+                .map(|(role, count)| format!("{} {}", count, role))
+                .collect();
+            vec![("roles", roles.join(", "))]
+        }
+        Event::Day { day_no, .. } => vec![("day_no", day_no.to_string())],
+        Event::DayStart {
+            day_no, threshold, ..
+        } => vec![
+            ("day_no", day_no.to_string()),
+            ("threshold", threshold.to_string()),
+        ],
+        Event::Vote {
+            voter,
+            ballot: b,
+            count,
+            threshold,
+            ..
+        } => vec![
+            ("voter", someone(voter)),
+            ("ballot", ballot(b)),
+            (
+                "count",
+                count.map_or_else(|| term(Term::Hidden), |c| c.to_string()),
+            ),
+            ("threshold", threshold.to_string()),
+        ],
+        Event::NightVote {
+            voter,
+            ballot: b,
+            count,
+            threshold,
+        } => vec![
+            ("voter", player(voter)),
+            ("ballot", ballot(b)),
+            ("count", count.to_string()),
+            ("threshold", threshold.to_string()),
+        ],
+        Event::NightElection { elected, .. } => vec![("elected", player(elected))],
+        Event::SkipVote {
+            voter,
+            count,
+            threshold,
+        } => vec![
+            ("voter", someone(voter)),
+            ("count", count.to_string()),
+            ("threshold", threshold.to_string()),
+        ],
+        Event::GhostVote {
+            ghost,
+            ballot: b,
+            count,
+            threshold,
+        } => vec![
+            ("ghost", someone(ghost)),
+            ("ballot", ballot(b)),
+            ("count", count.to_string()),
+            ("threshold", threshold.to_string()),
+        ],
+        Event::Retract { voter, former } => vec![
+            ("voter", someone(voter)),
+            (
+                "former",
+                former.as_ref().map_or_else(|| term(Term::Abstain), ballot),
+            ),
+        ],
+        Event::Reveal { celeb } => vec![("celeb", player(celeb))],
+        Event::Election {
+            ballot: b,
+            threshold,
+            ..
+        } => vec![("ballot", ballot(b)), ("threshold", threshold.to_string())],
+        Event::Dusk { actor, .. } => vec![("actor", player(actor))],
+        Event::Night { night_no, .. } => vec![("night_no", night_no.to_string())],
+        Event::Survived { victim } => vec![("victim", player(victim))],
+        Event::Kill { mark, .. } => vec![("mark", player(mark))],
+        Event::Avenged { avenger, victim } => {
+            vec![("avenger", player(avenger)), ("victim", player(victim))]
+        }
+        Event::PlayerExcused {
+            player: p,
+            threshold,
+        } => {
+            vec![("player", player(p)), ("threshold", threshold.to_string())]
+        }
+        Event::Modkill { player: p, role } => {
+            vec![("player", player(p)), ("role", role.to_string())]
+        }
+        Event::IdleModkill { player: p, phases } => {
+            vec![("player", player(p)), ("phases", phases.to_string())]
+        }
+        Event::Eliminate { player: p } => {
+            vec![("player", player(p)), ("role", p.role.to_string())]
+        }
+        Event::Revived { player: p } => vec![("player", player(p))],
+        Event::Trial { defendant } => vec![("defendant", player(defendant))],
+        Event::Verdict { juror, guilty } => vec![
+            ("juror", player(juror)),
+            (
+                "verdict",
+                if *guilty { "Guilty" } else { "Innocent" }.to_string(),
+            ),
+        ],
+        Event::Acquitted {
+            defendant,
+            guilty,
+            innocent,
+        } => vec![
+            ("defendant", player(defendant)),
+            ("guilty", guilty.to_string()),
+            ("innocent", innocent.to_string()),
+        ],
+        Event::TimeWarning { remaining } | Event::HammerGrace { remaining } => {
+            vec![("remaining", remaining.as_secs().to_string())]
+        }
+        Event::PhaseExtended { new_remaining } => {
+            vec![("remaining", new_remaining.as_secs().to_string())]
+        }
+        Event::End { winner, .. } => vec![(
+            "winner",
+            winner.map_or_else(|| term(Term::NoOne), |team| team.to_string()),
+        )],
+        _ => Vec::new(),
+    }
+}
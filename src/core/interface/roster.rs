@@ -0,0 +1,43 @@
+use std::collections::HashMap;
+
+use super::*;
+
+/// A snapshot of who is in a game, taken with `Game::roster()`, so that a Choice
+/// can be checked before it is sent into the game
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Roster<U: RawPID> {
+    pub alive: Vec<U>,
+    pub dead: Vec<U>,
+    /// Who each player with a Target can name with it right now
+    pub legal_targets: HashMap<U, Vec<U>>,
+}
+
+impl<U: RawPID> Roster<U> {
+    /// Checks that every player a Choice names is a living player, and, for a
+    /// Target, one the actor can name
+    pub fn check_choice(
+        &self,
+        actor: U,
+        choice: &Choice<U>,
+        action: ActionKind,
+    ) -> Result<(), ChoiceError<U>> {
+        let chosen = match *choice {
+            Choice::Player(p) => vec![p],
+            Choice::Pair(a, b) => vec![a, b],
+            Choice::Abstain => Vec::new(),
+        };
+        for pid in chosen {
+            if self.dead.contains(&pid) {
+                return Err(ChoiceError::PlayerDead { pid });
+            }
+            if !self.alive.contains(&pid) {
+                return Err(ChoiceError::NoSuchPlayer { pid });
+            }
+            let legal = |targets: &Vec<U>| targets.contains(&pid);
+            if action == ActionKind::Target && !self.legal_targets.get(&actor).is_some_and(legal) {
+                return Err(ChoiceError::IllegalTarget { pid });
+            }
+        }
+        Ok(())
+    }
+}
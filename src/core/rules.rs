@@ -1,3 +1,5 @@
+mod gamerules;
 mod rolegen;
 
+pub use gamerules::*;
 pub use rolegen::*;
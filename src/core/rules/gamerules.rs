@@ -1,23 +1,113 @@
-/// A set of rules that change how the game can be played.
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::default::Default;
+use std::time::Duration;
 
-pub struct GameRules {}
+use crate::core::{Role, Team};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+/// How long Dusk lasts when the dusk_length rule doesn't say
+pub const DUSK_LENGTH: Duration = Duration::from_secs(60);
+
+/// A set of rules that change how the game can be played.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameRules {
+    pub start_night: StartNight,
+    pub start_info: StartInfo,
+    pub election_process: ElectionProcess,
+    /// What happens at the Day's deadline when no ballot holds a majority. Decided
+    /// by the election_process if None
+    pub deadline_behavior: Option<DeadlineBehavior>,
+    pub day_style: DayStyle,
+    pub tie_break: TieBreak,
+    pub election_info: ElectionInfo,
+    pub vote_counts: VoteCounts,
+    pub save_self: SaveSelf,
+    pub save_against: SaveAgainst,
+    /// Whether players can vote at Night. A player with a majority of night votes
+    /// at dawn is eliminated, separately from the Mafia's kill
+    pub night_vote: bool,
+    /// Whether each dead player can cast one more vote during a later Day. It counts
+    /// towards the ballot, but dead players don't count towards the threshold
+    pub ghost_vote: bool,
+    /// Whether dead players can keep talking. Their TeamChat goes to the dead
+    /// and spectators instead of their team
+    pub dead_chat: bool,
+    /// Whether a vote that would elect a player waits for the voter to confirm it
+    /// with ConfirmHammer, so a misclick can't end the Day
+    pub confirm_hammer: bool,
+    pub mafia_knowledge: MafiaKnowledge,
+    pub mafia_kill: MafiaKill,
+    pub idiot_elect: IdiotElect,
+    /// How many times a player with this role can use their night action.
+    /// Unlimited for roles that aren't listed
+    #[serde(with = "role_map")]
+    pub ability_limits: HashMap<Role, usize>,
+    /// Which nights a player with this role can use their night action.
+    /// Every night for roles that aren't listed
+    #[serde(with = "role_map")]
+    pub ability_nights: HashMap<Role, AbilityNights>,
+    /// How long a Day lasts before its deadline. No deadline if None
+    pub day_length: Option<Duration>,
+    /// How long a Night lasts before its deadline. No deadline if None
+    pub night_length: Option<Duration>,
+    /// How long Dusk lasts before anyone who hasn't used their dusk action loses it.
+    /// DUSK_LENGTH if None, so Dusk always ends
+    pub dusk_length: Option<Duration>,
+    /// How long the game waits after an election before the Night begins. The Night
+    /// begins right away if None
+    pub hammer_grace: Option<Duration>,
+    /// Warnings are sent when this much time remains before a deadline
+    pub time_warnings: Vec<Duration>,
+    /// Night actions can't be made or changed once this little time remains before
+    /// the night's deadline. Never locked if None
+    pub action_lock: Option<Duration>,
+    /// A player who makes no action for this many phases in a row is modkilled.
+    /// Never if None
+    pub idle_modkill: Option<usize>,
+    /// The game ends instead of starting a Day after this many. No limit if None
+    pub max_days: Option<usize>,
+    /// Who wins when the game ends because of max_days. A draw if None
+    pub max_days_winner: Option<Team>,
+}
+
+/// Saves a rule for each Role as a list of pairs, since a custom role can't be
+/// the key of a JSON object
+mod role_map {
+    use super::*;
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<V: Serialize, S: Serializer>(
+        map: &HashMap<Role, V>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(map.iter())
+    }
+
+    pub fn deserialize<'de, V: Deserialize<'de>, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<HashMap<Role, V>, D::Error> {
+        let pairs = Vec::<(Role, V)>::deserialize(deserializer)?;
+        Ok(pairs.into_iter().collect())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
 /// At the start of the game, role info revealed includes...
 pub enum StartInfo {
     /// Nothing
     None,
     #[default]
-    /// Number of Mafia Aligned players vs number of Not Mafia Aligned players
+    /// Number of Mafia Aligned players vs number of Not Mafia Aligned players,
+    /// announced with MafiaAnnounce
     Mafia,
-    /// Number of Town Aligned, Mafia Aligned, and Rogue Unaligned players
+    /// Number of Town Aligned, Mafia Aligned, and Rogue Unaligned players (and the
+    /// Cult, if there is one), announced with TeamAnnounce
     Team,
-    /// Number of each Role
+    /// Number of each Role, announced with SetupAnnounce
     Role,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
 /// The game starts in Night Phase...
 pub enum StartNight {
     /// Always
@@ -43,12 +133,13 @@ pub enum DeathReveal {
     Role,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+/// Who voted for whom is...
 pub enum ElectionInfo {
     #[default]
     /// Votes are announced immediately in public
     Public,
-    /// Votes are cast in private and revealed at the end of the day
+    /// Votes are announced without the voter, and revealed in full at the end of the day
     Revealed,
     /// Votes are cast in private the number of votes for each option
     /// are revealed at the end of the day
@@ -57,7 +148,38 @@ pub enum ElectionInfo {
     Secret,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+/// The number of votes for an option is announced with each vote...
+pub enum VoteCounts {
+    #[default]
+    /// Always
+    Exact,
+    /// Only once the option is one vote short of being elected, or more
+    NearThreshold,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+/// Each night, the Mafia's kill is...
+pub enum MafiaKill {
+    #[default]
+    /// Up to them. They can Mark `Abstain` to kill no one
+    Optional,
+    /// Required. A Mark of `Abstain` is rejected
+    Mandatory,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+/// When a player reaches the threshold in a Dynamic election...
+pub enum DayStyle {
+    #[default]
+    /// They are elected
+    DirectLynch,
+    /// They are put on Trial, and every other player gives a Verdict. More Guilty
+    /// than Innocent verdicts elects them, and otherwise the Day goes on
+    NominateTrial,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
 pub enum ElectionProcess {
     #[default]
     /// Votes can be cast at any time. When a majority is reached, it causes an election
@@ -68,6 +190,74 @@ pub enum ElectionProcess {
     /// These is one scheduled election end time. If a majority exists at that time,
     /// is causes an election. If not, day ends with no election.
     SingleStatic,
+    /// There is one scheduled election end time. The option with the most votes at
+    /// that time is elected, with ties settled by the TieBreak rule.
+    Plurality,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// When the Day's deadline passes and no ballot holds a majority...
+pub enum DeadlineBehavior {
+    /// The Day ends with no one elected
+    NoLynch,
+    /// The option with the most votes is elected, with ties settled by the TieBreak rule
+    Plurality,
+    /// The Day carries on, with its votes standing, until the next deadline
+    CarryOver,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// A role's night action can only be used on...
+pub enum AbilityNights {
+    /// Nights 1, 3, 5, ...
+    Odd,
+    /// Nights 2, 4, 6, ...
+    Even,
+}
+
+impl AbilityNights {
+    pub fn allows(&self, night_no: usize) -> bool {
+        match self {
+            Self::Odd => !night_no.is_multiple_of(2),
+            Self::Even => night_no.is_multiple_of(2),
+        }
+    }
+}
+
+impl GameRules {
+    /// Whether a role's night action is resting on this night, under these rules
+    pub fn on_cooldown(&self, role: &Role, night_no: usize) -> bool {
+        self.ability_nights
+            .get(role)
+            .is_some_and(|nights| !nights.allows(night_no))
+    }
+
+    /// What happens at the Day's deadline without a majority, under these rules
+    pub fn deadline_behavior(&self) -> DeadlineBehavior {
+        match (self.deadline_behavior, self.election_process) {
+            (Some(behavior), _) => behavior,
+            (None, ElectionProcess::Static) => DeadlineBehavior::CarryOver,
+            (None, ElectionProcess::Plurality) => DeadlineBehavior::Plurality,
+            (None, ElectionProcess::Dynamic | ElectionProcess::SingleStatic) => {
+                DeadlineBehavior::NoLynch
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+/// When options are tied for the most votes at the end of a Plurality election,
+/// or more than one option holds enough votes to be elected at once...
+pub enum TieBreak {
+    #[default]
+    /// No one is elected
+    NoElection,
+    /// One of the tied options is picked at random, using the given seed
+    Random(u64),
+    /// The tied option that reached its number of votes first is elected
+    FirstVoted,
+    /// The day continues until a moderator chooses one of the tied options
+    ModChoice,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
@@ -94,7 +284,17 @@ pub enum StripNotify {
     Always,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+/// When the game starts, each Mafia Aligned player...
+pub enum MafiaKnowledge {
+    #[default]
+    /// Learns who the rest of the Mafia are
+    Known,
+    /// Only learns their own role, and can't talk to the rest of the Mafia
+    Blind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
 /// When can a DOCTOR save themself?
 pub enum SaveSelf {
     #[default]
@@ -107,6 +307,16 @@ pub enum SaveSelf {
     Never,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+/// A DOCTOR's save protects their patient against...
+pub enum SaveAgainst {
+    #[default]
+    /// Every kill that night, whether by the Mafia or a VIGILANTE
+    AnyKill,
+    /// Only the Mafia's kill
+    MafiaKill,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 /// Upon a successful save, where one or more DOCTORS save the Mafia killer's mark...
 pub enum SaveInfo {
@@ -121,7 +331,7 @@ pub enum SaveInfo {
     None,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
 /// Upon Eleting and IDIOT...
 pub enum IdiotElect {
     /// Nothing special happens
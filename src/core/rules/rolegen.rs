@@ -308,12 +308,9 @@ pub fn get_players<U: RawPID>(
     for (user, rolegen) in pairs.iter() {
         match rolegen.team() {
             Team::Mafia => mafia.push(user.clone()),
-            Team::Town | Team::Rogue => non_mafia.push(user.clone()),
+            Team::Town | Team::Rogue | Team::Cult => non_mafia.push(user.clone()),
         }
-        players.push(Player {
-            user_id: user.clone(),
-            role: rolegen.clone().into(),
-        });
+        players.push(Player::new(user.clone(), rolegen.clone().into()));
     }
     let mut contracts = Vec::new();
     for (holder, rolegen) in pairs {
@@ -2,6 +2,8 @@
 
 use std::sync::mpsc;
 use std::sync::mpsc::{Receiver, Sender};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
 use super::*;
 
@@ -56,6 +58,13 @@ fn expect_eventkind(rx: &Receiver<Event<u64>>, kind: EventKind) {
     assert_eq!(event.kind(), kind);
 }
 
+// Every player is privately sent their role after Start
+fn expect_role_pms(rx: &Receiver<Event<u64>>, n_players: usize) {
+    for _ in 0..n_players {
+        expect_eventkind(rx, EventKind::YourRole);
+    }
+}
+
 #[test]
 fn invalid_votes() {
     let (mut game, rx) = create_basic_game_1();
@@ -64,6 +73,8 @@ fn invalid_votes() {
     // Read off event queue and expect Day Phase
     expect_eventkind(&rx, EventKind::Init);
     expect_eventkind(&rx, EventKind::Start);
+    expect_eventkind(&rx, EventKind::MafiaAnnounce);
+    expect_role_pms(&rx, 5);
     expect_eventkind(&rx, EventKind::Day);
 
     assert!(
@@ -88,6 +99,8 @@ fn invalid_votes() {
     assert!(game.start().is_ok());
     expect_eventkind(&rx, EventKind::Init);
     expect_eventkind(&rx, EventKind::Start);
+    expect_eventkind(&rx, EventKind::MafiaAnnounce);
+    expect_role_pms(&rx, 4);
     expect_eventkind(&rx, EventKind::Night);
 
     assert!(
@@ -99,3 +112,4461 @@ fn invalid_votes() {
         "Invalid phase, should fail"
     );
 }
+
+fn vote(game: &mut Game<u64>, voter: u64, ballot: u64) {
+    assert!(game
+        .handle(Action::Vote {
+            voter,
+            ballot: Some(Choice::Player(ballot))
+        })
+        .is_ok());
+}
+
+#[test]
+fn deadline_election() {
+    let (mut game, rx) = create_basic_game_1();
+    game.rules.election_process = ElectionProcess::SingleStatic;
+
+    assert!(game.start().is_ok());
+    expect_eventkind(&rx, EventKind::Init);
+    expect_eventkind(&rx, EventKind::Start);
+    expect_eventkind(&rx, EventKind::MafiaAnnounce);
+    expect_role_pms(&rx, 5);
+    expect_eventkind(&rx, EventKind::Day);
+    expect_eventkind(&rx, EventKind::DayStart);
+
+    for voter in [101, 102, 103] {
+        vote(&mut game, voter, 104);
+        expect_eventkind(&rx, EventKind::VoteCast);
+        expect_eventkind(&rx, EventKind::Vote);
+    }
+    // Majority was reached, but there is no hammer before the deadline
+    assert!(rx.try_recv().is_err(), "No election before the deadline");
+    assert_eq!(game.phase.kind(), PhaseKind::Day);
+
+    assert!(game.resolve_day_at_deadline().is_ok());
+    expect_eventkind(&rx, EventKind::Election);
+    expect_eventkind(&rx, EventKind::Eliminate);
+    expect_eventkind(&rx, EventKind::End);
+}
+
+#[test]
+fn deadline_no_majority() {
+    let (mut game, rx) = create_basic_game_1();
+    game.rules.election_process = ElectionProcess::Static;
+    assert!(game.start().is_ok());
+    vote(&mut game, 101, 104);
+    vote(&mut game, 102, 104);
+    while rx.try_recv().is_ok() {}
+
+    // Static elections carry on to the next scheduled end time
+    assert!(game.resolve_day_at_deadline().is_ok());
+    assert!(rx.try_recv().is_err());
+    assert_eq!(game.phase.kind(), PhaseKind::Day);
+
+    // A single scheduled end time ends the day without an election
+    game.rules.election_process = ElectionProcess::SingleStatic;
+    assert!(game.resolve_day_at_deadline().is_ok());
+    expect_eventkind(&rx, EventKind::Election);
+    expect_eventkind(&rx, EventKind::Night);
+}
+
+fn create_game(players: Vec<Player<u64>>) -> (Game<u64>, Receiver<Event<u64>>) {
+    let (tx, rx): (Sender<Event<u64>>, Receiver<Event<u64>>) = mpsc::channel();
+    let game = Game::new(1, players, Vec::new(), Comm::new(&tx));
+    (game, rx)
+}
+
+fn target(
+    game: &mut Game<u64>,
+    actor: u64,
+    target: Choice<u64>,
+) -> Result<(), InvalidActionError<u64>> {
+    game.handle(Action::Target { actor, target })
+}
+
+#[test]
+fn pair_targets() {
+    let (mut game, rx) = create_game(vec![
+        Player::new(101, Role::TOWN),
+        Player::new(102, Role::COP),
+        Player::new(103, Role::BUSDRIVER),
+        Player::new(104, Role::MAFIA),
+    ]);
+    assert!(game.start().is_ok());
+
+    assert!(matches!(
+        target(&mut game, 103, Choice::Player(101)),
+        Err(InvalidActionError::InvalidChoice { .. })
+    ));
+    assert!(matches!(
+        target(&mut game, 102, Choice::Pair(101, 103)),
+        Err(InvalidActionError::InvalidChoice { .. })
+    ));
+    assert!(matches!(
+        target(&mut game, 103, Choice::Pair(101, 404)),
+        Err(InvalidActionError::PlayerNotFound { pid: 404 })
+    ));
+    assert!(matches!(
+        target(&mut game, 101, Choice::Player(102)),
+        Err(InvalidActionError::InvalidRole { .. })
+    ));
+
+    assert!(target(&mut game, 103, Choice::Pair(101, 102)).is_ok());
+    match &game.phase {
+        Phase::Night(night) => assert_eq!(night.targets.get(&2), Some(&Target::Swap(0, 1))),
+        phase => panic!("Expected Night, found {}", phase),
+    }
+    while rx.try_recv().is_ok() {}
+
+    // The Mafia's kill on 101 is driven to 102
+    assert!(target(&mut game, 102, Choice::Player(104)).is_ok());
+    assert!(game
+        .handle(Action::Mark {
+            killer: 104,
+            mark: Choice::Player(101)
+        })
+        .is_ok());
+    let killed = rx.try_iter().find_map(|e| match e {
+        Event::Kill { mark, .. } => Some(mark.user_id),
+        _ => None,
+    });
+    assert_eq!(killed, Some(102));
+}
+
+fn mark(game: &mut Game<u64>, killer: u64, mark: Choice<u64>) {
+    assert!(game.handle(Action::Mark { killer, mark }).is_ok());
+}
+
+#[test]
+fn cult_conversion() {
+    let (mut game, rx) = create_game(vec![
+        Player::new(101, Role::CULTLEADER),
+        Player::new(102, Role::TOWN),
+        Player::new(103, Role::TOWN),
+        Player::new(104, Role::MAFIA),
+    ]);
+    assert!(game.start().is_ok());
+    while rx.try_recv().is_ok() {}
+
+    assert!(target(&mut game, 101, Choice::Player(102)).is_ok());
+    mark(&mut game, 104, Choice::Player(103));
+
+    let events: Vec<_> = rx.try_iter().collect();
+    assert!(events.iter().any(|e| matches!(
+        e,
+        Event::Converted { convert, .. } if convert.user_id == 102 && convert.role == Role::CULTIST
+    )));
+    // With one TOWN dead and one converted, the Cult outnumbers everyone else
+    assert!(matches!(game.phase, Phase::End(Some(Team::Cult), _)));
+}
+
+#[test]
+fn cult_conversion_immunity() {
+    let (mut game, rx) = create_game(vec![
+        Player::new(101, Role::CULTLEADER),
+        Player::new(102, Role::COP),
+        Player::new(103, Role::TOWN),
+        Player::new(104, Role::MAFIA),
+    ]);
+    assert!(game.start().is_ok());
+
+    assert!(target(&mut game, 101, Choice::Player(102)).is_ok());
+    assert!(target(&mut game, 102, Choice::Player(104)).is_ok());
+    mark(&mut game, 104, Choice::Abstain);
+
+    assert!(rx.try_iter().all(|e| e.kind() != EventKind::Converted));
+    assert_eq!(game.players[1].role, Role::COP);
+    assert_eq!(game.phase.kind(), PhaseKind::Day);
+}
+
+fn expect_night_start(rx: &Receiver<Event<u64>>, expected: &[u64]) {
+    // Each actor is prompted privately, with nothing about the other actors
+    let actors: Vec<u64> = rx
+        .try_iter()
+        .filter_map(|e| match &e {
+            Event::NightStart { actor, .. } => {
+                assert_eq!(e.audience(), Audience::Player(actor.user_id));
+                Some(actor.user_id)
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(actors, expected);
+}
+
+#[test]
+fn night_start_actors() {
+    let (mut game, rx) = create_basic_game_2();
+    assert!(game.start().is_ok());
+    expect_night_start(&rx, &[102, 103]);
+
+    let (mut game, rx) = create_basic_game_1();
+    assert!(game.start().is_ok());
+    for voter in [101, 103, 104] {
+        vote(&mut game, voter, 102);
+    }
+    // The COP was elected, leaving only the DOCTOR to act
+    expect_night_start(&rx, &[103]);
+}
+
+fn expect_day_start(rx: &Receiver<Event<u64>>) -> (usize, usize) {
+    rx.try_iter()
+        .find_map(|e| match e {
+            Event::DayStart {
+                alive, threshold, ..
+            } => Some((alive.len(), threshold)),
+            _ => None,
+        })
+        .expect("DayStart should be sent")
+}
+
+#[test]
+fn day_start_threshold() {
+    let (mut game, rx) = create_basic_game_1();
+    assert!(game.start().is_ok());
+    assert_eq!(expect_day_start(&rx), (5, 3));
+
+    let (mut game, rx) = create_basic_game_2();
+    assert!(game.start().is_ok());
+    assert!(target(&mut game, 102, Choice::Player(104)).is_ok());
+    assert!(target(&mut game, 103, Choice::Player(103)).is_ok());
+    mark(&mut game, 104, Choice::Player(101));
+    // 4 players would need 3 votes, but one was killed in the night
+    assert_eq!(expect_day_start(&rx), (3, 2));
+}
+
+#[test]
+fn start_phase_rule() {
+    for (start_night, odd, even) in [
+        (StartNight::Even, PhaseKind::Day, PhaseKind::Night),
+        (StartNight::Always, PhaseKind::Night, PhaseKind::Night),
+        (StartNight::Never, PhaseKind::Day, PhaseKind::Day),
+    ] {
+        let (mut game, _rx) = create_basic_game_1();
+        game.rules.start_night = start_night;
+        assert!(game.start().is_ok());
+        assert_eq!(game.phase.kind(), odd, "{:?} with 5 players", start_night);
+
+        let (mut game, _rx) = create_basic_game_2();
+        game.rules.start_night = start_night;
+        assert!(game.start().is_ok());
+        assert_eq!(game.phase.kind(), even, "{:?} with 4 players", start_night);
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+struct MockTransport {
+    events: std::sync::Arc<std::sync::Mutex<Vec<Event<u64>>>>,
+}
+
+impl Transport<u64> for MockTransport {
+    fn tx(&self, event: Event<u64>) -> Result<(), SendError> {
+        self.events.lock().unwrap().push(event);
+        Ok(())
+    }
+}
+
+#[test]
+fn mock_transport() {
+    let transport = MockTransport::default();
+    let players = vec![
+        Player::new(101, Role::TOWN),
+        Player::new(102, Role::TOWN),
+        Player::new(103, Role::MAFIA),
+    ];
+    let mut game = Game::new(
+        1,
+        players,
+        Vec::new(),
+        Comm::with_transport(transport.clone()),
+    );
+    assert!(game.start().is_ok());
+
+    let kinds: Vec<_> = transport
+        .events
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|e| e.kind())
+        .filter(|kind| *kind != EventKind::YourRole)
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            EventKind::Init,
+            EventKind::Start,
+            EventKind::MafiaAnnounce,
+            EventKind::Day,
+            EventKind::DayStart
+        ]
+    );
+}
+
+#[derive(Debug, Default, Clone)]
+struct EnvelopeTransport {
+    envelopes: std::sync::Arc<std::sync::Mutex<Vec<Envelope<u64>>>>,
+}
+
+impl Transport<u64> for EnvelopeTransport {
+    fn tx(&self, _event: Event<u64>) -> Result<(), SendError> {
+        unreachable!("Comm always sends envelopes")
+    }
+    fn tx_envelope(&self, envelope: Envelope<u64>) -> Result<(), SendError> {
+        self.envelopes.lock().unwrap().push(envelope);
+        Ok(())
+    }
+}
+
+#[test]
+fn event_sequence_numbers() {
+    let transport = EnvelopeTransport::default();
+    let public = EnvelopeTransport::default();
+    let mut comm = Comm::with_transport(transport.clone());
+    comm.subscribe_audience(public.clone(), Audience::Public);
+    let players = vec![
+        Player::new(101, Role::TOWN),
+        Player::new(102, Role::TOWN),
+        Player::new(103, Role::TOWN),
+        Player::new(104, Role::MAFIA),
+    ];
+    let mut game = Game::new(1, players, Vec::new(), comm);
+    assert!(game.start().is_ok());
+    assert!(game.handle(Action::MyRole { player: 101 }).is_ok());
+    assert!(game.handle(Action::Phase { from: 102 }).is_ok());
+
+    let seqs: Vec<u64> = transport
+        .envelopes
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|e| e.seq)
+        .collect();
+    let expected: Vec<u64> = (1..=seqs.len() as u64).collect();
+    assert_eq!(seqs, expected);
+
+    // A subscriber sees the same number for each Event, skipping the ones it isn't sent
+    let envelopes = transport.envelopes.lock().unwrap();
+    for envelope in public.envelopes.lock().unwrap().iter() {
+        assert_eq!(envelopes[envelope.seq as usize - 1], *envelope);
+    }
+    assert!(public.envelopes.lock().unwrap().len() < envelopes.len());
+}
+
+#[test]
+fn resync_after_reconnect() {
+    let (mut game, rx) = create_basic_game_1();
+    assert!(game.start().is_ok());
+    let last_seen = rx.try_iter().count() as u64;
+    drop(rx);
+
+    // These are missed while no one is listening
+    assert!(game.handle(Action::MyRole { player: 101 }).is_ok());
+    assert!(game.handle(Action::Phase { from: 102 }).is_ok());
+
+    let (tx, rx) = mpsc::channel();
+    game.reconnect(tx);
+    let resync = Action::Resync {
+        from: 101,
+        after_seq: last_seen,
+    };
+    assert!(game.handle(resync).is_ok());
+    let kinds: Vec<_> = rx.try_iter().map(|e| e.kind()).collect();
+    assert_eq!(kinds, vec![EventKind::YourRole, EventKind::Phase]);
+}
+
+#[test]
+fn sequence_numbers_after_load() {
+    let fname = temp_path("sequence_numbers_test.json");
+    let fname = fname.to_str().unwrap();
+
+    let (mut game, rx) = create_basic_game_1();
+    assert!(game.start().is_ok());
+    let last_seq = rx.try_iter().count() as u64;
+    assert!(game.save_game(fname).is_ok());
+
+    // The loaded game numbers its Events after the ones sent before the save,
+    // so a Resync from before it can't be mistaken for one that is up to date
+    let transport = EnvelopeTransport::default();
+    let mut loaded = Game::<u64>::load_game(
+        fname,
+        SaveFormat::PrettyJson,
+        Comm::with_transport(transport.clone()),
+    )
+    .unwrap();
+    assert!(loaded.handle(Action::MyRole { player: 101 }).is_ok());
+    let seqs: Vec<u64> = transport
+        .envelopes
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|e| e.seq)
+        .collect();
+    assert_eq!(seqs, vec![last_seq + 1]);
+    let resync = Action::Resync {
+        from: 101,
+        after_seq: last_seq - 1,
+    };
+    assert!(matches!(
+        loaded.handle(resync),
+        Err(InvalidActionError::ResyncTooOld { .. })
+    ));
+    std::fs::remove_file(fname).unwrap();
+}
+
+#[test]
+fn resync_too_old() {
+    let (tx, rx): (Sender<Event<u64>>, Receiver<Event<u64>>) = mpsc::channel();
+    let comm = Comm::new(&tx).with_buffer_size(2);
+    let players = vec![
+        Player::new(101, Role::TOWN),
+        Player::new(102, Role::TOWN),
+        Player::new(103, Role::MAFIA),
+    ];
+    let mut game = Game::new(1, players, Vec::new(), comm);
+    assert!(game.start().is_ok());
+    let last_seq = rx.try_iter().count() as u64;
+
+    let resync = |after_seq| Action::Resync {
+        from: 101,
+        after_seq,
+    };
+    assert!(matches!(
+        game.handle(resync(0)),
+        Err(InvalidActionError::ResyncTooOld { oldest }) if oldest == last_seq - 1
+    ));
+    assert_eq!(rx.try_iter().count(), 0);
+    assert!(game.handle(resync(last_seq - 2)).is_ok());
+    assert_eq!(rx.try_iter().count(), 2);
+}
+
+#[test]
+fn history_size() {
+    let (tx, rx): (Sender<Event<u64>>, Receiver<Event<u64>>) = mpsc::channel();
+    let comm = Comm::new(&tx).with_history_size(2);
+    for paused in [true, false, true] {
+        comm.send(match paused {
+            true => Event::Paused,
+            false => Event::Resumed,
+        });
+    }
+    assert_eq!(rx.try_iter().count(), 3);
+    assert_eq!(comm.history(), vec![Event::Resumed, Event::Paused]);
+}
+
+#[test]
+fn dropped_receiver() {
+    let (mut game, rx) = create_basic_game_1();
+    assert!(game.start().is_ok());
+    drop(rx);
+
+    // Each query sends one Event that can't be delivered
+    for _ in 0..DISCONNECT_AFTER {
+        assert!(game.handle(Action::MyRole { player: 101 }).is_ok());
+    }
+    let result = game.handle(Action::Vote {
+        voter: 101,
+        ballot: Some(Choice::Player(104)),
+    });
+    assert!(matches!(result, Err(InvalidActionError::Disconnected)));
+}
+
+// Sets up a Plurality election where 104 and 105 are tied with two votes each.
+// 104 was voted for first, but 105 reached two votes first.
+fn tied_game(tie_break: TieBreak) -> (Game<u64>, Receiver<Event<u64>>) {
+    let (mut game, rx) = create_basic_game_1();
+    game.rules.election_process = ElectionProcess::Plurality;
+    game.rules.tie_break = tie_break;
+    assert!(game.start().is_ok());
+    vote(&mut game, 101, 104);
+    vote(&mut game, 102, 105);
+    vote(&mut game, 103, 105);
+    vote(&mut game, 105, 104);
+    while rx.try_recv().is_ok() {}
+
+    assert!(game.resolve_day_at_deadline().is_ok());
+    expect_eventkind(&rx, EventKind::Tie);
+    (game, rx)
+}
+
+fn expect_elected(rx: &Receiver<Event<u64>>) -> Option<u64> {
+    rx.try_iter()
+        .find_map(|e| match e {
+            Event::Election { ballot, .. } => Some(ballot.map(|p| p.user_id)),
+            _ => None,
+        })
+        .expect("Election should occur")
+}
+
+#[test]
+fn plurality_election() {
+    let (mut game, rx) = create_basic_game_1();
+    game.rules.election_process = ElectionProcess::Plurality;
+    assert!(game.start().is_ok());
+    vote(&mut game, 101, 104);
+    vote(&mut game, 102, 104);
+    vote(&mut game, 103, 105);
+    assert!(game.resolve_day_at_deadline().is_ok());
+    assert_eq!(expect_elected(&rx), Some(104));
+}
+
+#[test]
+fn tie_break_rules() {
+    let (game, rx) = tied_game(TieBreak::NoElection);
+    assert_eq!(expect_elected(&rx), None);
+    assert_eq!(game.phase.kind(), PhaseKind::Night);
+
+    let (_, rx) = tied_game(TieBreak::FirstVoted);
+    assert_eq!(expect_elected(&rx), Some(105));
+
+    let (_, rx) = tied_game(TieBreak::Random(7));
+    let elected = expect_elected(&rx);
+    assert!(matches!(elected, Some(104) | Some(105)));
+    let (_, rx) = tied_game(TieBreak::Random(7));
+    assert_eq!(expect_elected(&rx), elected, "Same seed, same result");
+
+    let (mut game, rx) = tied_game(TieBreak::ModChoice);
+    assert!(rx.try_recv().is_err());
+    assert_eq!(game.phase.kind(), PhaseKind::Day);
+    assert!(game.resolve_tie(Choice::Player(101)).is_err());
+    assert!(game.resolve_tie(Choice::Player(104)).is_ok());
+    assert_eq!(expect_elected(&rx), Some(104));
+}
+
+// Sets up 4 players where both Abstain and SkipDay hold the 2 votes they need at
+// the deadline. SkipDay was voted for first, but Abstain reached two votes first.
+fn double_majority(tie_break: TieBreak) -> (Game<u64>, Receiver<Event<u64>>) {
+    let (mut game, rx) = create_basic_game_2();
+    game.rules.start_night = StartNight::Never;
+    game.rules.election_process = ElectionProcess::Static;
+    game.rules.tie_break = tie_break;
+    assert!(game.start().is_ok());
+    let abstain = |voter| Action::Vote {
+        voter,
+        ballot: Some(Choice::Abstain),
+    };
+    assert!(game.handle(Action::SkipDay { voter: 101 }).is_ok());
+    assert!(game.handle(abstain(102)).is_ok());
+    assert!(game.handle(abstain(103)).is_ok());
+    assert!(game.handle(Action::SkipDay { voter: 104 }).is_ok());
+    rx.try_iter().for_each(drop);
+
+    assert!(game.resolve_day_at_deadline().is_ok());
+    (game, rx)
+}
+
+#[test]
+fn double_majority_tie_break() {
+    // Abstain reached its two votes first, so it is elected rather than the Skip
+    let (_game, rx) = double_majority(TieBreak::FirstVoted);
+    expect_eventkind(&rx, EventKind::Tie);
+    let kinds: Vec<_> = rx.try_iter().map(|e| e.kind()).collect();
+    assert!(kinds.contains(&EventKind::Election));
+    assert!(!kinds.contains(&EventKind::DaySkipped));
+
+    let (game, rx) = double_majority(TieBreak::ModChoice);
+    expect_eventkind(&rx, EventKind::Tie);
+    assert!(rx.try_recv().is_err(), "The day waits for a moderator");
+    match &game.phase {
+        Phase::Day(day) => assert_eq!(day.tied, vec![Ballot::Skip, Ballot::Abstain]),
+        phase => panic!("Expected Day, found {}", phase),
+    }
+}
+
+fn secs(s: u64) -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_secs(s)
+}
+
+/// A path in the temp directory that no other test run uses, so runs at the same
+/// time don't overwrite each other's saves
+fn temp_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("mafiabot_{}_{}", std::process::id(), name))
+}
+
+#[test]
+fn time_warnings() {
+    let (mut game, rx) = create_basic_game_1();
+    game.rules.day_length = Some(Duration::from_secs(60));
+    game.rules.time_warnings = vec![Duration::from_secs(10), Duration::from_secs(30)];
+    assert!(game.start().is_ok());
+    while rx.try_recv().is_ok() {}
+
+    let warnings = |rx: &Receiver<Event<u64>>| -> Vec<u64> {
+        rx.try_iter()
+            .filter_map(|e| match e {
+                Event::TimeWarning { remaining } => Some(remaining.as_secs()),
+                _ => None,
+            })
+            .collect()
+    };
+
+    game.tick(secs(0));
+    game.tick(secs(20));
+    assert!(warnings(&rx).is_empty());
+    game.tick(secs(35));
+    assert_eq!(warnings(&rx), vec![30]);
+    game.tick(secs(40));
+    assert!(warnings(&rx).is_empty());
+    game.tick(secs(55));
+    assert_eq!(warnings(&rx), vec![10]);
+    assert_eq!(game.phase.kind(), PhaseKind::Day);
+
+    // No one was elected before the deadline
+    game.tick(secs(60));
+    assert_eq!(expect_elected(&rx), None);
+    assert_eq!(game.phase.kind(), PhaseKind::Night);
+
+    // Night has no deadline
+    game.tick(secs(1000));
+    assert_eq!(game.phase.kind(), PhaseKind::Night);
+}
+
+#[test]
+fn night_deadline() {
+    let (mut game, rx) = create_basic_game_2();
+    game.rules.night_length = Some(Duration::from_secs(60));
+    game.rules.time_warnings = vec![Duration::from_secs(10), Duration::from_secs(30)];
+    assert!(game.start().is_ok());
+    mark(&mut game, 104, Choice::Player(101));
+    while rx.try_recv().is_ok() {}
+
+    // Skipping ahead sends every warning, in order, before resolving the night
+    game.tick(secs(0));
+    game.tick(secs(60));
+    let kinds: Vec<_> = rx.try_iter().map(|e| e.kind()).collect();
+    assert_eq!(
+        kinds[..3],
+        [
+            EventKind::TimeWarning,
+            EventKind::TimeWarning,
+            EventKind::Dawn
+        ]
+    );
+    assert!(kinds.contains(&EventKind::Kill));
+    assert_eq!(game.phase.kind(), PhaseKind::Day);
+}
+
+const MODERATOR: u64 = 900;
+
+#[test]
+fn pause_and_resume() {
+    let (mut game, rx) = create_basic_game_1();
+    game.moderators.push(MODERATOR);
+    game.rules.day_length = Some(Duration::from_secs(60));
+    assert!(game.start().is_ok());
+    game.tick(secs(0));
+    while rx.try_recv().is_ok() {}
+
+    assert!(matches!(
+        game.handle(Action::Pause { moderator: 101 }),
+        Err(InvalidActionError::NotModerator { pid: 101 })
+    ));
+    assert!(game
+        .handle(Action::Pause {
+            moderator: MODERATOR
+        })
+        .is_ok());
+    expect_eventkind(&rx, EventKind::Paused);
+
+    assert!(matches!(
+        game.handle(Action::Vote {
+            voter: 101,
+            ballot: Some(Choice::Player(104))
+        }),
+        Err(InvalidActionError::Paused)
+    ));
+
+    // The deadline doesn't pass while paused
+    game.tick(secs(100));
+    assert_eq!(game.phase.kind(), PhaseKind::Day);
+
+    assert!(game
+        .handle(Action::Resume {
+            moderator: MODERATOR
+        })
+        .is_ok());
+    expect_eventkind(&rx, EventKind::Resumed);
+    vote(&mut game, 101, 104);
+    expect_eventkind(&rx, EventKind::VoteCast);
+    expect_eventkind(&rx, EventKind::Vote);
+
+    game.tick(secs(130));
+    assert_eq!(game.phase.kind(), PhaseKind::Day);
+    game.tick(secs(160));
+    assert_eq!(game.phase.kind(), PhaseKind::Night);
+}
+
+#[test]
+fn pause_only_holds_paused_time() {
+    let mut timer = PhaseTimer::new(secs(0), Duration::from_secs(60), &[]);
+    timer.advance(secs(20));
+
+    // The time between the last tick and the pause still counts down
+    timer.pause(secs(30));
+    timer.hold(secs(40));
+    timer.hold(secs(100));
+    timer.resume(secs(110));
+    timer.advance(secs(120));
+    assert_eq!(timer.remaining(secs(120)), Duration::from_secs(20));
+}
+
+#[test]
+fn role_target_constraints() {
+    let (mut game, _rx) = create_game(vec![
+        Player::new(101, Role::CULTLEADER),
+        Player::new(102, Role::COP),
+        Player::new(103, Role::DOCTOR),
+        Player::new(104, Role::BUSDRIVER),
+        Player::new(105, Role::TOWN),
+        Player::new(106, Role::MAFIA),
+    ]);
+    game.rules.save_self = SaveSelf::Never;
+    assert!(game.start().is_ok());
+
+    let invalid = |result| matches!(result, Err(InvalidActionError::InvalidTarget { .. }));
+    assert!(invalid(target(&mut game, 102, Choice::Player(102))));
+    assert!(invalid(target(&mut game, 101, Choice::Player(101))));
+    assert!(invalid(target(&mut game, 103, Choice::Player(103))));
+    assert!(invalid(target(&mut game, 104, Choice::Pair(105, 105))));
+
+    assert!(target(&mut game, 102, Choice::Player(106)).is_ok());
+    assert!(target(&mut game, 103, Choice::Player(102)).is_ok());
+    assert!(target(&mut game, 104, Choice::Pair(104, 105)).is_ok());
+}
+
+#[test]
+fn doctor_save_self_rule() {
+    // The DOCTOR saves themself from the mafia kill
+    let (mut game, rx) = create_basic_game_2();
+    assert!(game.start().is_ok());
+    assert!(target(&mut game, 103, Choice::Player(103)).is_ok());
+    assert!(target(&mut game, 102, Choice::Player(101)).is_ok());
+    mark(&mut game, 104, Choice::Player(103));
+    assert!(rx.try_iter().any(|e| e.kind() == EventKind::Save));
+    assert!(game.players.check(103).is_ok());
+    assert!(game.in_day());
+
+    // Without the rule, the DOCTOR can only save someone else
+    let (mut game, _rx) = create_basic_game_2();
+    game.rules.save_self = SaveSelf::Never;
+    assert!(game.start().is_ok());
+    assert!(matches!(
+        target(&mut game, 103, Choice::Player(103)),
+        Err(InvalidActionError::InvalidTarget { .. })
+    ));
+    assert!(target(&mut game, 103, Choice::Player(101)).is_ok());
+    assert!(target(&mut game, 102, Choice::Player(101)).is_ok());
+    mark(&mut game, 104, Choice::Player(103));
+    assert!(game.players.check(103).is_err());
+}
+
+#[test]
+fn action_confirmed_is_private() {
+    let (mut game, rx) = create_basic_game_2();
+    assert!(game.start().is_ok());
+    rx.try_iter().for_each(drop);
+
+    assert!(target(&mut game, 102, Choice::Player(104)).is_ok());
+    let event = rx.try_recv().unwrap();
+    assert_eq!(event.kind(), EventKind::ActionConfirmed);
+    assert_eq!(event.audience(), Audience::Player(102));
+
+    mark(&mut game, 104, Choice::Player(101));
+    assert_eq!(
+        rx.try_recv().unwrap().audience(),
+        Audience::Team(Team::Mafia)
+    );
+}
+
+#[test]
+fn repeated_vote() {
+    let (mut game, rx) = create_basic_game_1();
+    assert!(game.start().is_ok());
+    rx.try_iter().for_each(drop);
+
+    vote(&mut game, 101, 104);
+    expect_eventkind(&rx, EventKind::VoteCast);
+    expect_eventkind(&rx, EventKind::Vote);
+    vote(&mut game, 101, 104);
+    expect_eventkind(&rx, EventKind::AlreadyVoted);
+    assert!(rx.try_recv().is_err());
+
+    vote(&mut game, 101, 105);
+    expect_eventkind(&rx, EventKind::VoteCast);
+    match rx.try_recv() {
+        Ok(Event::Vote {
+            ballot,
+            former,
+            count,
+            ..
+        }) => {
+            assert_eq!(ballot.map(|p| p.user_id), Some(105));
+            assert_eq!(former, Some(Some(Player::new(104, Role::MAFIA))));
+            assert_eq!(count, Some(1));
+        }
+        e => panic!("Expected Vote, got {:?}", e),
+    }
+}
+
+#[test]
+fn anonymous_voting() {
+    let (mut game, rx) = create_basic_game_1();
+    game.rules.election_info = ElectionInfo::Revealed;
+    assert!(game.start().is_ok());
+    rx.try_iter().for_each(drop);
+
+    for voter in [101, 102, 103] {
+        vote(&mut game, voter, 104);
+        // Spectators still see who voted
+        match rx.try_recv() {
+            Ok(Event::VoteCast { voter: v, .. }) => assert_eq!(v.user_id, voter),
+            e => panic!("Expected VoteCast, got {:?}", e),
+        }
+        match rx.try_recv() {
+            Ok(Event::Vote { voter, .. }) => assert_eq!(voter, None),
+            e => panic!("Expected Vote, got {:?}", e),
+        }
+    }
+
+    match rx.try_recv() {
+        Ok(Event::VoteTally { tally }) => {
+            let (ballot, electors) = &tally[0];
+            assert_eq!(ballot.as_ref().map(|p| p.user_id), Some(104));
+            let electors: Vec<u64> = electors.iter().map(|p| p.user_id).collect();
+            assert_eq!(electors, vec![101, 102, 103]);
+        }
+        e => panic!("Expected VoteTally, got {:?}", e),
+    }
+    expect_eventkind(&rx, EventKind::Election);
+}
+
+#[test]
+fn poll_watcher() {
+    let (mut game, rx) = create_game(vec![
+        Player::new(101, Role::POLLWATCHER),
+        Player::new(102, Role::TOWN),
+        Player::new(103, Role::TOWN),
+        Player::new(104, Role::MAFIA),
+        Player::new(105, Role::TOWN),
+    ]);
+    game.rules.election_info = ElectionInfo::Count;
+    assert!(game.start().is_ok());
+    rx.try_iter().for_each(drop);
+
+    for voter in [101, 102, 103] {
+        vote(&mut game, voter, 104);
+    }
+    let events: Vec<Event<u64>> = rx.try_iter().collect();
+    for event in &events {
+        if let Event::Vote { voter, .. } = event {
+            assert_eq!(*voter, None);
+        }
+    }
+    assert!(!events.iter().any(|e| matches!(e, Event::VoteTally { .. })));
+
+    let result = events
+        .iter()
+        .find(|e| matches!(e, Event::PollResult { .. }))
+        .expect("PollResult");
+    assert_eq!(result.audience(), Audience::Player(101));
+    match result {
+        Event::PollResult { watcher, tally } => {
+            assert_eq!(watcher.user_id, 101);
+            let (ballot, electors) = &tally[0];
+            assert_eq!(ballot.as_ref().map(|p| p.user_id), Some(104));
+            let electors: Vec<u64> = electors.iter().map(|p| p.user_id).collect();
+            assert_eq!(electors, vec![101, 102, 103]);
+        }
+        _ => unreachable!(),
+    }
+    match events.iter().find(|e| matches!(e, Event::Election { .. })) {
+        Some(Event::Election { electors, .. }) => assert!(electors.is_empty()),
+        e => panic!("Expected Election, got {:?}", e),
+    }
+}
+
+#[test]
+fn election_standings() {
+    let (mut game, rx) = create_basic_game_1();
+    assert!(game.start().is_ok());
+    vote(&mut game, 104, 101);
+    assert!(game
+        .handle(Action::Vote {
+            voter: 105,
+            ballot: Some(Choice::Abstain),
+        })
+        .is_ok());
+    for voter in [101, 102, 103] {
+        vote(&mut game, voter, 104);
+    }
+
+    let election = rx.try_iter().find_map(|e| match e {
+        Event::Election {
+            ballot,
+            standings,
+            threshold,
+            ..
+        } => Some((ballot, standings, threshold)),
+        _ => None,
+    });
+    let (ballot, standings, threshold) = election.expect("Election");
+    assert_eq!(ballot.map(|p| p.user_id), Some(104));
+    assert_eq!(threshold, 3);
+    let standings: Vec<_> = standings
+        .into_iter()
+        .map(|(b, count)| (b.map(|p| p.user_id), count))
+        .collect();
+    assert_eq!(standings, vec![(Some(101), 1), (None, 1), (Some(104), 3)]);
+}
+
+#[test]
+fn secret_election_standings() {
+    let (mut game, rx) = create_basic_game_1();
+    game.rules.election_info = ElectionInfo::Secret;
+    assert!(game.start().is_ok());
+    for voter in [101, 102, 103] {
+        vote(&mut game, voter, 104);
+    }
+    match rx.try_iter().find(|e| e.kind() == EventKind::Election) {
+        Some(Event::Election { standings, .. }) => assert!(standings.is_empty()),
+        e => panic!("Expected Election, got {:?}", e),
+    }
+}
+
+#[test]
+fn one_shot_vigilante() {
+    let (mut game, rx) = create_game(vec![
+        Player::new(101, Role::VIGILANTE),
+        Player::new(102, Role::TOWN),
+        Player::new(103, Role::TOWN),
+        Player::new(104, Role::TOWN),
+        Player::new(105, Role::MAFIA),
+        Player::new(106, Role::MAFIA),
+        Player::new(107, Role::TOWN),
+        Player::new(108, Role::TOWN),
+    ]);
+    game.rules.start_night = StartNight::Always;
+    game.rules.ability_limits.insert(Role::VIGILANTE, 1);
+    assert!(game.start().is_ok());
+    rx.try_iter().for_each(drop);
+
+    // Changing the target in the same night doesn't use another shot
+    assert!(target(&mut game, 101, Choice::Player(102)).is_ok());
+    assert!(target(&mut game, 101, Choice::Player(105)).is_ok());
+    let used: Vec<_> = rx
+        .try_iter()
+        .filter(|e| e.kind() == EventKind::AbilityUsed)
+        .collect();
+    assert_eq!(used.len(), 1);
+
+    mark(&mut game, 106, Choice::Player(102));
+    let killed: Vec<u64> = rx
+        .try_iter()
+        .filter_map(|e| match e {
+            Event::Eliminate { player } => Some(player.user_id),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(killed.len(), 2);
+    assert!(killed.contains(&102) && killed.contains(&105));
+
+    assert!(game.resolve_day_at_deadline().is_ok());
+    assert!(matches!(
+        target(&mut game, 101, Choice::Player(106)),
+        Err(InvalidActionError::AbilitySpent { .. })
+    ));
+    assert!(target(&mut game, 101, Choice::Abstain).is_ok());
+}
+
+#[test]
+fn abstain_gives_back_ability_use() {
+    let (mut game, rx) = create_game(vec![
+        Player::new(101, Role::VIGILANTE),
+        Player::new(102, Role::TOWN),
+        Player::new(103, Role::TOWN),
+        Player::new(104, Role::TOWN),
+        Player::new(105, Role::MAFIA),
+        Player::new(106, Role::TOWN),
+    ]);
+    game.rules.start_night = StartNight::Always;
+    game.rules.ability_limits.insert(Role::VIGILANTE, 1);
+    assert!(game.start().is_ok());
+    rx.try_iter().for_each(drop);
+
+    assert!(target(&mut game, 101, Choice::Player(102)).is_ok());
+    assert!(target(&mut game, 101, Choice::Abstain).is_ok());
+    let remaining: Vec<_> = rx
+        .try_iter()
+        .filter_map(|e| match e {
+            Event::AbilityUsed { remaining, .. } => Some(remaining),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(remaining, vec![0, 1]);
+
+    // The shot is still there the next night
+    mark(&mut game, 105, Choice::Player(102));
+    assert!(game.resolve_day_at_deadline().is_ok());
+    assert!(target(&mut game, 101, Choice::Player(105)).is_ok());
+}
+
+#[test]
+fn modkill_gives_back_pending_ability_use() {
+    let (mut game, rx) = create_game(vec![
+        Player::new(101, Role::VIGILANTE),
+        Player::new(102, Role::TOWN),
+        Player::new(103, Role::TOWN),
+        Player::new(104, Role::TOWN),
+        Player::new(105, Role::MAFIA),
+        Player::new(106, Role::MAFIA),
+        Player::new(107, Role::TOWN),
+        Player::new(108, Role::TOWN),
+    ]);
+    game.rules.start_night = StartNight::Always;
+    game.rules.ability_limits.insert(Role::VIGILANTE, 1);
+    game.moderators.push(MODERATOR);
+    assert!(game.start().is_ok());
+    assert!(target(&mut game, 101, Choice::Player(105)).is_ok());
+    rx.try_iter().for_each(drop);
+
+    // The modkill clears the shot before it resolves, so it isn't used up
+    assert!(game
+        .handle(Action::Modkill {
+            moderator: MODERATOR,
+            player: 108
+        })
+        .is_ok());
+    assert!(rx
+        .try_iter()
+        .any(|e| matches!(e, Event::AbilityUsed { remaining: 1, .. })));
+    assert!(target(&mut game, 101, Choice::Player(105)).is_ok());
+}
+
+#[test]
+fn bulletproof_modifier() {
+    let (mut game, rx) = create_game(vec![
+        Player::new(101, Role::TOWN).with_modifiers(vec![Modifier::Bulletproof]),
+        Player::new(102, Role::VIGILANTE),
+        Player::new(103, Role::TOWN),
+        Player::new(104, Role::TOWN),
+        Player::new(105, Role::MAFIA),
+        Player::new(106, Role::TOWN),
+    ]);
+    game.rules.start_night = StartNight::Always;
+    assert!(game.start().is_ok());
+    rx.try_iter().for_each(drop);
+
+    // Shot twice in one night, Bulletproof protects against both
+    assert!(target(&mut game, 102, Choice::Player(101)).is_ok());
+    mark(&mut game, 105, Choice::Player(101));
+    let kinds: Vec<_> = rx.try_iter().map(|e| e.kind()).collect();
+    assert_eq!(
+        kinds.iter().filter(|k| **k == EventKind::Survived).count(),
+        1
+    );
+    assert!(!kinds.contains(&EventKind::Eliminate));
+    assert!(!game.players[0].has(Modifier::Bulletproof));
+
+    // The next night, the protection is gone
+    assert!(game.resolve_day_at_deadline().is_ok());
+    assert!(target(&mut game, 102, Choice::Abstain).is_ok());
+    mark(&mut game, 105, Choice::Player(101));
+    let killed: Vec<_> = rx
+        .try_iter()
+        .filter_map(|e| match e {
+            Event::Eliminate { player } => Some(player.user_id),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(killed, vec![101]);
+}
+
+#[test]
+fn ascetic_modifier() {
+    let (mut game, rx) = create_game(vec![
+        Player::new(101, Role::TOWN).with_modifiers(vec![Modifier::Ascetic]),
+        Player::new(102, Role::COP),
+        Player::new(103, Role::TOWN),
+        Player::new(104, Role::MAFIA),
+    ]);
+    assert!(game.start().is_ok());
+    rx.try_iter().for_each(drop);
+
+    assert!(target(&mut game, 102, Choice::Player(101)).is_ok());
+    mark(&mut game, 104, Choice::Player(101));
+
+    let events: Vec<_> = rx.try_iter().collect();
+    let fizzled: Vec<_> = events
+        .iter()
+        .filter_map(|e| match e {
+            Event::VisitFizzled { actor, target } => Some((actor.user_id, target.user_id)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(fizzled.len(), 2);
+    assert!(fizzled.contains(&(102, 101)) && fizzled.contains(&(104, 101)));
+    assert!(events.iter().all(|e| e.kind() != EventKind::Investigate));
+    assert!(events.iter().any(|e| e.kind() == EventKind::NoKill));
+    assert_eq!(game.players.len(), 4);
+}
+
+#[test]
+fn audience_subscribers() {
+    let (tx, _rx) = mpsc::channel();
+    let (public_tx, public_rx) = mpsc::channel();
+    let (cop_tx, cop_rx) = mpsc::channel();
+    let (mafia_tx, mafia_rx) = mpsc::channel();
+    let mut comm = Comm::new(&tx);
+    comm.subscribe_audience(public_tx, Audience::Public);
+    comm.subscribe_audience(cop_tx, Audience::Player(102));
+    comm.subscribe_audience(mafia_tx, Audience::Player(104));
+
+    let players = vec![
+        Player::new(101, Role::TOWN),
+        Player::new(102, Role::COP),
+        Player::new(103, Role::DOCTOR),
+        Player::new(104, Role::MAFIA),
+    ];
+    let mut game = Game::new(1, players, Vec::new(), comm);
+    assert!(game.start().is_ok());
+    assert!(target(&mut game, 102, Choice::Player(104)).is_ok());
+    assert!(target(&mut game, 103, Choice::Player(101)).is_ok());
+    mark(&mut game, 104, Choice::Player(101));
+
+    let public: Vec<_> = public_rx.try_iter().collect();
+    let cop: Vec<_> = cop_rx.try_iter().collect();
+    assert!(public.iter().all(|e| e.audience() == Audience::Public));
+    assert!(public.iter().any(|e| e.kind() == EventKind::Dawn));
+    assert!(cop.iter().all(|e| e.audience() == Audience::Player(102)));
+    assert!(cop.iter().any(|e| e.kind() == EventKind::Investigate));
+    assert!(cop.iter().all(|e| !public.contains(e)));
+    // The Mafia's Mark is sent to each living Mafia member
+    assert!(mafia_rx.try_iter().any(|e| e.kind() == EventKind::Mark));
+}
+
+#[test]
+fn game_stats() {
+    let (mut game, rx) = create_basic_game_2();
+    assert!(game.start().is_ok());
+
+    assert!(target(&mut game, 102, Choice::Player(104)).is_ok());
+    assert!(target(&mut game, 103, Choice::Player(101)).is_ok());
+    mark(&mut game, 104, Choice::Player(101));
+    for voter in [101, 102, 103] {
+        vote(&mut game, voter, 104);
+    }
+
+    let stats = rx.try_iter().find_map(|e| match e {
+        Event::GameStats { stats } => Some(stats),
+        _ => None,
+    });
+    let stats = stats.expect("Game should have ended with stats");
+    assert_eq!(stats.votes_cast, 3);
+    assert_eq!(stats.votes_received, vec![(104, 3)]);
+    assert_eq!(
+        stats.nights_survived,
+        vec![(101, 1), (102, 1), (103, 1), (104, 1)]
+    );
+    assert_eq!(stats.kills, 0);
+    assert_eq!(stats.saves, 1);
+    assert_eq!(stats.investigations, 1);
+    assert_eq!(stats.correct_investigations, 1);
+}
+
+#[test]
+fn game_stats_after_load() {
+    let fname = temp_path("game_stats_test.json");
+    let fname = fname.to_str().unwrap();
+
+    let (mut game, _rx) = create_basic_game_2();
+    assert!(game.start().is_ok());
+    assert!(target(&mut game, 102, Choice::Player(104)).is_ok());
+    assert!(target(&mut game, 103, Choice::Player(101)).is_ok());
+    mark(&mut game, 104, Choice::Player(101));
+    vote(&mut game, 101, 104);
+    assert!(game.save_game(fname).is_ok());
+
+    // What happened before the save is still counted
+    let (tx, rx) = mpsc::channel();
+    let mut loaded = Game::load_game(fname, SaveFormat::PrettyJson, Comm::new(&tx)).unwrap();
+    for voter in [102, 103] {
+        vote(&mut loaded, voter, 104);
+    }
+    let stats = rx.try_iter().find_map(|e| match e {
+        Event::GameStats { stats } => Some(stats),
+        _ => None,
+    });
+    let stats = stats.expect("Game should have ended with stats");
+    assert_eq!(stats.votes_cast, 3);
+    assert_eq!(stats.votes_received, vec![(104, 3)]);
+    assert_eq!(
+        stats.nights_survived,
+        vec![(101, 1), (102, 1), (103, 1), (104, 1)]
+    );
+    assert_eq!(stats.saves, 1);
+    assert_eq!(stats.investigations, 1);
+    std::fs::remove_file(fname).unwrap();
+}
+
+#[test]
+fn max_days_draw() {
+    let (mut game, rx) = create_basic_game_1();
+    game.rules.max_days = Some(2);
+    assert!(game.start().is_ok());
+
+    for day_no in 1..=2 {
+        assert!(matches!(&game.phase, Phase::Day(day) if day.day_no == day_no));
+        assert!(game.resolve_day_at_deadline().is_ok());
+        assert!(game.resolve_night_at_deadline().is_ok());
+    }
+    assert!(matches!(game.phase, Phase::End(None, _)));
+    let end = rx.try_iter().find(|e| e.kind() == EventKind::End);
+    assert!(matches!(end, Some(Event::End { winner: None, .. })));
+}
+
+#[test]
+fn my_role() {
+    let (mut game, rx) = create_basic_game_1();
+    assert!(game.start().is_ok());
+    rx.try_iter().for_each(drop);
+
+    assert!(game.handle(Action::MyRole { player: 102 }).is_ok());
+    let event = rx.try_recv().unwrap();
+    assert_eq!(event.audience(), Audience::Player(102));
+    match event {
+        Event::YourRole { role, team, .. } => {
+            assert_eq!(role, Role::COP);
+            assert_eq!(team, Team::Town);
+        }
+        e => panic!("Expected YourRole, got {:?}", e),
+    }
+    assert!(rx.try_recv().is_err());
+    assert!(game.handle(Action::MyRole { player: 404 }).is_err());
+}
+
+#[test]
+fn team_chat() {
+    let (mut game, rx) = create_game(vec![
+        Player::new(101, Role::TOWN),
+        Player::new(102, Role::COP),
+        Player::new(103, Role::TOWN),
+        Player::new(104, Role::MAFIA),
+        Player::new(105, Role::GODFATHER),
+        Player::new(106, Role::TOWN),
+        Player::new(107, Role::TOWN),
+    ]);
+    assert!(game.start().is_ok());
+    rx.try_iter().for_each(drop);
+
+    let chat = |from: u64| Action::TeamChat {
+        from,
+        text: "Kill 102".to_string(),
+    };
+    assert!(game.handle(chat(104)).is_ok());
+    match rx.try_recv() {
+        Ok(Event::TeamMessage { to, .. }) => {
+            let to: Vec<u64> = to.iter().map(|p| p.user_id).collect();
+            assert_eq!(to, vec![104, 105]);
+        }
+        e => panic!("Expected TeamMessage, got {:?}", e),
+    }
+    assert!(matches!(
+        game.handle(chat(101)),
+        Err(InvalidActionError::InvalidRole { .. })
+    ));
+}
+
+fn bodyguard_game() -> (Game<u64>, Receiver<Event<u64>>) {
+    let (mut game, rx) = create_game(vec![
+        Player::new(101, Role::TOWN),
+        Player::new(102, Role::BODYGUARD),
+        Player::new(103, Role::BODYGUARD),
+        Player::new(104, Role::DOCTOR),
+        Player::new(105, Role::MAFIA),
+        Player::new(106, Role::TOWN),
+        Player::new(107, Role::TOWN),
+    ]);
+    game.rules.start_night = StartNight::Always;
+    assert!(game.start().is_ok());
+    rx.try_iter().for_each(drop);
+    (game, rx)
+}
+
+fn eliminated(rx: &Receiver<Event<u64>>) -> Vec<u64> {
+    rx.try_iter()
+        .filter_map(|e| match e {
+            Event::Eliminate { player } => Some(player.user_id),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn bodyguard_and_doctor() {
+    let (mut game, rx) = bodyguard_game();
+    assert!(target(&mut game, 102, Choice::Player(101)).is_ok());
+    assert!(target(&mut game, 103, Choice::Abstain).is_ok());
+    assert!(target(&mut game, 104, Choice::Player(101)).is_ok());
+    mark(&mut game, 105, Choice::Player(101));
+    assert_eq!(eliminated(&rx), vec![102]);
+}
+
+#[test]
+fn bodyguard_chain() {
+    let (mut game, rx) = bodyguard_game();
+    assert!(target(&mut game, 102, Choice::Player(101)).is_ok());
+    assert!(target(&mut game, 103, Choice::Player(102)).is_ok());
+    assert!(target(&mut game, 104, Choice::Abstain).is_ok());
+    mark(&mut game, 105, Choice::Player(101));
+    assert_eq!(eliminated(&rx), vec![103]);
+}
+
+#[test]
+fn cop_and_rolecop() {
+    let (mut game, rx) = create_game(vec![
+        Player::new(101, Role::COP),
+        Player::new(102, Role::ROLECOP),
+        Player::new(103, Role::TOWN),
+        Player::new(104, Role::GODFATHER),
+    ]);
+    assert!(game.start().is_ok());
+    rx.try_iter().for_each(drop);
+
+    assert!(target(&mut game, 101, Choice::Player(104)).is_ok());
+    assert!(target(&mut game, 102, Choice::Player(104)).is_ok());
+    mark(&mut game, 104, Choice::Abstain);
+
+    let mut results: Vec<_> = rx
+        .try_iter()
+        .filter_map(|e| match e {
+            Event::Investigate { cop, result, .. } => Some((cop.user_id, result)),
+            _ => None,
+        })
+        .collect();
+    results.sort_by_key(|(cop, _)| *cop);
+    assert_eq!(
+        results,
+        vec![
+            (101, InvestigationResult::Guilty(false)),
+            (102, InvestigationResult::Role(Role::GODFATHER)),
+        ]
+    );
+}
+
+#[test]
+fn goon_makes_faction_kill() {
+    let (mut game, rx) = create_game(vec![
+        Player::new(101, Role::TOWN),
+        Player::new(102, Role::COP),
+        Player::new(103, Role::TOWN),
+        Player::new(104, Role::GOON),
+        Player::new(105, Role::TOWN),
+        Player::new(106, Role::TOWN),
+    ]);
+    assert!(game.start().is_ok());
+    expect_night_start(&rx, &[102]);
+
+    assert!(target(&mut game, 102, Choice::Player(101)).is_ok());
+    mark(&mut game, 104, Choice::Player(101));
+    assert_eq!(eliminated(&rx), vec![101]);
+}
+
+#[test]
+fn skip_day() {
+    let (mut game, rx) = create_basic_game_1();
+    assert!(game.start().is_ok());
+    rx.try_iter().for_each(drop);
+
+    for (voter, count) in [(101, 1), (102, 2)] {
+        assert!(game.handle(Action::SkipDay { voter }).is_ok());
+        expect_eventkind(&rx, EventKind::VoteCast);
+        match rx.try_recv() {
+            Ok(Event::SkipVote {
+                count: c,
+                threshold,
+                ..
+            }) => assert_eq!((c, threshold), (count, 3)),
+            e => panic!("Expected SkipVote, got {:?}", e),
+        }
+    }
+    vote(&mut game, 103, 104);
+    assert!(game.handle(Action::SkipDay { voter: 103 }).is_ok());
+
+    let kinds: Vec<_> = rx.try_iter().map(|e| e.kind()).collect();
+    assert!(kinds.contains(&EventKind::DaySkipped));
+    assert!(!kinds.contains(&EventKind::Election));
+    assert!(!kinds.contains(&EventKind::Eliminate));
+    assert!(game.phase.is_night().is_ok());
+    assert_eq!(game.players.len(), 5);
+}
+
+#[test]
+fn wrong_phase() {
+    // Voting at night
+    let (mut game, rx) = create_basic_game_2();
+    assert!(game.start().is_ok());
+    rx.try_iter().for_each(drop);
+
+    let voted = game.handle(Action::Vote {
+        voter: 101,
+        ballot: Some(Choice::Player(104)),
+    });
+    assert!(matches!(
+        voted,
+        Err(InvalidActionError::InvalidPhase {
+            expected: PhaseKind::Day,
+            ..
+        })
+    ));
+    match rx.try_recv() {
+        Ok(event @ Event::WrongPhase { .. }) => {
+            assert_eq!(event.audience(), Audience::Player(101));
+            assert!(matches!(
+                event,
+                Event::WrongPhase {
+                    action: ActionKind::Vote,
+                    expected: PhaseKind::Day,
+                    got: PhaseKind::Night,
+                    ..
+                }
+            ));
+        }
+        e => panic!("Expected WrongPhase, got {:?}", e),
+    }
+
+    // Acting during the day
+    let (mut game, rx) = create_basic_game_1();
+    assert!(game.start().is_ok());
+    rx.try_iter().for_each(drop);
+
+    assert!(target(&mut game, 102, Choice::Player(104)).is_err());
+    assert!(matches!(
+        rx.try_recv(),
+        Ok(Event::WrongPhase {
+            action: ActionKind::Target,
+            expected: PhaseKind::Night,
+            got: PhaseKind::Day,
+            ..
+        })
+    ));
+
+    // Someone not in the game isn't told anything
+    assert!(target(&mut game, 999, Choice::Player(104)).is_err());
+    assert!(rx.try_recv().is_err());
+}
+
+#[test]
+fn phase_query() {
+    let (mut game, rx) = create_basic_game_1();
+    assert!(game.start().is_ok());
+    rx.try_iter().for_each(drop);
+
+    assert!(game.in_day() && !game.in_night() && !game.has_ended());
+    assert!(game.handle(Action::Phase { from: MODERATOR }).is_ok());
+    assert_eq!(
+        rx.try_recv(),
+        Ok(Event::Phase {
+            to: MODERATOR,
+            phase: PhaseKind::Day,
+            number: Some(1),
+        })
+    );
+
+    vote(&mut game, 102, 101);
+    vote(&mut game, 103, 101);
+    vote(&mut game, 104, 101);
+    rx.try_iter().for_each(drop);
+
+    assert!(game.in_night());
+    assert_eq!(game.current_phase().kind(), PhaseKind::Night);
+    assert!(game.handle(Action::Phase { from: 102 }).is_ok());
+    match rx.try_recv() {
+        Ok(event) => {
+            assert_eq!(event.audience(), Audience::Player(102));
+            assert!(matches!(
+                event,
+                Event::Phase {
+                    phase: PhaseKind::Night,
+                    number: Some(1),
+                    ..
+                }
+            ));
+        }
+        e => panic!("Expected Phase, got {:?}", e),
+    }
+}
+
+#[test]
+fn night_vote() {
+    let night_vote = |voter| Action::Vote {
+        voter,
+        ballot: Some(Choice::Player(101)),
+    };
+
+    // Disabled by default
+    let (mut game, rx) = create_basic_game_2();
+    assert!(game.start().is_ok());
+    assert!(game.handle(night_vote(102)).is_err());
+
+    // Enabled, a majority of night votes eliminates 101 at dawn
+    let (mut game, rx) = {
+        drop(rx);
+        create_basic_game_2()
+    };
+    game.rules.night_vote = true;
+    assert!(game.start().is_ok());
+    rx.try_iter().for_each(drop);
+
+    for (voter, count) in [(102, 1), (103, 2)] {
+        assert!(game.handle(night_vote(voter)).is_ok());
+        match rx.try_recv() {
+            Ok(Event::NightVote {
+                count: c,
+                threshold,
+                ..
+            }) => assert_eq!((c, threshold), (count, 3)),
+            e => panic!("Expected NightVote, got {:?}", e),
+        }
+    }
+    assert!(game.handle(night_vote(104)).is_ok());
+    // The night vote doesn't end the night on its own
+    assert!(game.in_night());
+
+    target(&mut game, 102, Choice::Player(104)).unwrap();
+    target(&mut game, 103, Choice::Player(103)).unwrap();
+    mark(&mut game, 104, Choice::Abstain);
+
+    let kinds: Vec<_> = rx.try_iter().map(|e| e.kind()).collect();
+    // The night election is a kill, so there is no NoKill
+    assert!(!kinds.contains(&EventKind::NoKill));
+    assert!(kinds.contains(&EventKind::NightElection));
+    assert!(kinds.contains(&EventKind::Eliminate));
+    assert!(game.players.check(101).is_err());
+    assert!(game.in_day());
+}
+
+#[test]
+fn game_errors() {
+    let (mut game, _rx) = create_game(vec![
+        Player::new(101, Role::TOWN),
+        Player::new(102, Role::MAFIA),
+    ]);
+    assert_eq!(game.start(), Err(GameError::NotEnoughPlayers { found: 2 }));
+    assert_eq!(
+        game.add_player(Player::new(101, Role::COP)),
+        Err(GameError::PlayerExists { pid: 101 })
+    );
+
+    let (mut game, _rx) = create_game(vec![
+        Player::new(101, Role::TOWN),
+        Player::new(102, Role::COP),
+        Player::new(103, Role::DOCTOR),
+    ]);
+    assert_eq!(
+        game.start(),
+        Err(GameError::GameDecided { winner: Team::Town })
+    );
+    assert!(game.add_player(Player::new(104, Role::MAFIA)).is_ok());
+    assert!(game.start().is_ok());
+
+    assert_eq!(game.start(), Err(GameError::GameStarted));
+    assert_eq!(
+        game.add_player(Player::new(105, Role::TOWN)),
+        Err(GameError::GameStarted)
+    );
+}
+
+#[test]
+fn render_locales() {
+    let vote = Event::Vote {
+        voter: Some(Player::new(101, Role::TOWN)),
+        ballot: Some(Player::new(104, Role::MAFIA)),
+        former: None,
+        threshold: 3,
+        count: Some(1),
+    };
+    let name = |pid: u64| format!("Player {}", pid);
+
+    assert_eq!(
+        render(&vote, &English, name),
+        "Player 101 votes for Player 104! (1/3)"
+    );
+
+    let spanish = Catalog::new()
+        .with(
+            EventKind::Vote,
+            "¡{voter} vota por {ballot}! ({count}/{threshold})",
+        )
+        .with_term(Term::Abstain, "Nadie");
+    assert_eq!(
+        render(&vote, &spanish, name),
+        "¡Player 101 vota por Player 104! (1/3)"
+    );
+
+    let abstain = Event::Election {
+        electors: Vec::new(),
+        ballot: None,
+        hammer: None,
+        standings: Vec::new(),
+        threshold: 3,
+    };
+    let spanish = spanish.with(EventKind::Election, "El pueblo ha elegido a {ballot}.");
+    assert_eq!(
+        render(&abstain, &spanish, name),
+        "El pueblo ha elegido a Nadie."
+    );
+    // Kinds without a template fall back to Display
+    assert_eq!(render(&Event::<u64>::NoKill, &spanish, name), "NoKill");
+}
+
+#[test]
+fn validate_setup() {
+    let (mut game, _rx) = create_game(vec![
+        Player::new(101, Role::GODFATHER),
+        Player::new(102, Role::GODFATHER),
+    ]);
+    game.rules.max_days_winner = Some(Team::Town);
+    game.rules.time_warnings = vec![Duration::from_secs(10)];
+    game.rules.ability_limits.insert(Role::TOWN, 1);
+    game.rules.tie_break = TieBreak::FirstVoted;
+
+    let errors = game.validate_setup().unwrap_err();
+    assert_eq!(
+        errors,
+        vec![
+            GameError::NotEnoughPlayers { found: 2 },
+            GameError::DuplicateRole {
+                role: Role::GODFATHER,
+                count: 2
+            },
+            GameError::IncompatibleRule {
+                rule: "max_days_winner",
+                requires: "max_days"
+            },
+            GameError::IncompatibleRule {
+                rule: "time_warnings",
+                requires: "a day_length or night_length"
+            },
+            GameError::IncompatibleRule {
+                rule: "tie_break",
+                requires: "a deadline election or Plurality deadline_behavior"
+            },
+            GameError::IncompatibleRule {
+                rule: "ability_limits",
+                requires: "roles with a night action"
+            },
+        ]
+    );
+    // Nothing was started, and start gives the first problem
+    assert_eq!(game.phase, Phase::Init);
+    assert_eq!(game.start(), Err(GameError::NotEnoughPlayers { found: 2 }));
+
+    let (game, _rx) = create_basic_game_1();
+    assert_eq!(game.validate_setup(), Ok(()));
+}
+
+#[test]
+fn night_complete() {
+    let (mut game, rx) = create_basic_game_2();
+    assert!(game.start().is_ok());
+    rx.try_iter().for_each(drop);
+
+    let night_completes = |rx: &Receiver<Event<u64>>| {
+        rx.try_iter()
+            .filter(|e| e.kind() == EventKind::NightComplete)
+            .count()
+    };
+
+    target(&mut game, 102, Choice::Player(104)).unwrap();
+    mark(&mut game, 104, Choice::Player(101));
+    assert_eq!(night_completes(&rx), 0);
+
+    target(&mut game, 103, Choice::Player(101)).unwrap();
+    // It comes before anything from resolving the night
+    let kinds: Vec<_> = rx.try_iter().map(|e| e.kind()).collect();
+    let complete = kinds.iter().position(|k| *k == EventKind::NightComplete);
+    assert_eq!(complete, Some(1));
+    assert_eq!(kinds[2], EventKind::Dawn);
+    assert_eq!(
+        kinds
+            .iter()
+            .filter(|k| **k == EventKind::NightComplete)
+            .count(),
+        1
+    );
+
+    // Not sent when the deadline resolves the night
+    let (mut game, rx) = create_basic_game_2();
+    assert!(game.start().is_ok());
+    assert!(game.resolve_night_at_deadline().is_ok());
+    assert_eq!(night_completes(&rx), 0);
+}
+
+#[test]
+fn mod_inspect() {
+    let (mut game, rx) = create_basic_game_1();
+    game.moderators.push(MODERATOR);
+    assert!(game.start().is_ok());
+    vote(&mut game, 102, 101);
+    vote(&mut game, 103, 101);
+    vote(&mut game, 104, 101);
+    rx.try_iter().for_each(drop);
+
+    // A moderator can inspect the dead and the living
+    let inspect = |moderator, player| Action::ModInspect { moderator, player };
+    assert!(game.handle(inspect(MODERATOR, 101)).is_ok());
+    match rx.try_recv() {
+        Ok(event) => {
+            assert_eq!(event.audience(), Audience::Player(MODERATOR));
+            assert!(matches!(
+                event,
+                Event::ModInspect {
+                    role: Role::TOWN,
+                    alive: false,
+                    ..
+                }
+            ));
+        }
+        e => panic!("Expected ModInspect, got {:?}", e),
+    }
+    assert!(game.handle(inspect(MODERATOR, 104)).is_ok());
+    assert!(matches!(
+        rx.try_recv(),
+        Ok(Event::ModInspect {
+            role: Role::MAFIA,
+            alive: true,
+            ..
+        })
+    ));
+
+    // A regular player can't
+    assert!(matches!(
+        game.handle(inspect(102, 104)),
+        Err(InvalidActionError::NotModerator { pid: 102 })
+    ));
+    assert!(rx.try_recv().is_err());
+}
+
+#[test]
+fn mafia_knowledge() {
+    let players = || {
+        vec![
+            Player::new(101, Role::TOWN),
+            Player::new(102, Role::COP),
+            Player::new(103, Role::DOCTOR),
+            Player::new(104, Role::TOWN),
+            Player::new(105, Role::MAFIA),
+            Player::new(106, Role::GODFATHER),
+            Player::new(107, Role::TOWN),
+        ]
+    };
+    let teammates = |events: &[Event<u64>], user_id| -> Vec<u64> {
+        events
+            .iter()
+            .find_map(|e| match e {
+                Event::YourRole {
+                    player, teammates, ..
+                } if player.user_id == user_id => {
+                    assert_eq!(e.audience(), Audience::Player(user_id));
+                    Some(teammates.iter().map(|p| p.user_id).collect())
+                }
+                _ => None,
+            })
+            .unwrap()
+    };
+
+    // Known: each mafioso learns their partners, and town learns no one
+    let (mut game, rx) = create_game(players());
+    assert!(game.start().is_ok());
+    let events: Vec<_> = rx.try_iter().collect();
+    assert_eq!(teammates(&events, 105), vec![106]);
+    assert_eq!(teammates(&events, 106), vec![105]);
+    assert!(teammates(&events, 101).is_empty());
+
+    // Blind: a mafioso only learns their own role, and can't use team chat
+    let (mut game, rx) = create_game(players());
+    game.rules.mafia_knowledge = MafiaKnowledge::Blind;
+    assert!(game.start().is_ok());
+    let events: Vec<_> = rx.try_iter().collect();
+    assert!(teammates(&events, 105).is_empty());
+    assert!(teammates(&events, 106).is_empty());
+    let chat = Action::TeamChat {
+        from: 105,
+        text: "Hello?".to_string(),
+    };
+    assert!(game.handle(chat).is_err());
+}
+
+#[test]
+fn revive() {
+    let (mut game, rx) = create_basic_game_1();
+    game.moderators.push(MODERATOR);
+    assert!(game.start().is_ok());
+    let revive = |moderator, player| Action::Revive { moderator, player };
+
+    // 101 is elected by mistake, and brought back during the night
+    vote(&mut game, 102, 101);
+    vote(&mut game, 103, 101);
+    vote(&mut game, 104, 101);
+    assert!(game.players.check(101).is_err());
+    assert!(game.handle(revive(102, 101)).is_err());
+    assert!(game.handle(revive(MODERATOR, 105)).is_err());
+    rx.try_iter().for_each(drop);
+
+    assert!(game.handle(revive(MODERATOR, 101)).is_ok());
+    expect_eventkind(&rx, EventKind::Revived);
+    assert!(game.players.check(101).is_ok());
+    assert!(game.dead.is_empty());
+    assert!(game.in_night());
+
+    // The game continues, and 101 can be killed again
+    target(&mut game, 102, Choice::Player(104)).unwrap();
+    target(&mut game, 103, Choice::Player(102)).unwrap();
+    mark(&mut game, 104, Choice::Player(101));
+    assert!(game.players.check(101).is_err());
+    assert!(game.in_day());
+
+    // Reviving the Mafia after they were elected un-ends the game
+    vote(&mut game, 102, 104);
+    vote(&mut game, 103, 104);
+    vote(&mut game, 105, 104);
+    assert!(game.has_ended());
+    rx.try_iter().for_each(drop);
+
+    assert!(game.handle(revive(MODERATOR, 104)).is_ok());
+    expect_eventkind(&rx, EventKind::Revived);
+    expect_eventkind(&rx, EventKind::Night);
+    assert_eq!(game.phase.number(), Some(2));
+}
+
+#[test]
+fn undo() {
+    let (mut game, rx) = create_basic_game_1();
+    game.moderators.push(MODERATOR);
+    assert!(game.start().is_ok());
+    let undo = || Action::Undo {
+        moderator: MODERATOR,
+    };
+    assert!(matches!(
+        game.handle(undo()),
+        Err(InvalidActionError::NothingToUndo)
+    ));
+
+    vote(&mut game, 102, 101);
+    vote(&mut game, 103, 101);
+    vote(&mut game, 104, 101);
+    assert!(game.in_night());
+    rx.try_iter().for_each(drop);
+
+    // Only moderators can undo
+    assert!(game.handle(Action::Undo { moderator: 102 }).is_err());
+    assert!(game.handle(undo()).is_ok());
+    match rx.try_recv() {
+        Ok(Event::Undone {
+            phase,
+            number,
+            restored,
+        }) => {
+            assert_eq!((phase, number), (PhaseKind::Day, Some(1)));
+            assert_eq!(restored.len(), 1);
+            assert_eq!(restored[0].user_id, 101);
+        }
+        e => panic!("Expected Undone, got {:?}", e),
+    }
+    assert!(game.in_day());
+    assert!(game.players.check(101).is_ok());
+    assert!(game.dead.is_empty());
+    // The hammer vote was undone, but the earlier votes still stand
+    match &game.phase {
+        Phase::Day(day) => assert_eq!(day.votes.len(), 2),
+        phase => panic!("Expected Day, found {}", phase),
+    }
+
+    // Only one step back
+    assert!(matches!(
+        game.handle(undo()),
+        Err(InvalidActionError::NothingToUndo)
+    ));
+    // A rejected action doesn't replace what can be undone
+    vote(&mut game, 105, 104);
+    assert!(game.handle(Action::MyRole { player: 101 }).is_ok());
+    assert!(game
+        .handle(Action::Vote {
+            voter: 999,
+            ballot: None
+        })
+        .is_err());
+    assert!(game.handle(undo()).is_ok());
+    match &game.phase {
+        Phase::Day(day) => assert_eq!(day.votes.len(), 2),
+        phase => panic!("Expected Day, found {}", phase),
+    }
+}
+
+#[test]
+fn undo_keeps_timer() {
+    let (mut game, rx) = create_basic_game_1();
+    game.moderators.push(MODERATOR);
+    game.rules.day_length = Some(Duration::from_secs(60));
+    assert!(game.start().is_ok());
+    game.tick(secs(0));
+    game.tick(secs(30));
+
+    // The hammer starts the Night, but undoing it returns to the Day's deadline
+    vote(&mut game, 102, 101);
+    vote(&mut game, 103, 101);
+    vote(&mut game, 104, 101);
+    assert!(game.in_night());
+    assert!(game
+        .handle(Action::Undo {
+            moderator: MODERATOR
+        })
+        .is_ok());
+    assert_eq!(game.timer.as_ref().unwrap().deadline, secs(60));
+
+    // Undoing doesn't change whether the game is paused
+    assert!(game
+        .handle(Action::Pause {
+            moderator: MODERATOR
+        })
+        .is_ok());
+    rx.try_iter().for_each(drop);
+    assert!(game
+        .handle(Action::Modkill {
+            moderator: MODERATOR,
+            player: 105
+        })
+        .is_ok());
+    assert!(game
+        .handle(Action::Undo {
+            moderator: MODERATOR
+        })
+        .is_ok());
+    assert!(game.paused);
+    assert!(!rx.try_iter().any(|e| e.kind() == EventKind::Resumed));
+}
+
+#[test]
+fn autosave() {
+    let fname = temp_path("autosave_test.json");
+    let fname = fname.to_str().unwrap();
+    let _ = std::fs::remove_file(fname);
+    let minute = Duration::from_secs(60);
+
+    let (mut game, _rx) = create_basic_game_1();
+    assert!(game.start().is_ok());
+    assert_eq!(game.autosave(fname, secs(0), minute), Ok(true));
+    std::fs::remove_file(fname).unwrap();
+
+    // Nothing changed, so nothing is written
+    assert!(game
+        .handle(Action::Vote {
+            voter: 999,
+            ballot: None
+        })
+        .is_err());
+    assert!(game.handle(Action::MyRole { player: 101 }).is_ok());
+    assert_eq!(game.autosave(fname, secs(10), minute), Ok(false));
+    assert!(std::fs::metadata(fname).is_err());
+
+    // A change is saved, but not more often than the interval
+    vote(&mut game, 101, 104);
+    assert_eq!(game.autosave(fname, secs(30), minute), Ok(false));
+    assert_eq!(game.autosave(fname, secs(60), minute), Ok(true));
+    assert!(std::fs::metadata(fname).is_ok());
+    std::fs::remove_file(fname).unwrap();
+}
+
+#[test]
+fn archive_on_end() {
+    let dir = temp_path("archive_test");
+    let fname = temp_path("archive_test.json");
+    let fname = fname.to_str().unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+    let minute = Duration::from_secs(60);
+
+    let (mut game, _rx) = create_basic_game_1();
+    assert_eq!(
+        game.save_on_end,
+        SaveOnEnd::Archive {
+            dir: ARCHIVE_DIR.to_string()
+        }
+    );
+    game.save_on_end = SaveOnEnd::Archive {
+        dir: dir.to_str().unwrap().to_string(),
+    };
+    assert!(game.start().is_ok());
+    assert_eq!(game.autosave(fname, secs(0), minute), Ok(true));
+    assert!(std::fs::metadata(fname).is_ok());
+
+    // Electing the only MAFIA ends the game, and its final save is archived
+    // without waiting for the next autosave
+    vote(&mut game, 101, 104);
+    vote(&mut game, 102, 104);
+    vote(&mut game, 103, 104);
+    assert!(game.has_ended());
+    assert!(std::fs::metadata(fname).is_err());
+    assert_eq!(game.autosave(fname, secs(10), minute), Ok(false));
+    let archived: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    assert_eq!(archived.len(), 1);
+    let name = archived[0].file_name().unwrap().to_str().unwrap();
+    assert!(name.starts_with("1-") && name.ends_with(".json"));
+    let loaded = Game::<u64>::load_game(
+        archived[0].to_str().unwrap(),
+        SaveFormat::PrettyJson,
+        Comm::default(),
+    );
+    assert!(loaded.is_ok_and(|game| game.has_ended()));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn binary_save_format() {
+    let json = temp_path("save_format_test.json");
+    let binary = temp_path("save_format_test.bin");
+    let (json, binary) = (json.to_str().unwrap(), binary.to_str().unwrap());
+
+    let (mut game, _rx) = create_basic_game_1();
+    assert!(game.start().is_ok());
+    assert!(game.save_game(json).is_ok());
+    game.save_format = SaveFormat::Binary;
+    assert!(game.save_game(binary).is_ok());
+
+    let size = |fname| std::fs::metadata(fname).unwrap().len();
+    assert!(size(binary) < size(json));
+
+    // Each save loads back in the format it was written in
+    for (fname, format) in [(json, SaveFormat::PrettyJson), (binary, SaveFormat::Binary)] {
+        let loaded = Game::<u64>::load_game(fname, format, Comm::default()).unwrap();
+        assert_eq!(loaded.players, game.players);
+        assert_eq!(loaded.phase, game.phase);
+        std::fs::remove_file(fname).unwrap();
+    }
+}
+
+#[test]
+fn save_version() {
+    let fname = temp_path("save_version_test.json");
+    let fname = fname.to_str().unwrap();
+
+    let (mut game, _rx) = create_basic_game_1();
+    assert!(game.start().is_ok());
+    vote(&mut game, 101, 104);
+    assert!(game.save_game(fname).is_ok());
+
+    let save = std::fs::read_to_string(fname).unwrap();
+    let version = format!("\"version\": {}", SAVE_VERSION);
+    assert!(save.contains(&version));
+
+    // The save loads, and sends its Events through the new Comm
+    let (tx, rx) = mpsc::channel();
+    let mut loaded = Game::load_game(fname, SaveFormat::PrettyJson, Comm::new(&tx)).unwrap();
+    vote(&mut loaded, 102, 104);
+    expect_eventkind(&rx, EventKind::VoteCast);
+    expect_eventkind(&rx, EventKind::Vote);
+
+    // A save from another version is rejected before it is read
+    std::fs::write(fname, save.replace(&version, "\"version\": 99")).unwrap();
+    assert_eq!(
+        Game::<u64>::load_game(fname, SaveFormat::PrettyJson, Comm::default()).unwrap_err(),
+        LoadError::IncompatibleVersion {
+            found: 99,
+            expected: SAVE_VERSION
+        }
+    );
+    std::fs::remove_file(fname).unwrap();
+}
+
+#[test]
+fn migrate_save() {
+    let fname = temp_path("migrate_save_test.json");
+    let fname = fname.to_str().unwrap();
+
+    let (mut game, _rx) = create_basic_game_1();
+    assert!(game.start().is_ok());
+    vote(&mut game, 101, 104);
+    assert!(game.save_game(fname).is_ok());
+
+    // A version 1 save of the same game
+    let mut v1: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(fname).unwrap()).unwrap();
+    v1["version"] = serde_json::Value::from(1);
+
+    let migrated = migrate(1, v1.to_owned()).unwrap();
+    assert_eq!(migrated["version"], SAVE_VERSION);
+    assert!(migrate(0, v1.to_owned()).is_err());
+
+    // Loading the version 1 save migrates it first
+    std::fs::write(fname, serde_json::to_string_pretty(&v1).unwrap()).unwrap();
+    let loaded = Game::<u64>::load_game(fname, SaveFormat::PrettyJson, Comm::default()).unwrap();
+    assert_eq!(loaded.players, game.players);
+    assert_eq!(loaded.phase, game.phase);
+    std::fs::remove_file(fname).unwrap();
+}
+
+#[test]
+fn pending_actions() {
+    let (mut game, rx) = create_basic_game_2();
+    game.moderators.push(MODERATOR);
+    assert!(game.start().is_ok());
+    let pending = Action::PendingActions {
+        moderator: MODERATOR,
+    };
+
+    // The DOCTOR has acted, but the COP hasn't
+    assert!(target(&mut game, 103, Choice::Player(101)).is_ok());
+    rx.try_iter().for_each(drop);
+    assert!(game.handle(pending.to_owned()).is_ok());
+    match rx.try_recv() {
+        Ok(event) => {
+            assert_eq!(event.audience(), Audience::Player(MODERATOR));
+            match event {
+                Event::PendingActions { missing, .. } => {
+                    let missing: Vec<u64> = missing.iter().map(|p| p.user_id).collect();
+                    assert_eq!(missing, vec![102]);
+                }
+                e => panic!("Expected PendingActions, got {:?}", e),
+            }
+        }
+        e => panic!("Expected PendingActions, got {:?}", e),
+    }
+
+    // Only moderators can ask
+    assert!(matches!(
+        game.handle(Action::PendingActions { moderator: 101 }),
+        Err(InvalidActionError::NotModerator { pid: 101 })
+    ));
+}
+
+#[test]
+fn setup_preview() {
+    let (mut game, rx) = create_basic_game_1();
+    game.moderators.push(MODERATOR);
+    expect_eventkind(&rx, EventKind::Init);
+    let preview = Action::SetupPreview {
+        moderator: MODERATOR,
+    };
+
+    assert!(matches!(
+        game.handle(Action::SetupPreview { moderator: 101 }),
+        Err(InvalidActionError::NotModerator { .. })
+    ));
+    assert!(game.handle(preview.to_owned()).is_ok());
+    match rx.try_recv() {
+        Ok(event) => {
+            assert_eq!(event.audience(), Audience::Player(MODERATOR));
+            match event {
+                Event::SetupPreview { roles, .. } => assert_eq!(
+                    roles,
+                    vec![
+                        (101, Role::TOWN),
+                        (102, Role::COP),
+                        (103, Role::DOCTOR),
+                        (104, Role::MAFIA),
+                        (105, Role::TOWN),
+                    ]
+                ),
+                e => panic!("Expected SetupPreview, got {:?}", e),
+            }
+        }
+        e => panic!("Expected SetupPreview, got {:?}", e),
+    }
+
+    // Nothing reaches the players until the game starts
+    assert!(rx.try_recv().is_err());
+    assert!(game.start().is_ok());
+    rx.try_iter().for_each(drop);
+    assert!(matches!(
+        game.handle(preview),
+        Err(InvalidActionError::InvalidPhase { .. })
+    ));
+}
+
+#[test]
+fn not_voted() {
+    let (mut game, rx) = create_basic_game_1();
+    game.moderators.push(MODERATOR);
+    assert!(game.start().is_ok());
+    vote(&mut game, 101, 104);
+    vote(&mut game, 102, 104);
+    vote(&mut game, 103, 101);
+    // A retracted vote isn't a standing vote
+    assert!(game
+        .handle(Action::Vote {
+            voter: 103,
+            ballot: None
+        })
+        .is_ok());
+    rx.try_iter().for_each(drop);
+
+    assert!(game
+        .handle(Action::NotVoted {
+            moderator: MODERATOR
+        })
+        .is_ok());
+    match rx.try_recv() {
+        Ok(Event::NotVoted { moderator, missing }) => {
+            assert_eq!(moderator, MODERATOR);
+            let missing: Vec<u64> = missing.iter().map(|p| p.user_id).collect();
+            assert_eq!(missing, vec![103, 104, 105]);
+        }
+        e => panic!("Expected NotVoted, got {:?}", e),
+    }
+}
+
+#[test]
+fn modkill() {
+    let (mut game, rx) = create_basic_game_2();
+    game.moderators.push(MODERATOR);
+    assert!(game.start().is_ok());
+    rx.try_iter().for_each(drop);
+    let modkill = |moderator, player| Action::Modkill { moderator, player };
+
+    // Only moderators can modkill
+    assert!(matches!(
+        game.handle(modkill(101, 104)),
+        Err(InvalidActionError::NotModerator { pid: 101 })
+    ));
+
+    // Modkilling the only MAFIA at night ends the game
+    assert!(game.in_night());
+    assert!(game.handle(modkill(MODERATOR, 104)).is_ok());
+    assert!(matches!(
+        rx.try_recv(),
+        Ok(Event::Modkill {
+            role: Role::MAFIA,
+            ..
+        })
+    ));
+    expect_eventkind(&rx, EventKind::Eliminate);
+    assert_eq!(game.dead.len(), 1);
+    assert!(matches!(game.phase, Phase::End(Some(Team::Town), _)));
+
+    // The game is over, so there is no one left to modkill
+    assert!(game.handle(modkill(MODERATOR, 101)).is_err());
+}
+
+#[test]
+fn remove_player() {
+    let (mut game, rx) = create_basic_game_1();
+    game.moderators.push(MODERATOR);
+    game.add_player(Player::new(106, Role::TOWN)).unwrap();
+    rx.try_iter().for_each(drop);
+    let remove = |moderator, player| Action::Remove { moderator, player };
+
+    // A player backing out during signups is simply removed
+    assert!(matches!(
+        game.handle(remove(101, 106)),
+        Err(InvalidActionError::NotModerator { pid: 101 })
+    ));
+    assert!(game.handle(remove(MODERATOR, 106)).is_ok());
+    assert!(game.players.check(106).is_err());
+    assert!(game.dead.is_empty());
+    assert!(rx.try_recv().is_err());
+    assert_eq!(
+        game.remove_player(106),
+        Err(GameError::PlayerNotFound { pid: 106 })
+    );
+
+    // Once the game has started, a player can't be removed
+    assert!(game.start().is_ok());
+    assert!(matches!(
+        game.handle(remove(MODERATOR, 105)),
+        Err(InvalidActionError::InvalidPhase {
+            expected: PhaseKind::Init,
+            ..
+        })
+    ));
+    assert_eq!(game.remove_player(105), Err(GameError::GameStarted));
+    assert_eq!(game.players.len(), 5);
+}
+
+#[test]
+fn join_and_leave() {
+    let (mut game, rx) = create_basic_game_1();
+    rx.try_iter().for_each(drop);
+    let join = |player| Action::Join { player };
+    let leave = |player| Action::Leave { player };
+
+    // Users can sign up, but only once
+    assert!(game.handle(join(106)).is_ok());
+    expect_eventkind(&rx, EventKind::PlayerJoined);
+    assert!(game.players.check(106).is_ok());
+    assert!(matches!(
+        game.handle(join(106)),
+        Err(InvalidActionError::Setup {
+            error: GameError::PlayerExists { pid: 106 }
+        })
+    ));
+    assert!(rx.try_recv().is_err());
+
+    // And back out before the game starts
+    assert!(game.handle(leave(106)).is_ok());
+    match rx.try_recv() {
+        Ok(Event::PlayerLeft { player }) => assert_eq!(player.user_id, 106),
+        e => panic!("Expected PlayerLeft, got {:?}", e),
+    }
+    assert!(matches!(
+        game.handle(leave(106)),
+        Err(InvalidActionError::PlayerNotFound { pid: 106 })
+    ));
+
+    // But not once it has started
+    assert!(game.start().is_ok());
+    assert!(game.handle(join(106)).is_err());
+    assert!(game.handle(leave(101)).is_err());
+    assert_eq!(game.players.len(), 5);
+}
+
+#[test]
+fn max_players() {
+    let (mut game, rx) = create_basic_game_1();
+    assert!(game.set_max_players(Some(6)).is_ok());
+    rx.try_iter().for_each(drop);
+
+    // The sixth player fills the game
+    assert!(game.handle(Action::Join { player: 106 }).is_ok());
+    expect_eventkind(&rx, EventKind::PlayerJoined);
+    assert!(matches!(
+        game.handle(Action::Join { player: 107 }),
+        Err(InvalidActionError::Setup {
+            error: GameError::GameFull { max: 6 }
+        })
+    ));
+    assert_eq!(
+        game.add_player(Player::new(107, Role::TOWN)),
+        Err(GameError::GameFull { max: 6 })
+    );
+    assert!(rx.try_recv().is_err());
+    assert_eq!(game.players.len(), 6);
+
+    // Once someone leaves, there is room again
+    assert!(game.handle(Action::Leave { player: 106 }).is_ok());
+    assert!(game.handle(Action::Join { player: 107 }).is_ok());
+
+    assert!(game.assign_role(107, Role::TOWN).is_ok());
+    assert!(game.start().is_ok());
+    assert_eq!(game.set_max_players(None), Err(GameError::GameStarted));
+}
+
+#[test]
+fn ballot_kinds() {
+    let (mut game, rx) = create_basic_game_1();
+    assert!(game.start().is_ok());
+    rx.try_iter().for_each(drop);
+    let ballots = |game: &Game<u64>| match game.current_phase() {
+        Phase::Day(day) => day.votes.to_owned(),
+        phase => panic!("Expected Day, got {:?}", phase),
+    };
+    let cast = |game: &mut Game<u64>, ballot| game.handle(Action::Vote { voter: 101, ballot });
+
+    // Each ballot from the interface is cast as its own kind of engine Ballot
+    assert!(cast(&mut game, Some(Choice::Player(104))).is_ok());
+    assert_eq!(ballots(&game), vec![(0, Ballot::Player(3))]);
+    expect_eventkind(&rx, EventKind::VoteCast);
+    expect_eventkind(&rx, EventKind::Vote);
+
+    assert!(cast(&mut game, Some(Choice::Abstain)).is_ok());
+    assert_eq!(ballots(&game), vec![(0, Ballot::Abstain)]);
+    expect_eventkind(&rx, EventKind::VoteCast);
+    expect_eventkind(&rx, EventKind::Vote);
+
+    assert!(cast(&mut game, None).is_ok());
+    assert_eq!(ballots(&game), vec![]);
+    expect_eventkind(&rx, EventKind::VoteCast);
+    expect_eventkind(&rx, EventKind::Retract);
+
+    assert!(game.handle(Action::SkipDay { voter: 101 }).is_ok());
+    assert_eq!(ballots(&game), vec![(0, Ballot::Skip)]);
+
+    assert!(matches!(
+        cast(&mut game, Some(Choice::Pair(102, 103))),
+        Err(InvalidActionError::InvalidChoice { .. })
+    ));
+}
+
+#[test]
+fn resolve_names() {
+    let (game, _rx) = create_game(vec![
+        Player::new(101, Role::TOWN).with_name("Alice"),
+        Player::new(102, Role::COP).with_name("Alfred"),
+        Player::new(103, Role::DOCTOR).with_name("Bob"),
+        Player::new(104, Role::MAFIA).with_name("Bobby"),
+        Player::new(105, Role::TOWN),
+    ]);
+
+    // Exact matches, ignoring case, even when the name starts another
+    assert_eq!(game.resolve_name("alice"), Ok(101));
+    assert_eq!(game.resolve_name("BOB"), Ok(103));
+
+    // A unique prefix, or part of a name
+    assert_eq!(game.resolve_name("alf"), Ok(102));
+    assert_eq!(game.resolve_name("bby"), Ok(104));
+
+    let ambiguous = game.resolve_name("al");
+    assert_eq!(
+        ambiguous,
+        Err(GameError::AmbiguousName {
+            name: "al".to_string(),
+            candidates: vec![101, 102]
+        })
+    );
+    assert_eq!(
+        game.resolve_name("carol"),
+        Err(GameError::NameNotFound {
+            name: "carol".to_string()
+        })
+    );
+    assert!(game.resolve_name("").is_err());
+}
+
+#[test]
+fn vote_counts_near_threshold() {
+    let (mut game, rx) = create_basic_game_1();
+    game.rules.vote_counts = VoteCounts::NearThreshold;
+    assert!(game.start().is_ok());
+    rx.try_iter().for_each(drop);
+    let count = |rx: &Receiver<Event<u64>>| {
+        expect_eventkind(rx, EventKind::VoteCast);
+        match rx.try_recv() {
+            Ok(Event::Vote {
+                count, threshold, ..
+            }) => {
+                assert_eq!(threshold, 3);
+                count
+            }
+            e => panic!("Expected Vote, got {:?}", e),
+        }
+    };
+
+    // It takes 3 votes to elect, so only the second and third are shown
+    vote(&mut game, 101, 104);
+    assert_eq!(count(&rx), None);
+    vote(&mut game, 102, 105);
+    assert_eq!(count(&rx), None);
+    vote(&mut game, 103, 104);
+    assert_eq!(count(&rx), Some(2));
+
+    // The election itself isn't affected
+    vote(&mut game, 105, 104);
+    assert_eq!(count(&rx), Some(3));
+    expect_eventkind(&rx, EventKind::Election);
+}
+
+#[test]
+fn extend_phase() {
+    let (mut game, rx) = create_basic_game_1();
+    game.moderators.push(MODERATOR);
+    game.rules.day_length = Some(Duration::from_secs(60));
+    assert!(game.start().is_ok());
+    rx.try_iter().for_each(drop);
+    let extend = |moderator, secs| Action::ExtendPhase {
+        moderator,
+        by: Duration::from_secs(secs),
+    };
+
+    // The timer only starts on the first tick
+    assert!(matches!(
+        game.handle(extend(MODERATOR, 30)),
+        Err(InvalidActionError::NoTimer)
+    ));
+
+    let start = SystemTime::UNIX_EPOCH;
+    let at = |secs| start + Duration::from_secs(secs);
+    game.tick(start);
+    assert_eq!(game.timer.as_ref().unwrap().deadline, at(60));
+
+    assert!(matches!(
+        game.handle(extend(101, 30)),
+        Err(InvalidActionError::NotModerator { pid: 101 })
+    ));
+    assert!(game.handle(extend(MODERATOR, 30)).is_ok());
+    assert_eq!(
+        rx.try_recv(),
+        Ok(Event::PhaseExtended {
+            new_remaining: Duration::from_secs(90)
+        })
+    );
+    assert_eq!(game.timer.as_ref().unwrap().deadline, at(90));
+
+    // The day goes on past its original deadline
+    game.tick(at(70));
+    assert!(game.in_day());
+    game.tick(at(90));
+    assert!(game.in_night());
+}
+
+#[test]
+fn night_report() {
+    let (tx, _rx) = mpsc::channel();
+    let (public_tx, public_rx) = mpsc::channel();
+    let (spectator_tx, spectator_rx) = mpsc::channel();
+    let mut comm = Comm::new(&tx);
+    comm.subscribe_audience(public_tx, Audience::Public);
+    comm.subscribe_audience(spectator_tx, Audience::Spectators);
+
+    let players = vec![
+        Player::new(101, Role::TOWN),
+        Player::new(102, Role::COP),
+        Player::new(103, Role::DOCTOR),
+        Player::new(104, Role::STRIPPER),
+        Player::new(105, Role::MAFIA),
+        Player::new(106, Role::TOWN),
+    ];
+    let mut game = Game::new(1, players, Vec::new(), comm);
+    assert!(game.start().is_ok());
+    assert!(game.in_night());
+
+    // The COP is blocked, and the DOCTOR saves the Mafia's mark
+    assert!(target(&mut game, 102, Choice::Player(105)).is_ok());
+    assert!(target(&mut game, 103, Choice::Player(101)).is_ok());
+    assert!(target(&mut game, 104, Choice::Player(102)).is_ok());
+    mark(&mut game, 105, Choice::Player(101));
+
+    let public: Vec<_> = public_rx.try_iter().collect();
+    assert!(public.iter().any(|e| e.kind() == EventKind::NoKill));
+    assert!(public
+        .iter()
+        .all(|e| !matches!(e.kind(), EventKind::Block | EventKind::NightReport)));
+
+    let report = spectator_rx.try_iter().find_map(|e| match e {
+        Event::NightReport { night_no, actions } => Some((night_no, actions)),
+        _ => None,
+    });
+    let (night_no, actions) = report.expect("Spectators should get a NightReport");
+    assert_eq!(night_no, 1);
+    let outcomes: Vec<(u64, ActionOutcome)> = actions
+        .iter()
+        .map(|a| (a.actor.user_id, a.outcome))
+        .collect();
+    assert_eq!(
+        outcomes,
+        vec![
+            (105, ActionOutcome::Saved),
+            (102, ActionOutcome::Blocked),
+            (103, ActionOutcome::Targeted),
+            (104, ActionOutcome::Targeted),
+        ]
+    );
+    assert_eq!(actions[1].targets[0].user_id, 105);
+}
+
+#[test]
+fn dawn_event_order() {
+    let (mut game, rx) = create_game(vec![
+        Player::new(101, Role::STRIPPER),
+        Player::new(102, Role::COP),
+        Player::new(103, Role::DOCTOR),
+        Player::new(104, Role::VIGILANTE),
+        Player::new(105, Role::MAFIA),
+        Player::new(106, Role::TOWN),
+        Player::new(107, Role::COP),
+        Player::new(108, Role::VIGILANTE),
+        Player::new(109, Role::TOWN),
+        Player::new(110, Role::TOWN),
+    ]);
+    assert!(game.start().is_ok());
+    assert!(game.in_night());
+
+    // Made out of turn order, which must not change the order of the dawn
+    assert!(target(&mut game, 108, Choice::Player(109)).is_ok());
+    assert!(target(&mut game, 107, Choice::Player(101)).is_ok());
+    assert!(target(&mut game, 103, Choice::Player(106)).is_ok());
+    assert!(target(&mut game, 102, Choice::Player(105)).is_ok());
+    assert!(target(&mut game, 104, Choice::Player(110)).is_ok());
+    assert!(target(&mut game, 101, Choice::Player(104)).is_ok());
+    rx.try_iter().for_each(drop);
+    mark(&mut game, 105, Choice::Player(106));
+
+    let mut dawn = Vec::new();
+    for event in rx.try_iter() {
+        let player = match &event {
+            Event::Block { blocked } => Some(blocked.user_id),
+            Event::Strip { stripper, .. } => Some(stripper.user_id),
+            Event::Investigate { cop, .. } => Some(cop.user_id),
+            Event::Save { doctor, .. } => Some(doctor.user_id),
+            Event::Kill { mark, .. } => Some(mark.user_id),
+            _ => None,
+        };
+        dawn.push((event.kind(), player));
+        if event.kind() == EventKind::NightReport {
+            break;
+        }
+    }
+    assert_eq!(
+        dawn,
+        vec![
+            (EventKind::Mark, None),
+            (EventKind::NightComplete, None),
+            (EventKind::Dawn, None),
+            (EventKind::Block, Some(104)),
+            (EventKind::Strip, Some(101)),
+            (EventKind::Block, Some(105)),
+            (EventKind::Save, Some(103)),
+            (EventKind::Investigate, Some(102)),
+            (EventKind::Investigate, Some(107)),
+            (EventKind::Kill, Some(109)),
+            (EventKind::NightReport, None),
+        ]
+    );
+}
+
+fn elect_idiot() -> (Game<u64>, Receiver<Event<u64>>) {
+    let (mut game, rx) = create_game(vec![
+        Player::new(101, Role::IDIOT),
+        Player::new(102, Role::TOWN),
+        Player::new(103, Role::TOWN),
+        Player::new(104, Role::TOWN),
+        Player::new(105, Role::MAFIA),
+        Player::new(106, Role::TOWN),
+        Player::new(107, Role::COP),
+    ]);
+    assert!(game.start().is_ok());
+    for voter in [102, 103, 104, 105] {
+        vote(&mut game, voter, 101);
+    }
+    assert_eq!(game.phase.kind(), PhaseKind::Dusk);
+    match rx.try_iter().last() {
+        Some(Event::Dusk { actor, options }) => {
+            assert_eq!(actor.user_id, 101);
+            let options: Vec<_> = options.iter().map(|o| o.user_id).collect();
+            assert_eq!(options, vec![102, 103, 104, 105]);
+        }
+        e => panic!("Expected Dusk, got {:?}", e),
+    }
+    (game, rx)
+}
+
+#[test]
+fn dusk_target() {
+    let (mut game, rx) = elect_idiot();
+
+    // Only the IDIOT acts at Dusk, and only against someone who voted for them
+    assert!(matches!(
+        target(&mut game, 107, Choice::Player(102)),
+        Err(InvalidActionError::InvalidRole { .. })
+    ));
+    assert!(matches!(
+        target(&mut game, 101, Choice::Player(106)),
+        Err(InvalidActionError::InvalidChoice { .. })
+    ));
+    assert!(matches!(
+        game.handle(Action::Vote {
+            voter: 106,
+            ballot: Some(Choice::Player(101))
+        }),
+        Err(InvalidActionError::InvalidPhase { .. })
+    ));
+
+    assert!(target(&mut game, 101, Choice::Player(105)).is_ok());
+    let mut dead = eliminated(&rx);
+    dead.sort();
+    assert_eq!(dead, vec![101, 105]);
+    // Killing the only MAFIA ends the game
+    assert_eq!(game.phase.kind(), PhaseKind::End);
+}
+
+#[test]
+fn dusk_deadline() {
+    let (mut game, rx) = elect_idiot();
+    game.rules.dusk_length = Some(Duration::from_secs(30));
+
+    game.tick(secs(0));
+    game.tick(secs(20));
+    assert_eq!(game.phase.kind(), PhaseKind::Dusk);
+    game.tick(secs(30));
+    assert_eq!(eliminated(&rx), vec![101]);
+    assert_eq!(game.phase.kind(), PhaseKind::Night);
+    assert_eq!(game.phase.number(), Some(1));
+
+    // Without a dusk_length, Dusk still ends on its own
+    let (mut game, rx) = elect_idiot();
+    game.tick(secs(0));
+    game.tick(secs(DUSK_LENGTH.as_secs()));
+    assert_eq!(eliminated(&rx), vec![101]);
+    assert_eq!(game.phase.kind(), PhaseKind::Night);
+}
+
+#[test]
+fn idle_through_dusk() {
+    let (mut game, rx) = elect_idiot();
+    game.rules.idle_modkill = Some(1);
+
+    // The Day's votes still count once Dusk is over
+    assert!(target(&mut game, 101, Choice::Player(102)).is_ok());
+    let idle: Vec<_> = rx
+        .try_iter()
+        .filter_map(|e| match e {
+            Event::IdleModkill { player, .. } => Some(player.user_id),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(idle, vec![106, 107]);
+    assert!(game.in_night());
+}
+
+fn elect_idiot_under(idiot_elect: IdiotElect) -> (Game<u64>, Receiver<Event<u64>>) {
+    let (mut game, rx) = create_game(vec![
+        Player::new(101, Role::IDIOT),
+        Player::new(102, Role::COP),
+        Player::new(103, Role::TOWN),
+        Player::new(104, Role::TOWN),
+        Player::new(105, Role::MAFIA),
+        Player::new(106, Role::TOWN),
+        Player::new(107, Role::TOWN),
+        Player::new(108, Role::MAFIA),
+    ]);
+    game.rules.idiot_elect = idiot_elect;
+    game.rules.start_night = StartNight::Never;
+    assert!(game.start().is_ok());
+    for voter in [102, 103, 104, 105, 106] {
+        vote(&mut game, voter, 101);
+    }
+    (game, rx)
+}
+
+#[test]
+fn idiot_elect_rules() {
+    // None: the IDIOT is eliminated like anyone else
+    let (game, rx) = elect_idiot_under(IdiotElect::None);
+    assert_eq!(eliminated(&rx), vec![101]);
+    assert_eq!(game.phase.kind(), PhaseKind::Night);
+
+    // Day: a new Day begins instead of the Night
+    let (game, rx) = elect_idiot_under(IdiotElect::Day);
+    assert_eq!(eliminated(&rx), vec![101]);
+    assert_eq!(game.phase.kind(), PhaseKind::Day);
+    assert_eq!(game.phase.number(), Some(2));
+
+    // Cull: everyone who voted for the IDIOT goes with them
+    let (game, rx) = elect_idiot_under(IdiotElect::Cull);
+    let mut dead = eliminated(&rx);
+    dead.sort();
+    assert_eq!(dead, vec![101, 102, 103, 104, 105, 106]);
+    assert!(matches!(game.phase, Phase::End(Some(Team::Mafia), _)));
+
+    // Stun: the electors can't act tonight, and aren't waited on
+    let (mut game, rx) = elect_idiot_under(IdiotElect::Stun);
+    assert_eq!(eliminated(&rx), vec![101]);
+    assert!(target(&mut game, 102, Choice::Player(108)).is_ok());
+    let blocked = rx.try_iter().last();
+    assert!(matches!(blocked, Some(Event::Block { blocked }) if blocked.user_id == 102));
+    mark(&mut game, 105, Choice::Player(107));
+    assert_eq!(game.phase.kind(), PhaseKind::Night);
+    // The unstunned Mafia member makes the kill, and no one investigates
+    mark(&mut game, 108, Choice::Player(107));
+    assert!(rx
+        .try_iter()
+        .all(|e| !matches!(e, Event::Investigate { .. })));
+    assert_eq!(game.phase.kind(), PhaseKind::Day);
+}
+
+fn avenger_game() -> (Game<u64>, Receiver<Event<u64>>) {
+    let (mut game, rx) = create_game(vec![
+        Player::new(101, Role::AVENGER),
+        Player::new(102, Role::TOWN),
+        Player::new(103, Role::TOWN),
+        Player::new(104, Role::MAFIA),
+        Player::new(105, Role::TOWN),
+    ]);
+    assert!(game.start().is_ok());
+    rx.try_iter().for_each(drop);
+    (game, rx)
+}
+
+#[test]
+fn avenger_elected() {
+    let (mut game, rx) = avenger_game();
+    assert!(matches!(
+        target(&mut game, 101, Choice::Player(101)),
+        Err(InvalidActionError::InvalidTarget { .. })
+    ));
+    assert!(target(&mut game, 101, Choice::Player(102)).is_ok());
+    expect_eventkind(&rx, EventKind::ActionConfirmed);
+
+    for voter in [102, 103, 104] {
+        vote(&mut game, voter, 101);
+    }
+    let events: Vec<_> = rx.try_iter().collect();
+    match events.iter().find(|e| e.kind() == EventKind::Avenged) {
+        Some(Event::Avenged { avenger, victim }) => {
+            assert_eq!((avenger.user_id, victim.user_id), (101, 102));
+        }
+        e => panic!("Expected Avenged, got {:?}", e),
+    }
+    let mut dead: Vec<_> = game.dead.iter().map(|p| p.user_id).collect();
+    dead.sort();
+    assert_eq!(dead, vec![101, 102]);
+    assert_eq!(game.phase.kind(), PhaseKind::Night);
+}
+
+#[test]
+fn avenger_ends_game() {
+    let (mut game, rx) = avenger_game();
+    assert!(target(&mut game, 101, Choice::Player(104)).is_ok());
+
+    // Taking the last MAFIA down wins the game for the Town
+    for voter in [102, 103, 104] {
+        vote(&mut game, voter, 101);
+    }
+    assert!(matches!(game.phase, Phase::End(Some(Team::Town), _)));
+    let mut dead = eliminated(&rx);
+    dead.sort();
+    assert_eq!(dead, vec![101, 104]);
+}
+
+#[test]
+fn mafia_kill_mandatory() {
+    let (mut game, _rx) = create_basic_game_2();
+    game.rules.mafia_kill = MafiaKill::Mandatory;
+    assert!(game.start().is_ok());
+    assert!(matches!(
+        game.handle(Action::Mark {
+            killer: 104,
+            mark: Choice::Abstain
+        }),
+        Err(InvalidActionError::InvalidChoice { .. })
+    ));
+    mark(&mut game, 104, Choice::Player(101));
+}
+
+#[test]
+fn mafia_kill_optional() {
+    let (mut game, rx) = create_basic_game_2();
+    assert_eq!(game.rules.mafia_kill, MafiaKill::Optional);
+    assert!(game.start().is_ok());
+    target(&mut game, 102, Choice::Player(104)).unwrap();
+    target(&mut game, 103, Choice::Player(103)).unwrap();
+    mark(&mut game, 104, Choice::Abstain);
+    let kinds: Vec<_> = rx.try_iter().map(|e| e.kind()).collect();
+    assert!(kinds.contains(&EventKind::NoKill));
+    assert!(!kinds.contains(&EventKind::Kill));
+}
+
+#[test]
+fn action_lock() {
+    let (mut game, rx) = create_basic_game_2();
+    game.rules.night_length = Some(Duration::from_secs(60));
+    game.rules.action_lock = Some(Duration::from_secs(10));
+    assert!(game.start().is_ok());
+    game.tick(secs(0));
+    game.tick(secs(45));
+    mark(&mut game, 104, Choice::Player(101));
+    rx.try_iter().for_each(drop);
+
+    // Within the last 10 seconds, the Mafia can't change their mark
+    game.tick(secs(50));
+    assert!(matches!(
+        game.handle(Action::Mark {
+            killer: 104,
+            mark: Choice::Player(102)
+        }),
+        Err(InvalidActionError::ActionsLocked)
+    ));
+    match rx.try_recv() {
+        Ok(Event::ActionsLocked { player, action }) => {
+            assert_eq!(player.user_id, 104);
+            assert_eq!(action, ActionKind::Mark);
+        }
+        e => panic!("Expected ActionsLocked, got {:?}", e),
+    }
+    assert!(matches!(
+        target(&mut game, 102, Choice::Player(104)),
+        Err(InvalidActionError::ActionsLocked)
+    ));
+
+    game.tick(secs(60));
+    let killed: Vec<_> = rx
+        .try_iter()
+        .filter_map(|e| match e {
+            Event::Kill { mark, .. } => Some(mark.user_id),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(killed, vec![101]);
+}
+
+#[test]
+fn excuse_player() {
+    let (mut game, rx) = create_basic_game_2();
+    game.rules.start_night = StartNight::Never;
+    game.moderators.push(MODERATOR);
+    assert!(game.start().is_ok());
+    vote(&mut game, 103, 104);
+    rx.try_iter().for_each(drop);
+
+    let excuse = |player| Action::Excuse {
+        moderator: MODERATOR,
+        player,
+    };
+    assert!(matches!(
+        game.handle(Action::Excuse {
+            moderator: 101,
+            player: 103
+        }),
+        Err(InvalidActionError::NotModerator { .. })
+    ));
+    assert!(game.handle(excuse(103)).is_ok());
+    match rx.try_recv() {
+        Ok(Event::PlayerExcused { player, threshold }) => {
+            assert_eq!(player.user_id, 103);
+            // 3 of 4 players were needed, now 2 of the 3 who can vote
+            assert_eq!(threshold, 2);
+        }
+        e => panic!("Expected PlayerExcused, got {:?}", e),
+    }
+    assert!(matches!(
+        game.handle(Action::Vote {
+            voter: 103,
+            ballot: Some(Choice::Player(104))
+        }),
+        Err(InvalidActionError::Excused)
+    ));
+
+    // 103's vote was dropped, so it takes two more
+    vote(&mut game, 101, 104);
+    assert_eq!(game.phase.kind(), PhaseKind::Day);
+    vote(&mut game, 102, 104);
+    assert_eq!(expect_elected(&rx), Some(104));
+}
+
+#[test]
+fn game_from_setup() {
+    let spec = vec![
+        (101, Some("Alice".to_string()), Role::TOWN),
+        (102, Some("Bob".to_string()), Role::COP),
+        (103, None, Role::DOCTOR),
+        (104, Some("Dave".to_string()), Role::MAFIA),
+        (105, None, Role::TOWN),
+    ];
+    let (tx, rx): (Sender<Event<u64>>, Receiver<Event<u64>>) = mpsc::channel();
+    let mut game = Game::from_setup(1, spec.clone(), Comm::new(&tx)).unwrap();
+    assert_eq!(game.resolve_name("bob"), Ok(102));
+    assert_eq!(game.players[2].name, None);
+    assert!(game.start().is_ok());
+    expect_eventkind(&rx, EventKind::Init);
+    expect_eventkind(&rx, EventKind::Start);
+    assert_eq!(game.phase.kind(), PhaseKind::Day);
+
+    let mut repeated = spec;
+    repeated.push((103, Some("Carol".to_string()), Role::TOWN));
+    assert!(matches!(
+        Game::from_setup(2, repeated, Comm::new(&tx)),
+        Err(GameError::PlayerExists { pid: 103 })
+    ));
+}
+
+#[test]
+fn legal_targets() {
+    let (mut game, rx) = create_basic_game_2();
+    game.rules.ability_limits.insert(Role::DOCTOR, 1);
+    assert!(game.start().is_ok());
+    rx.try_iter().for_each(drop);
+
+    // A COP can't investigate themself
+    assert_eq!(game.legal_targets(1), vec![0, 2, 3]);
+    assert!(game.handle(Action::LegalTargets { player: 102 }).is_ok());
+    match rx.try_recv() {
+        Ok(Event::LegalTargets { player, targets }) => {
+            assert_eq!(player.user_id, 102);
+            let targets: Vec<_> = targets.iter().map(|p| p.user_id).collect();
+            assert_eq!(targets, vec![101, 103, 104]);
+        }
+        e => panic!("Expected LegalTargets, got {:?}", e),
+    }
+    // A DOCTOR can save themself by default, but TOWN has no Target
+    assert_eq!(game.legal_targets(2), vec![0, 1, 2, 3]);
+    assert!(game.legal_targets(0).is_empty());
+
+    // Once the DOCTOR's only save is spent, they can only change tonight's target
+    target(&mut game, 103, Choice::Player(101)).unwrap();
+    assert_eq!(game.legal_targets(2), vec![0, 1, 2, 3]);
+
+    // Once the Night is over, no one has a Target
+    target(&mut game, 102, Choice::Player(104)).unwrap();
+    mark(&mut game, 104, Choice::Player(101));
+    assert!(game.in_day());
+    assert!(game.legal_targets(1).is_empty());
+}
+
+#[test]
+fn dead_spectator() {
+    let (mut game, rx) = create_basic_game_1();
+    game.rules.ghost_vote = true;
+    game.moderators.push(MODERATOR);
+    assert!(game.start().is_ok());
+    assert!(game
+        .handle(Action::Modkill {
+            moderator: MODERATOR,
+            player: 105
+        })
+        .is_ok());
+    let (tx, spectator_rx) = mpsc::channel();
+    assert!(game.spectate(105, tx, SpectatorView::FullInfo).is_ok());
+    rx.try_iter().for_each(drop);
+
+    // Watching doesn't take away the ghost vote, but only shows public Events
+    // until it is cast, so it can't be cast knowing every role
+    vote(&mut game, 105, 104);
+    assert!(rx.try_iter().any(|e| e.kind() == EventKind::GhostVote));
+    let seen: Vec<_> = spectator_rx.try_iter().collect();
+    assert!(seen.iter().any(|e| e.kind() == EventKind::GhostVote));
+    assert!(seen.iter().all(|e| e.audience() == Audience::Public));
+
+    // Once it is cast, they can watch with every Event
+    let (tx, spectator_rx) = mpsc::channel();
+    assert!(game.spectate(105, tx, SpectatorView::FullInfo).is_ok());
+    vote(&mut game, 101, 104);
+    assert!(spectator_rx
+        .try_iter()
+        .any(|e| matches!(e, Event::VoteCast { voter, .. } if voter.user_id == 101)));
+}
+
+#[test]
+fn ghost_vote() {
+    let (mut game, rx) = create_basic_game_1();
+    game.rules.ghost_vote = true;
+    game.moderators.push(MODERATOR);
+    assert!(game.start().is_ok());
+    assert!(game
+        .handle(Action::Modkill {
+            moderator: MODERATOR,
+            player: 105
+        })
+        .is_ok());
+    rx.try_iter().for_each(drop);
+
+    // 3 of the 4 living players are needed, and the ghost counts towards them
+    vote(&mut game, 105, 104);
+    expect_eventkind(&rx, EventKind::VoteCast);
+    match rx.try_recv() {
+        Ok(Event::GhostVote {
+            ghost,
+            ballot,
+            count,
+            threshold,
+        }) => {
+            assert_eq!(ghost.map(|g| g.user_id), Some(105));
+            assert_eq!(ballot.map(|b| b.user_id), Some(104));
+            assert_eq!((count, threshold), (1, 3));
+        }
+        e => panic!("Expected GhostVote, got {:?}", e),
+    }
+    assert!(matches!(
+        game.handle(Action::Vote {
+            voter: 105,
+            ballot: Some(Choice::Player(101))
+        }),
+        Err(InvalidActionError::GhostVoteFinal)
+    ));
+
+    vote(&mut game, 101, 104);
+    vote(&mut game, 102, 104);
+    assert_eq!(expect_elected(&rx), Some(104));
+    assert_eq!(game.ghosts_spent, vec![105]);
+}
+
+#[test]
+fn block_is_private() {
+    let (tx, _rx) = mpsc::channel();
+    let (public_tx, public_rx) = mpsc::channel();
+    let (cop_tx, cop_rx) = mpsc::channel();
+    let (stripper_tx, stripper_rx) = mpsc::channel();
+    let mut comm = Comm::new(&tx);
+    comm.subscribe_audience(public_tx, Audience::Public);
+    comm.subscribe_audience(cop_tx, Audience::Player(102));
+    comm.subscribe_audience(stripper_tx, Audience::Player(104));
+
+    let players = vec![
+        Player::new(101, Role::TOWN),
+        Player::new(102, Role::COP),
+        Player::new(103, Role::TOWN),
+        Player::new(104, Role::STRIPPER),
+        Player::new(105, Role::MAFIA),
+        Player::new(106, Role::TOWN),
+    ];
+    let mut game = Game::new(1, players, Vec::new(), comm);
+    assert!(game.start().is_ok());
+    assert!(target(&mut game, 102, Choice::Player(105)).is_ok());
+    assert!(target(&mut game, 104, Choice::Player(102)).is_ok());
+    mark(&mut game, 105, Choice::Player(101));
+
+    // The COP learns their investigation was blocked, and gets no result
+    let cop: Vec<_> = cop_rx.try_iter().map(|e| e.kind()).collect();
+    assert!(cop.contains(&EventKind::Block));
+    assert!(!cop.contains(&EventKind::Investigate));
+    match stripper_rx
+        .try_iter()
+        .find(|e| e.kind() == EventKind::Strip)
+    {
+        Some(Event::Strip { stripper, blocked }) => {
+            assert_eq!((stripper.user_id, blocked.user_id), (104, 102));
+        }
+        e => panic!("Expected Strip, got {:?}", e),
+    }
+    assert!(public_rx
+        .try_iter()
+        .all(|e| !matches!(e.kind(), EventKind::Block | EventKind::Strip)));
+}
+
+#[test]
+fn strip_killer() {
+    let (mut game, rx) = create_game(vec![
+        Player::new(101, Role::TOWN),
+        Player::new(102, Role::STRIPPER),
+        Player::new(103, Role::MAFIA),
+        Player::new(104, Role::TOWN),
+        Player::new(105, Role::TOWN),
+        Player::new(106, Role::TOWN),
+    ]);
+    assert!(game.start().is_ok());
+    assert!(game.in_night());
+    rx.try_iter().for_each(drop);
+
+    assert!(target(&mut game, 102, Choice::Player(103)).is_ok());
+    mark(&mut game, 103, Choice::Player(101));
+
+    let events: Vec<_> = rx.try_iter().collect();
+    assert!(events
+        .iter()
+        .any(|e| matches!(e, Event::Block { blocked } if blocked.user_id == 103)));
+    assert!(events.iter().any(|e| e.kind() == EventKind::Strip));
+    assert!(events.iter().any(|e| e.kind() == EventKind::NoKill));
+    assert!(events.iter().all(|e| e.kind() != EventKind::Kill));
+    assert!(game.in_day());
+    assert_eq!(game.players.len(), 6);
+}
+
+#[test]
+fn strip_stripper_killer() {
+    let (mut game, rx) = create_game(vec![
+        Player::new(101, Role::TOWN),
+        Player::new(102, Role::STRIPPER),
+        Player::new(103, Role::STRIPPER),
+        Player::new(104, Role::COP),
+        Player::new(105, Role::TOWN),
+        Player::new(106, Role::TOWN),
+        Player::new(107, Role::TOWN),
+        Player::new(108, Role::TOWN),
+    ]);
+    assert!(game.start().is_ok());
+    rx.try_iter().for_each(drop);
+
+    // 102 is a STRIPPER, but makes the Mafia's kill instead of their own strip.
+    // They are blocked in their Mafia capacity, so the kill is blocked
+    assert!(target(&mut game, 102, Choice::Player(104)).is_ok());
+    assert!(target(&mut game, 103, Choice::Player(102)).is_ok());
+    assert!(target(&mut game, 104, Choice::Player(103)).is_ok());
+    mark(&mut game, 102, Choice::Player(101));
+
+    let events: Vec<_> = rx.try_iter().collect();
+    let blocked: Vec<_> = events
+        .iter()
+        .filter_map(|e| match e {
+            Event::Block { blocked } => Some(blocked.user_id),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(blocked, vec![102]);
+    assert!(events.iter().any(|e| e.kind() == EventKind::NoKill));
+    // Making the kill retracted 102's strip, so the COP isn't blocked
+    assert!(events.iter().any(|e| e.kind() == EventKind::Investigate));
+}
+
+// The DOCTOR saves 101, who is shot by the VIGILANTE, and marked by the Mafia if `mafia`
+fn save_against(rule: SaveAgainst, mafia: bool) -> (Game<u64>, Vec<Event<u64>>) {
+    let (mut game, rx) = create_game(vec![
+        Player::new(101, Role::TOWN),
+        Player::new(102, Role::VIGILANTE),
+        Player::new(103, Role::DOCTOR),
+        Player::new(104, Role::TOWN),
+        Player::new(105, Role::MAFIA),
+        Player::new(106, Role::TOWN),
+    ]);
+    game.rules.save_against = rule;
+    assert!(game.start().is_ok());
+    rx.try_iter().for_each(drop);
+
+    assert!(target(&mut game, 102, Choice::Player(101)).is_ok());
+    assert!(target(&mut game, 103, Choice::Player(101)).is_ok());
+    match mafia {
+        true => mark(&mut game, 105, Choice::Player(101)),
+        false => mark(&mut game, 105, Choice::Abstain),
+    }
+    let events = rx.try_iter().collect();
+    (game, events)
+}
+
+fn saves(events: &[Event<u64>]) -> usize {
+    events
+        .iter()
+        .filter(|e| e.kind() == EventKind::Save)
+        .count()
+}
+
+#[test]
+fn save_against_vigilante() {
+    let (game, events) = save_against(SaveAgainst::AnyKill, false);
+    assert_eq!(saves(&events), 1);
+    assert!(events.iter().any(|e| e.kind() == EventKind::NoKill));
+    assert_eq!(game.players.len(), 6);
+
+    let (game, events) = save_against(SaveAgainst::MafiaKill, false);
+    assert_eq!(saves(&events), 0);
+    assert_eq!(game.dead[0].user_id, 101);
+}
+
+#[test]
+fn save_against_two_kills() {
+    // The save covers both the Mafia's kill and the VIGILANTE's shot
+    let (game, events) = save_against(SaveAgainst::AnyKill, true);
+    assert_eq!(saves(&events), 2);
+    assert_eq!(game.players.len(), 6);
+
+    // Only the Mafia's kill is saved, so the shot still kills
+    let (game, events) = save_against(SaveAgainst::MafiaKill, true);
+    assert_eq!(saves(&events), 1);
+    assert_eq!(game.dead[0].user_id, 101);
+    match events.iter().find(|e| e.kind() == EventKind::Kill) {
+        Some(Event::Kill { killer, .. }) => assert_eq!(killer.user_id, 102),
+        e => panic!("Expected Kill, got {:?}", e),
+    }
+}
+
+#[test]
+fn resolution_trace() {
+    let (mut game, rx) = create_game(vec![
+        Player::new(101, Role::TOWN),
+        Player::new(102, Role::STRIPPER),
+        Player::new(103, Role::COP),
+        Player::new(104, Role::DOCTOR),
+        Player::new(105, Role::MAFIA),
+        Player::new(106, Role::TOWN),
+    ]);
+    assert!(game.start().is_ok());
+    assert!(game.in_night());
+    rx.try_iter().for_each(drop);
+
+    assert!(target(&mut game, 102, Choice::Player(103)).is_ok());
+    assert!(target(&mut game, 103, Choice::Player(105)).is_ok());
+    assert!(target(&mut game, 104, Choice::Player(101)).is_ok());
+    mark(&mut game, 105, Choice::Player(101));
+    assert!(game.in_day());
+
+    let trace = rx
+        .try_iter()
+        .find(|e| e.kind() == EventKind::ResolutionTrace);
+    assert_eq!(
+        trace.as_ref().map(|e| e.audience()),
+        Some(Audience::Spectators)
+    );
+    let steps = match trace {
+        Some(Event::ResolutionTrace { night_no, steps }) => {
+            assert_eq!(night_no, 1);
+            steps
+        }
+        e => panic!("Expected ResolutionTrace, got {:?}", e),
+    };
+    assert_eq!(steps.len(), 2);
+    match &steps[0] {
+        ResolutionStep::Blocked { blocked, strippers } => {
+            assert_eq!(blocked.user_id, 103);
+            assert_eq!(strippers[0].user_id, 102);
+        }
+        s => panic!("Expected Blocked, got {:?}", s),
+    }
+    match &steps[1] {
+        ResolutionStep::Saved {
+            killer,
+            saved,
+            doctors,
+        } => {
+            assert_eq!(killer.user_id, 105);
+            assert_eq!(saved.user_id, 101);
+            assert_eq!(doctors[0].user_id, 104);
+        }
+        s => panic!("Expected Saved, got {:?}", s),
+    }
+}
+
+#[test]
+fn unassigned_role() {
+    let (mut game, rx) = create_game(vec![
+        Player::new(101, Role::TOWN),
+        Player::new(102, Role::COP),
+        Player::new(103, Role::MAFIA),
+    ]);
+    for player in [104, 105] {
+        assert!(game.handle(Action::Join { player }).is_ok());
+    }
+    assert!(matches!(
+        rx.try_iter().last(),
+        Some(Event::PlayerJoined { player }) if player.role == Role::UNASSIGNED
+    ));
+
+    assert_eq!(
+        game.validate_setup(),
+        Err(vec![
+            GameError::Unassigned { pid: 104 },
+            GameError::Unassigned { pid: 105 },
+        ])
+    );
+    assert_eq!(game.start(), Err(GameError::Unassigned { pid: 104 }));
+    assert_eq!(game.phase, Phase::Init);
+
+    assert!(game.assign_role(104, Role::DOCTOR).is_ok());
+    assert!(game.assign_role(105, Role::TOWN).is_ok());
+    assert_eq!(
+        game.assign_role(106, Role::TOWN),
+        Err(GameError::PlayerNotFound { pid: 106 })
+    );
+    assert!(game.start().is_ok());
+    expect_eventkind(&rx, EventKind::Start);
+    assert_eq!(game.players[3].role, Role::DOCTOR);
+    assert_eq!(
+        game.assign_role(105, Role::COP),
+        Err(GameError::GameStarted)
+    );
+}
+
+#[test]
+fn setup_announce() {
+    let (mut game, rx) = create_game(vec![
+        Player::new(101, Role::TOWN),
+        Player::new(102, Role::COP),
+        Player::new(103, Role::DOCTOR),
+        Player::new(104, Role::MAFIA),
+        Player::new(105, Role::TOWN),
+        Player::new(106, Role::MAFIA),
+        Player::new(107, Role::TOWN),
+    ]);
+    game.rules.start_info = StartInfo::Role;
+    assert!(game.start().is_ok());
+    expect_eventkind(&rx, EventKind::Init);
+    expect_eventkind(&rx, EventKind::Start);
+
+    let event = rx.try_recv().unwrap();
+    assert_eq!(event.audience(), Audience::Public);
+    assert_eq!(
+        event,
+        Event::SetupAnnounce {
+            role_counts: vec![
+                (Role::COP, 1),
+                (Role::DOCTOR, 1),
+                (Role::MAFIA, 2),
+                (Role::TOWN, 3),
+            ]
+        }
+    );
+    // Only the roles are announced, never who has them
+    let text = event.to_string();
+    assert!((101..=107).all(|id| !text.contains(&id.to_string())));
+    assert_eq!(
+        render(&event, &English, |id| id.to_string()),
+        "This game: 1 COP, 1 DOCTOR, 2 MAFIA, 3 TOWN"
+    );
+
+    // By default, only how many players are Mafia Aligned is announced
+    let (mut game, rx) = create_basic_game_1();
+    assert!(game.start().is_ok());
+    let events: Vec<_> = rx.try_iter().collect();
+    assert!(events.contains(&Event::MafiaAnnounce {
+        mafia: 1,
+        others: 4
+    }));
+    assert!(events.iter().all(|e| e.kind() != EventKind::SetupAnnounce));
+
+    let (mut game, rx) = create_basic_game_1();
+    game.rules.start_info = StartInfo::Team;
+    assert!(game.start().is_ok());
+    assert!(rx.try_iter().any(|e| e
+        == Event::TeamAnnounce {
+            team_counts: vec![(Team::Town, 4), (Team::Mafia, 1)]
+        }));
+}
+
+#[test]
+fn comm_tx_to() {
+    let (tx, rx) = mpsc::channel();
+    let (public_tx, public_rx) = mpsc::channel();
+    let mut comm: Comm<u64> = Comm::new(&tx);
+    comm.subscribe_audience(public_tx, Audience::Public);
+    let mut players = Vec::new();
+    for id in [101, 102, 103] {
+        let (player_tx, player_rx) = mpsc::channel();
+        comm.subscribe_audience(player_tx, Audience::Player(id));
+        players.push(player_rx);
+    }
+
+    let event = Event::TimeWarning {
+        remaining: Duration::from_secs(30),
+    };
+    assert!(comm.tx_to(&[101, 103], event.clone()).is_ok());
+    assert_eq!(
+        players[0].try_iter().collect::<Vec<_>>(),
+        vec![event.clone()]
+    );
+    assert!(players[1].try_recv().is_err());
+    assert_eq!(
+        players[2].try_iter().collect::<Vec<_>>(),
+        vec![event.clone()]
+    );
+    assert!(public_rx.try_recv().is_err());
+
+    // The main Transport still sees it, numbered like any other Event
+    assert_eq!(rx.try_recv(), Ok(event.clone()));
+    assert_eq!(comm.last_seq(), 1);
+    assert_eq!(comm.history(), vec![event]);
+}
+
+#[test]
+fn idle_modkill() {
+    let (mut game, rx) = create_basic_game_1();
+    game.rules.idle_modkill = Some(2);
+    assert!(game.start().is_ok());
+
+    // 105 makes no action during Day 1
+    vote(&mut game, 102, 101);
+    vote(&mut game, 103, 101);
+    vote(&mut game, 104, 101);
+    assert!(game.in_night());
+    assert_eq!(game.idle.get(&105), Some(&1));
+    assert_eq!(game.players.len(), 4);
+
+    // A TOWN has nothing to do at Night, so Night 1 doesn't count against them
+    assert!(target(&mut game, 102, Choice::Player(104)).is_ok());
+    assert!(target(&mut game, 103, Choice::Player(102)).is_ok());
+    mark(&mut game, 104, Choice::Abstain);
+    assert!(game.in_day());
+    assert_eq!(game.idle.get(&105), Some(&1));
+
+    // Nor do they act during Day 2
+    vote(&mut game, 102, 103);
+    vote(&mut game, 103, 103);
+    vote(&mut game, 104, 103);
+
+    let events: Vec<_> = rx.try_iter().collect();
+    let idle: Vec<_> = events
+        .iter()
+        .filter_map(|e| match e {
+            Event::IdleModkill { player, phases } => Some((player.user_id, *phases)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(idle, vec![(105, 2)]);
+    assert_eq!(game.dead.last().unwrap().user_id, 105);
+    assert_eq!(game.players.len(), 2);
+    assert!(game.idle.is_empty());
+}
+
+#[test]
+fn pending_actor_dies() {
+    let (mut game, rx) = create_basic_game_2();
+    game.moderators.push(MODERATOR);
+    assert!(game.start().is_ok());
+    assert!(target(&mut game, 102, Choice::Player(104)).is_ok());
+    mark(&mut game, 104, Choice::Player(101));
+    rx.try_iter().for_each(drop);
+
+    // The DOCTOR dies before making their action
+    let modkill = Action::Modkill {
+        moderator: MODERATOR,
+        player: 103,
+    };
+    assert!(game.handle(modkill).is_ok());
+    assert!(game.in_night());
+
+    // The night's actions were cleared along with the death, and the night
+    // resolves without waiting on the dead DOCTOR
+    assert!(target(&mut game, 102, Choice::Player(104)).is_ok());
+    mark(&mut game, 104, Choice::Abstain);
+    assert!(game.in_day());
+    let events: Vec<_> = rx.try_iter().collect();
+    assert!(events.iter().any(|e| e.kind() == EventKind::NightComplete));
+    assert!(events.iter().any(|e| e.kind() == EventKind::Investigate));
+    assert_eq!(game.dead.len(), 1);
+    assert_eq!(game.players.len(), 3);
+}
+
+#[test]
+fn confirm_hammer() {
+    let (mut game, rx) = create_basic_game_1();
+    game.rules.confirm_hammer = true;
+    assert!(game.start().is_ok());
+    rx.try_iter().for_each(drop);
+    let confirm = |voter| Action::ConfirmHammer { voter };
+
+    assert!(matches!(
+        game.handle(confirm(101)),
+        Err(InvalidActionError::NoPendingHammer)
+    ));
+    vote(&mut game, 101, 104);
+    vote(&mut game, 102, 104);
+    vote(&mut game, 103, 104);
+    assert!(game.in_day());
+    match rx.try_iter().last() {
+        Some(Event::HammerPending { hammer, elected }) => {
+            assert_eq!(hammer.user_id, 103);
+            assert_eq!(elected.user_id, 104);
+        }
+        e => panic!("Expected HammerPending, got {:?}", e),
+    }
+
+    // Only the hammer can confirm
+    assert!(matches!(
+        game.handle(confirm(101)),
+        Err(InvalidActionError::NoPendingHammer)
+    ));
+    assert!(game.handle(confirm(103)).is_ok());
+    assert_eq!(eliminated(&rx), vec![104]);
+    assert!(matches!(game.phase, Phase::End(Some(Team::Town), _)));
+}
+
+#[test]
+fn confirm_hammer_repend() {
+    let (mut game, rx) = create_basic_game_1();
+    game.rules.confirm_hammer = true;
+    assert!(game.start().is_ok());
+    for voter in [101, 102, 103, 105] {
+        vote(&mut game, voter, 104);
+    }
+    rx.try_iter().for_each(drop);
+
+    // The ballot still holds once the hammer retracts, so the latest pile-on takes over
+    assert!(game
+        .handle(Action::Vote {
+            voter: 103,
+            ballot: None,
+        })
+        .is_ok());
+    let events: Vec<_> = rx.try_iter().collect();
+    assert!(events.iter().any(|e| matches!(
+        e,
+        Event::HammerCancelled { hammer, .. } if hammer.user_id == 103
+    )));
+    assert!(matches!(
+        events.last(),
+        Some(Event::HammerPending { hammer, elected }) if hammer.user_id == 105 && elected.user_id == 104
+    ));
+    assert!(game.handle(Action::ConfirmHammer { voter: 105 }).is_ok());
+    assert_eq!(eliminated(&rx), vec![104]);
+}
+
+#[test]
+fn confirm_hammer_retract() {
+    let (mut game, rx) = create_basic_game_1();
+    game.rules.confirm_hammer = true;
+    assert!(game.start().is_ok());
+    vote(&mut game, 101, 104);
+    vote(&mut game, 102, 104);
+    vote(&mut game, 103, 104);
+    rx.try_iter().for_each(drop);
+
+    // Another elector retracting drops the ballot below the threshold
+    assert!(game
+        .handle(Action::Vote {
+            voter: 101,
+            ballot: None,
+        })
+        .is_ok());
+    let events: Vec<_> = rx.try_iter().collect();
+    assert!(events.iter().any(|e| matches!(
+        e,
+        Event::HammerCancelled { hammer, ballot: Some(b) } if hammer.user_id == 103 && b.user_id == 104
+    )));
+    assert!(matches!(
+        game.handle(Action::ConfirmHammer { voter: 103 }),
+        Err(InvalidActionError::NoPendingHammer)
+    ));
+    assert!(game.in_day());
+    assert_eq!(game.players.len(), 5);
+
+    // Voting back to the threshold makes that vote the pending hammer instead
+    vote(&mut game, 105, 104);
+    assert!(matches!(
+        rx.try_iter().last(),
+        Some(Event::HammerPending { hammer, .. }) if hammer.user_id == 105
+    ));
+    assert!(game.handle(Action::ConfirmHammer { voter: 105 }).is_ok());
+    assert_eq!(game.dead[0].user_id, 104);
+}
+
+fn elect_with_grace() -> (Game<u64>, Receiver<Event<u64>>) {
+    let (mut game, rx) = create_basic_game_1();
+    game.moderators.push(MODERATOR);
+    game.rules.hammer_grace = Some(Duration::from_secs(10));
+    assert!(game.start().is_ok());
+    for voter in [102, 103, 104] {
+        vote(&mut game, voter, 101);
+    }
+    let events: Vec<_> = rx.try_iter().collect();
+    assert!(events.iter().any(|e| matches!(
+        e,
+        Event::HammerGrace { remaining } if *remaining == Duration::from_secs(10)
+    )));
+    assert!(!events.iter().any(|e| e.kind() == EventKind::Night));
+    assert_eq!(game.dead[0].user_id, 101);
+    assert_eq!(game.phase.kind(), PhaseKind::Grace);
+    (game, rx)
+}
+
+#[test]
+fn hammer_grace() {
+    let (mut game, rx) = elect_with_grace();
+
+    game.tick(secs(0));
+    game.tick(secs(5));
+    assert_eq!(game.phase.kind(), PhaseKind::Grace);
+    assert!(rx.try_iter().all(|e| e.kind() != EventKind::NightStart));
+    game.tick(secs(10));
+    assert_eq!(game.phase.kind(), PhaseKind::Night);
+    assert_eq!(game.phase.number(), Some(1));
+    expect_eventkind(&rx, EventKind::Night);
+}
+
+#[test]
+fn end_grace() {
+    let (mut game, rx) = elect_with_grace();
+    assert!(matches!(
+        game.handle(Action::EndGrace { moderator: 102 }),
+        Err(InvalidActionError::NotModerator { .. })
+    ));
+    assert!(game
+        .handle(Action::EndGrace {
+            moderator: MODERATOR
+        })
+        .is_ok());
+    assert_eq!(game.phase.kind(), PhaseKind::Night);
+    expect_eventkind(&rx, EventKind::Night);
+    assert!(matches!(
+        game.handle(Action::EndGrace {
+            moderator: MODERATOR
+        }),
+        Err(InvalidActionError::InvalidPhase {
+            expected: PhaseKind::Grace,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn investigate_blocked() {
+    let (mut game, rx) = create_game(vec![
+        Player::new(101, Role::TOWN),
+        Player::new(102, Role::STRIPPER),
+        Player::new(103, Role::COP),
+        Player::new(104, Role::DOCTOR),
+        Player::new(105, Role::MAFIA),
+        Player::new(106, Role::TOWN),
+    ]);
+    assert!(game.start().is_ok());
+    rx.try_iter().for_each(drop);
+
+    assert!(target(&mut game, 102, Choice::Player(103)).is_ok());
+    assert!(target(&mut game, 103, Choice::Player(105)).is_ok());
+    assert!(target(&mut game, 104, Choice::Player(101)).is_ok());
+    mark(&mut game, 105, Choice::Player(101));
+    assert!(game.in_day());
+
+    let events: Vec<_> = rx.try_iter().collect();
+    assert!(!events.iter().any(|e| e.kind() == EventKind::Investigate));
+    let blocked = events
+        .iter()
+        .find(|e| e.kind() == EventKind::InvestigateBlocked);
+    assert!(matches!(
+        blocked,
+        Some(Event::InvestigateBlocked { cop }) if cop.user_id == 103
+    ));
+    assert_eq!(blocked.map(|e| e.audience()), Some(Audience::Player(103)));
+}
+
+fn contract_game(contracts: Vec<Contract<u64>>) -> (Game<u64>, Receiver<Event<u64>>) {
+    let (tx, rx): (Sender<Event<u64>>, Receiver<Event<u64>>) = mpsc::channel();
+    let players = vec![
+        Player::new(101, Role::IDIOT),
+        Player::new(102, Role::TOWN),
+        Player::new(103, Role::AGENT),
+        Player::new(104, Role::MAFIA),
+        Player::new(105, Role::TOWN),
+    ];
+    let mut game = Game::new(1, players, contracts, Comm::new(&tx));
+    assert!(game.start().is_ok());
+    (game, rx)
+}
+
+#[test]
+fn idiot_contract() {
+    let (mut game, _rx) = contract_game(vec![Contract::new(101, 101, true)]);
+    for voter in [102, 103, 104] {
+        vote(&mut game, voter, 101);
+    }
+    assert_eq!(game.phase.kind(), PhaseKind::Dusk);
+    // The IDIOT takes the MAFIA down with them, and wins alongside the Town
+    assert!(target(&mut game, 101, Choice::Player(104)).is_ok());
+    match &game.phase {
+        Phase::End(winner, contract_results) => {
+            assert_eq!(*winner, Some(Team::Town));
+            assert_eq!(
+                *contract_results,
+                vec![ContractResult::Success { holder: 101 }]
+            );
+        }
+        p => panic!("Expected End, got {:?}", p),
+    }
+}
+
+#[test]
+fn execute_contract() {
+    let execute = Contract::Execute {
+        holder: 103,
+        charge: 104,
+        status: IdiotStatus::Unelected,
+    };
+    let (mut game, _rx) = contract_game(vec![execute]);
+    for voter in [101, 102, 105] {
+        vote(&mut game, voter, 104);
+    }
+    match &game.phase {
+        Phase::End(_, contract_results) => {
+            assert_eq!(
+                *contract_results,
+                vec![ContractResult::Success { holder: 103 }]
+            );
+        }
+        p => panic!("Expected End, got {:?}", p),
+    }
+
+    // A charge killed by anything but an election refocuses the contract on the holder
+    let (mut game, rx) = contract_game(vec![execute]);
+    for voter in [102, 103, 104] {
+        vote(&mut game, voter, 101);
+    }
+    assert!(target(&mut game, 101, Choice::Player(104)).is_ok());
+    let refocus = rx.try_iter().find(|e| e.kind() == EventKind::Refocus);
+    assert!(matches!(
+        refocus,
+        Some(Event::Refocus {
+            new_contract: Contract::Elect { holder: 103, .. }
+        })
+    ));
+    match &game.phase {
+        Phase::End(_, contract_results) => {
+            assert_eq!(
+                *contract_results,
+                vec![ContractResult::Failure { holder: 103 }]
+            );
+        }
+        p => panic!("Expected End, got {:?}", p),
+    }
+}
+
+#[test]
+fn transcript() {
+    let (mut game, _rx) = create_basic_game_2();
+    game.rules.election_info = ElectionInfo::Count;
+    assert!(game.start().is_ok());
+    assert!(game.transcript().is_none());
+
+    assert!(target(&mut game, 102, Choice::Player(104)).is_ok());
+    assert!(target(&mut game, 103, Choice::Player(102)).is_ok());
+    mark(&mut game, 104, Choice::Player(101));
+    assert!(game.handle(Action::SkipDay { voter: 104 }).is_ok());
+    for voter in [102, 103] {
+        vote(&mut game, voter, 104);
+    }
+
+    let transcript = game.transcript().expect("Game should have ended");
+    assert_eq!(transcript.game_id, 1);
+    let setup: Vec<_> = transcript
+        .setup
+        .iter()
+        .map(|p| (p.user_id, p.role.to_owned()))
+        .collect();
+    assert_eq!(
+        setup,
+        vec![
+            (101, Role::TOWN),
+            (102, Role::COP),
+            (103, Role::DOCTOR),
+            (104, Role::MAFIA)
+        ]
+    );
+    assert_eq!(transcript.phases.len(), 2);
+    match &transcript.phases[0] {
+        PhaseRecord::Night {
+            night_no,
+            actions,
+            eliminated,
+        } => {
+            assert_eq!(*night_no, 1);
+            assert_eq!(actions.len(), 3);
+            assert_eq!(*eliminated, vec![101]);
+        }
+        p => panic!("Expected Night, got {:?}", p),
+    }
+    match &transcript.phases[1] {
+        PhaseRecord::Day {
+            day_no,
+            votes,
+            elected,
+            eliminated,
+        } => {
+            assert_eq!(*day_no, 2);
+            let votes: Vec<_> = votes
+                .iter()
+                .map(|v| (v.voter, v.ballot.to_owned(), v.ghost))
+                .collect();
+            let mafia = transcript.setup.iter().find(|p| p.user_id == 104).unwrap();
+            assert_eq!(
+                votes,
+                vec![
+                    (104, Some(CastBallot::Skip), false),
+                    (102, Some(CastBallot::Player(mafia.to_owned())), false),
+                    (103, Some(CastBallot::Player(mafia.to_owned())), false),
+                ]
+            );
+            assert_eq!(*elected, Some(104));
+            assert_eq!(*eliminated, vec![104]);
+        }
+        p => panic!("Expected Day, got {:?}", p),
+    }
+    assert_eq!(transcript.winner, Some(Team::Town));
+    assert!(serde_json::to_string(&transcript).is_ok());
+}
+
+#[test]
+fn transcript_after_load() {
+    let fname = temp_path("transcript_test.json");
+    let fname = fname.to_str().unwrap();
+
+    let (mut game, _rx) = create_basic_game_2();
+    assert!(game.start().is_ok());
+    assert!(target(&mut game, 102, Choice::Player(104)).is_ok());
+    assert!(target(&mut game, 103, Choice::Player(102)).is_ok());
+    mark(&mut game, 104, Choice::Player(101));
+    assert!(game.save_game(fname).is_ok());
+
+    // The Night before the save is still in the transcript of the loaded game
+    let mut loaded =
+        Game::<u64>::load_game(fname, SaveFormat::PrettyJson, Comm::default()).unwrap();
+    for voter in [102, 103] {
+        vote(&mut loaded, voter, 104);
+    }
+    let transcript = loaded.transcript().expect("Game should have ended");
+    assert_eq!(transcript.game_id, 1);
+    assert_eq!(transcript.setup.len(), 4);
+    assert!(matches!(
+        &transcript.phases[..],
+        [
+            PhaseRecord::Night { night_no: 1, actions, .. },
+            PhaseRecord::Day { day_no: 2, votes, elected: Some(104), .. },
+        ] if actions.len() == 3 && votes.len() == 2
+    ));
+    assert_eq!(transcript.winner, Some(Team::Town));
+    std::fs::remove_file(fname).unwrap();
+}
+
+fn nominate_trial() -> (Game<u64>, Receiver<Event<u64>>) {
+    let (mut game, rx) = create_basic_game_1();
+    game.rules.day_style = DayStyle::NominateTrial;
+    assert!(game.start().is_ok());
+    rx.try_iter().for_each(drop);
+
+    for voter in [102, 103, 105] {
+        vote(&mut game, voter, 104);
+    }
+    assert!(matches!(
+        rx.try_iter().last(),
+        Some(Event::Trial { defendant }) if defendant.user_id == 104
+    ));
+    assert!(game.in_day());
+    assert!(matches!(
+        game.handle(Action::Vote {
+            voter: 101,
+            ballot: Some(Choice::Player(102))
+        }),
+        Err(InvalidActionError::TrialUnderway)
+    ));
+    assert!(matches!(
+        game.handle(Action::Verdict {
+            juror: 104,
+            guilty: false
+        }),
+        Err(InvalidActionError::NoTrial)
+    ));
+    (game, rx)
+}
+
+#[test]
+fn trial_guilty() {
+    let (mut game, rx) = nominate_trial();
+    for (juror, guilty) in [(101, true), (102, true), (103, false), (105, true)] {
+        assert!(game.handle(Action::Verdict { juror, guilty }).is_ok());
+    }
+    assert_eq!(eliminated(&rx), vec![104]);
+    assert!(matches!(game.phase, Phase::End(Some(Team::Town), _)));
+}
+
+#[test]
+fn trial_innocent() {
+    let (mut game, rx) = nominate_trial();
+    // A juror can change their verdict until the trial is decided
+    assert!(game
+        .handle(Action::Verdict {
+            juror: 101,
+            guilty: true
+        })
+        .is_ok());
+    for (juror, guilty) in [(101, false), (102, true), (103, false), (105, false)] {
+        assert!(game.handle(Action::Verdict { juror, guilty }).is_ok());
+    }
+    assert!(matches!(
+        rx.try_iter().last(),
+        Some(Event::Acquitted { defendant, guilty: 1, innocent: 3 }) if defendant.user_id == 104
+    ));
+    assert!(game.in_day());
+    assert_eq!(game.players.len(), 5);
+    assert!(matches!(
+        game.handle(Action::Verdict {
+            juror: 101,
+            guilty: true
+        }),
+        Err(InvalidActionError::NoTrial)
+    ));
+
+    // The acquitted player's votes are cleared, and voting goes on
+    vote(&mut game, 101, 102);
+    match &game.phase {
+        Phase::Day(day) => assert_eq!(day.votes, vec![(0, Ballot::Player(1))]),
+        p => panic!("Expected Day, got {:?}", p),
+    }
+
+    // Ghost votes for the acquitted player are cleared too, so they don't count
+    // towards nominating them again
+    let (mut game, _rx) = create_game(vec![
+        Player::new(101, Role::TOWN),
+        Player::new(102, Role::COP),
+        Player::new(103, Role::DOCTOR),
+        Player::new(104, Role::MAFIA),
+        Player::new(105, Role::TOWN),
+        Player::new(106, Role::TOWN),
+    ]);
+    game.rules.day_style = DayStyle::NominateTrial;
+    game.rules.start_night = StartNight::Never;
+    game.rules.ghost_vote = true;
+    game.moderators.push(MODERATOR);
+    assert!(game.start().is_ok());
+    assert!(game
+        .handle(Action::Modkill {
+            moderator: MODERATOR,
+            player: 106
+        })
+        .is_ok());
+    for voter in [106, 102, 103] {
+        vote(&mut game, voter, 104);
+    }
+    for juror in [101, 102, 103, 105] {
+        let verdict = Action::Verdict {
+            juror,
+            guilty: false,
+        };
+        assert!(game.handle(verdict).is_ok());
+    }
+    for voter in [101, 102] {
+        vote(&mut game, voter, 104);
+    }
+    match &game.phase {
+        Phase::Day(day) => {
+            assert!(day.ghosts.is_empty());
+            assert!(day.trial.is_none());
+        }
+        p => panic!("Expected Day, got {:?}", p),
+    }
+}
+
+#[test]
+fn load_inconsistent() {
+    let fname = temp_path("load_inconsistent_test.json");
+    let fname = fname.to_str().unwrap();
+
+    let (mut game, _rx) = create_basic_game_1();
+    assert!(game.start().is_ok());
+    vote(&mut game, 101, 104);
+    assert!(game.save_game(fname).is_ok());
+    let save: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(fname).unwrap()).unwrap();
+
+    let load = |save: &serde_json::Value| {
+        std::fs::write(fname, save.to_string()).unwrap();
+        Game::<u64>::load_game(fname, SaveFormat::PrettyJson, Comm::default())
+    };
+    assert!(load(&save).is_ok());
+
+    // A negative Day number can't even be read
+    let mut tampered = save.to_owned();
+    tampered["game"]["phase"]["Day"]["day_no"] = serde_json::json!(-1);
+    assert!(matches!(load(&tampered), Err(LoadError::Invalid { .. })));
+
+    for day_no in [0, usize::MAX] {
+        let mut tampered = save.to_owned();
+        tampered["game"]["phase"]["Day"]["day_no"] = serde_json::json!(day_no);
+        assert!(matches!(
+            load(&tampered),
+            Err(LoadError::InconsistentState { .. })
+        ));
+    }
+
+    // A vote for a player who isn't in the game
+    let mut tampered = save.to_owned();
+    tampered["game"]["phase"]["Day"]["votes"] = serde_json::json!([[0, { "Player": 9 }]]);
+    assert!(matches!(
+        load(&tampered),
+        Err(LoadError::InconsistentState { .. })
+    ));
+    std::fs::remove_file(fname).unwrap();
+}
+
+/// A custom role that shoots its target, and learns their team
+#[derive(Debug)]
+struct Hunter;
+
+impl RoleBehavior for Hunter {
+    fn name(&self) -> String {
+        "HUNTER".to_string()
+    }
+    fn team(&self) -> Team {
+        Team::Town
+    }
+    fn description(&self) -> &'static str {
+        "You can hunt a player each night, learning their team as you shoot them!"
+    }
+    fn night_priority(&self) -> Option<usize> {
+        Some(1)
+    }
+    fn resolve(&self, ctx: &mut RoleContext) {
+        ctx.tell(format!("Your prey was {}", ctx.roles[ctx.target].team()));
+        ctx.attack(ctx.target);
+    }
+}
+
+#[test]
+fn custom_role() {
+    let hunter = Role::custom(Hunter);
+    assert_eq!(hunter.team(), Team::Town);
+    assert!(hunter.targeting());
+    assert_eq!(hunter.to_string(), "HUNTER");
+    assert_eq!(hunter.behavior().night_priority(), Some(1));
+
+    let (mut game, rx) = create_game(vec![
+        Player::new(101, Role::TOWN),
+        Player::new(102, hunter),
+        Player::new(103, Role::DOCTOR),
+        Player::new(104, Role::MAFIA),
+        Player::new(105, Role::TOWN),
+        Player::new(106, Role::TOWN),
+    ]);
+    assert!(game.start().is_ok());
+    assert!(game.in_night());
+    rx.try_iter().for_each(drop);
+
+    assert!(target(&mut game, 102, Choice::Player(104)).is_ok());
+    assert!(target(&mut game, 103, Choice::Player(101)).is_ok());
+    mark(&mut game, 104, Choice::Player(101));
+
+    let events: Vec<_> = rx.try_iter().collect();
+    let result = events.iter().find(|e| e.kind() == EventKind::CustomResult);
+    assert!(matches!(
+        result,
+        Some(Event::CustomResult { actor, text })
+            if actor.user_id == 102 && text == "Your prey was Mafia Aligned"
+    ));
+    assert_eq!(result.map(|e| e.audience()), Some(Audience::Player(102)));
+    assert!(events.iter().any(|e| matches!(
+        e,
+        Event::Kill { killer, mark } if killer.user_id == 102 && mark.user_id == 104
+    )));
+    assert!(matches!(game.phase, Phase::End(Some(Team::Town), _)));
+}
+
+#[test]
+fn custom_role_without_behavior() {
+    let fname = temp_path("custom_role_test.json");
+    let fname = fname.to_str().unwrap();
+
+    let (game, _rx) = create_game(vec![
+        Player::new(101, Role::custom(Hunter)),
+        Player::new(102, Role::DOCTOR),
+        Player::new(103, Role::MAFIA),
+        Player::new(104, Role::TOWN),
+    ]);
+    assert!(game.save_game(fname).is_ok());
+    assert_eq!(
+        Game::<u64>::load_game(fname, SaveFormat::PrettyJson, Comm::default()).unwrap_err(),
+        LoadError::UnknownRole {
+            name: "HUNTER".to_string()
+        }
+    );
+    let hunter: Arc<dyn RoleBehavior> = Arc::new(Hunter);
+    let loaded =
+        Game::<u64>::load_game_with(fname, SaveFormat::PrettyJson, Comm::default(), &[hunter])
+            .unwrap();
+    assert!(loaded.players[0].role.targeting());
+    std::fs::remove_file(fname).unwrap();
+
+    // A rule for a custom role is saved and loaded along with it
+    let (mut game, _rx) = create_game(vec![
+        Player::new(101, Role::custom(Hunter)),
+        Player::new(102, Role::DOCTOR),
+        Player::new(103, Role::MAFIA),
+        Player::new(104, Role::TOWN),
+    ]);
+    game.rules.ability_limits.insert(Role::custom(Hunter), 1);
+    game.rules
+        .ability_nights
+        .insert(Role::custom(Hunter), AbilityNights::Odd);
+    assert!(game.save_game(fname).is_ok());
+    let hunter: Arc<dyn RoleBehavior> = Arc::new(Hunter);
+    let loaded =
+        Game::<u64>::load_game_with(fname, SaveFormat::PrettyJson, Comm::default(), &[hunter])
+            .unwrap();
+    assert_eq!(loaded.rules, game.rules);
+    assert!(loaded
+        .rules
+        .ability_limits
+        .keys()
+        .all(|role| role.targeting()));
+    assert!(loaded.validate_setup().is_ok());
+    std::fs::remove_file(fname).unwrap();
+
+    // A role read without its behavior can't start a game
+    let unbound: Role = serde_json::from_str(r#"{"Custom":"HUNTER"}"#).unwrap();
+    assert_eq!(unbound, Role::custom(Hunter));
+    let (game, _rx) = create_game(vec![
+        Player::new(101, unbound),
+        Player::new(102, Role::DOCTOR),
+        Player::new(103, Role::MAFIA),
+        Player::new(104, Role::TOWN),
+    ]);
+    assert!(game
+        .validate_setup()
+        .unwrap_err()
+        .contains(&GameError::UnknownRole {
+            name: "HUNTER".to_string()
+        }));
+}
+
+/// A Day of create_basic_game_1 where 104 leads the vote without a majority
+fn no_majority(behavior: DeadlineBehavior) -> (Game<u64>, Receiver<Event<u64>>) {
+    let (mut game, rx) = create_basic_game_1();
+    game.rules.deadline_behavior = Some(behavior);
+    game.rules.day_length = Some(Duration::from_secs(60));
+    assert!(game.start().is_ok());
+    vote(&mut game, 101, 104);
+    vote(&mut game, 102, 104);
+    vote(&mut game, 103, 105);
+    assert!(game.in_day());
+    game.tick(secs(0));
+    game.tick(secs(60));
+    (game, rx)
+}
+
+#[test]
+fn deadline_no_lynch() {
+    let (game, rx) = no_majority(DeadlineBehavior::NoLynch);
+    assert!(eliminated(&rx).is_empty());
+    assert!(game.in_night());
+}
+
+#[test]
+fn deadline_plurality() {
+    let (game, rx) = no_majority(DeadlineBehavior::Plurality);
+    assert_eq!(eliminated(&rx), vec![104]);
+    assert!(matches!(game.phase, Phase::End(Some(Team::Town), _)));
+}
+
+#[test]
+fn deadline_carry_over() {
+    let (mut game, rx) = no_majority(DeadlineBehavior::CarryOver);
+    assert!(eliminated(&rx).is_empty());
+    assert!(game.in_day());
+
+    // The votes stand into the next deadline, which restarts with a full day_length
+    game.tick(secs(90));
+    assert!(game.in_day());
+    vote(&mut game, 105, 104);
+    assert_eq!(eliminated(&rx), vec![104]);
+}
+
+#[test]
+fn killers_kill_each_other() {
+    let (mut game, rx) = create_game(vec![
+        Player::new(101, Role::TOWN),
+        Player::new(102, Role::VIGILANTE),
+        Player::new(103, Role::TOWN),
+        Player::new(104, Role::MAFIA),
+        Player::new(105, Role::GOON),
+        Player::new(106, Role::TOWN),
+    ]);
+    assert!(game.start().is_ok());
+    assert!(game.in_night());
+    rx.try_iter().for_each(drop);
+
+    // The VIGILANTE shoots the Mafia's killer, who marked the VIGILANTE
+    assert!(target(&mut game, 102, Choice::Player(104)).is_ok());
+    mark(&mut game, 104, Choice::Player(102));
+    let mut dead = eliminated(&rx);
+    dead.sort();
+    assert_eq!(dead, vec![102, 104]);
+    assert!(game.in_day());
+}
+
+#[test]
+fn roster_choices() {
+    let (mut game, rx) = create_basic_game_1();
+    assert!(game.start().is_ok());
+    vote(&mut game, 102, 101);
+    vote(&mut game, 103, 101);
+    vote(&mut game, 104, 101);
+    assert_eq!(eliminated(&rx), vec![101]);
+    assert!(game.in_night());
+
+    let roster = game.roster();
+    let check = |actor, pid, action| roster.check_choice(actor, &Choice::Player(pid), action);
+    assert_eq!(
+        check(102, 999, ActionKind::Target),
+        Err(ChoiceError::NoSuchPlayer { pid: 999 })
+    );
+    assert_eq!(
+        check(102, 101, ActionKind::Target),
+        Err(ChoiceError::PlayerDead { pid: 101 })
+    );
+    // A COP can't investigate themself, and a TOWN has nobody to target
+    assert_eq!(
+        check(102, 102, ActionKind::Target),
+        Err(ChoiceError::IllegalTarget { pid: 102 })
+    );
+    assert_eq!(
+        check(105, 104, ActionKind::Target),
+        Err(ChoiceError::IllegalTarget { pid: 104 })
+    );
+    assert_eq!(check(102, 104, ActionKind::Target), Ok(()));
+    assert_eq!(check(104, 102, ActionKind::Mark), Ok(()));
+    assert_eq!(
+        roster.check_choice(104, &Choice::Pair(101, 102), ActionKind::Target),
+        Err(ChoiceError::PlayerDead { pid: 101 })
+    );
+    assert_eq!(
+        roster.check_choice(105, &Choice::Abstain, ActionKind::Vote),
+        Ok(())
+    );
+}
+
+#[test]
+fn ability_on_cooldown() {
+    let (mut game, rx) = create_game(vec![
+        Player::new(101, Role::TOWN),
+        Player::new(102, Role::COP),
+        Player::new(103, Role::DOCTOR),
+        Player::new(104, Role::MAFIA),
+        Player::new(105, Role::TOWN),
+        Player::new(106, Role::TOWN),
+    ]);
+    game.rules
+        .ability_nights
+        .insert(Role::COP, AbilityNights::Odd);
+    assert!(game.start().is_ok());
+    assert!(game.in_night());
+    let prompted = |rx: &Receiver<Event<u64>>| -> Vec<u64> {
+        rx.try_iter()
+            .filter_map(|e| match e {
+                Event::NightStart { actor, .. } => Some(actor.user_id),
+                _ => None,
+            })
+            .collect()
+    };
+    assert_eq!(prompted(&rx), vec![102, 103]);
+
+    // Night 1 is odd, so the COP can investigate
+    assert!(target(&mut game, 102, Choice::Player(104)).is_ok());
+    assert!(target(&mut game, 103, Choice::Player(105)).is_ok());
+    mark(&mut game, 104, Choice::Player(105));
+    assert!(game.in_day());
+    rx.try_iter().for_each(drop);
+    for voter in [102, 103, 105, 106] {
+        vote(&mut game, voter, 101);
+    }
+    assert!(game.players.check(101).is_err());
+    assert!(game.in_night());
+
+    // On night 2 the COP isn't prompted, is turned away, and dawn doesn't wait for them
+    assert_eq!(prompted(&rx), vec![103]);
+    let cop = game.players.iter().position(|p| p.user_id == 102).unwrap();
+    assert!(game.legal_targets(cop).is_empty());
+    assert!(matches!(
+        target(&mut game, 102, Choice::Player(104)),
+        Err(InvalidActionError::AbilityOnCooldown {
+            role: Role::COP,
+            night_no: 2
+        })
+    ));
+    assert!(target(&mut game, 103, Choice::Player(105)).is_ok());
+    mark(&mut game, 104, Choice::Player(105));
+    assert!(game.in_day());
+}
+
+/// The hammer named by the Election the game sent
+fn placed_hammer(rx: &Receiver<Event<u64>>) -> Option<u64> {
+    match rx.try_iter().find(|e| e.kind() == EventKind::Election) {
+        Some(Event::Election { hammer, .. }) => hammer.map(|p| p.user_id),
+        e => panic!("Expected Election, got {:?}", e),
+    }
+}
+
+#[test]
+fn election_hammer() {
+    let (mut game, rx) = create_basic_game_1();
+    assert!(game.start().is_ok());
+    vote(&mut game, 101, 104);
+    vote(&mut game, 102, 104);
+    vote(&mut game, 103, 105);
+    vote(&mut game, 105, 101);
+    // Changing a vote makes it the latest, so 103 places the hammer
+    vote(&mut game, 103, 104);
+    assert_eq!(placed_hammer(&rx), Some(103));
+
+    // A player can place the hammer on themself
+    let (mut game, rx) = create_basic_game_1();
+    assert!(game.start().is_ok());
+    vote(&mut game, 101, 104);
+    vote(&mut game, 102, 104);
+    vote(&mut game, 104, 104);
+    assert_eq!(placed_hammer(&rx), Some(104));
+
+    // Hidden votes hide the hammer too
+    let (mut game, rx) = create_basic_game_1();
+    game.rules.election_info = ElectionInfo::Secret;
+    assert!(game.start().is_ok());
+    for voter in [101, 102, 103] {
+        vote(&mut game, voter, 104);
+    }
+    assert_eq!(placed_hammer(&rx), None);
+
+    // No one's vote decides an election at the deadline
+    let (_game, rx) = no_majority(DeadlineBehavior::Plurality);
+    assert_eq!(placed_hammer(&rx), None);
+}
+
+#[test]
+fn spectator() {
+    let (mut game, _rx) = create_basic_game_2();
+    let (full_tx, full_rx) = mpsc::channel();
+    let (public_tx, public_rx) = mpsc::channel();
+    assert_eq!(
+        game.spectate(101, full_tx.clone(), SpectatorView::FullInfo),
+        Err(GameError::PlayerExists { pid: 101 })
+    );
+    assert!(game.spectate(200, full_tx, SpectatorView::FullInfo).is_ok());
+    assert!(game
+        .spectate(201, public_tx, SpectatorView::PublicOnly)
+        .is_ok());
+    assert!(game.start().is_ok());
+
+    // Spectators can't vote or act
+    assert!(matches!(
+        game.handle(Action::Vote {
+            voter: 200,
+            ballot: Some(Choice::Player(104)),
+        }),
+        Err(InvalidActionError::Spectating { pid: 200 })
+    ));
+    assert!(matches!(
+        game.handle(Action::Join { player: 201 }),
+        Err(InvalidActionError::Spectating { pid: 201 })
+    ));
+
+    assert!(target(&mut game, 102, Choice::Player(104)).is_ok());
+    assert!(target(&mut game, 103, Choice::Player(101)).is_ok());
+    mark(&mut game, 104, Choice::Player(101));
+    assert!(game.in_day());
+
+    // Both see the public stream, but only the full view sees the night's actions,
+    // and neither sees a player's private Events
+    let full: Vec<_> = full_rx.try_iter().collect();
+    let public: Vec<_> = public_rx.try_iter().collect();
+    let reported = |events: &[Event<u64>]| {
+        events
+            .iter()
+            .any(|e| matches!(e, Event::NightReport { .. }))
+    };
+    assert!(reported(&full));
+    assert!(!reported(&public));
+    assert!(public.iter().any(|e| matches!(e, Event::Start { .. })));
+    let private = |e: &Event<u64>| matches!(e.audience(), Audience::Player(_) | Audience::Team(_));
+    assert!(!full.iter().any(private));
+    assert!(!public.iter().any(private));
+}
+
+#[test]
+fn dead_chat() {
+    let chat = |game: &mut Game<u64>| {
+        game.handle(Action::TeamChat {
+            from: 101,
+            text: "I was town!".to_string(),
+        })
+    };
+    let (tx, rx) = mpsc::channel();
+    let (dead_tx, dead_rx) = mpsc::channel();
+    let (living_tx, living_rx) = mpsc::channel();
+    let mut comm = Comm::new(&tx);
+    comm.subscribe_audience(dead_tx, Audience::Player(101));
+    comm.subscribe_audience(living_tx, Audience::Player(102));
+    let players = vec![
+        Player::new(101, Role::TOWN),
+        Player::new(102, Role::COP),
+        Player::new(103, Role::DOCTOR),
+        Player::new(104, Role::MAFIA),
+        Player::new(105, Role::TOWN),
+    ];
+    let mut game = Game::new(1, players, Vec::new(), comm);
+    assert!(game.start().is_ok());
+    for voter in [102, 103, 104] {
+        vote(&mut game, voter, 101);
+    }
+    assert_eq!(eliminated(&rx), vec![101]);
+    let (spectator_tx, spectator_rx) = mpsc::channel();
+    assert!(game
+        .spectate(200, spectator_tx, SpectatorView::FullInfo)
+        .is_ok());
+    let (public_tx, public_rx) = mpsc::channel();
+    assert!(game
+        .spectate(201, public_tx, SpectatorView::PublicOnly)
+        .is_ok());
+    rx.try_iter().for_each(drop);
+
+    // Without the rule, the dead are silent
+    assert!(matches!(
+        chat(&mut game),
+        Err(InvalidActionError::DeadSilence)
+    ));
+    expect_eventkind(&rx, EventKind::DeadSilence);
+    dead_rx.try_iter().for_each(drop);
+
+    // With it, they talk to the dead and spectators
+    game.rules.dead_chat = true;
+    assert!(chat(&mut game).is_ok());
+    let event = rx.try_recv().unwrap();
+    assert_eq!(event.audience(), Audience::Dead);
+    assert!(matches!(&event, Event::DeadChat { from, to, .. }
+        if from.user_id == 101 && to.iter().map(|p| p.user_id).eq([101])));
+    assert!(dead_rx.try_iter().any(|e| e == event));
+    assert!(spectator_rx.try_iter().any(|e| e == event));
+    assert!(living_rx
+        .try_iter()
+        .all(|e| e.kind() != EventKind::DeadChat));
+    assert!(public_rx
+        .try_iter()
+        .all(|e| e.kind() != EventKind::DeadChat));
+}
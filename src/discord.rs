@@ -19,8 +19,12 @@ pub type ChannelID = u64;
 pub type MessageID = u64;
 pub type GuildID = u64;
 
+mod mention;
 pub mod parser;
 mod services;
+#[cfg(test)]
+mod test;
 
+pub use mention::*;
 pub use services::*;
 pub use parser::*;
@@ -0,0 +1,20 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::UserID;
+
+/// Reads a user mention, as written in a Discord message (`<@123>`, or `<@!123>` for
+/// a nickname mention). Anything else, including an ID too large to be a UserID,
+/// is not a mention.
+pub fn parse_mention(text: &str) -> Option<UserID> {
+    lazy_static! {
+        static ref MENTION: Regex = Regex::new(r"^<@!?(\d+)>$").unwrap();
+    };
+    let caps = MENTION.captures(text.trim())?;
+    caps.get(1)?.as_str().parse().ok()
+}
+
+/// Writes a mention of the user, which Discord shows as their name
+pub fn mention(user: UserID) -> String {
+    format!("<@{}>", user)
+}
@@ -20,8 +20,45 @@ use regex::Regex;
 use regex::RegexSet;
 use lazy_static::lazy_static;
 use std::collections::HashMap;
+use std::sync::mpsc::Sender;
 
-struct Handler;
+use super::UserID;
+use crate::controller::{dispatch_checked, parse_command, Command, ParseError, SharedRoster};
+
+struct Handler {
+    /// Where game commands are sent, to the Controller
+    commands: std::sync::Mutex<Sender<Command>>,
+    /// Who is in the running game, to check game commands against before sending them
+    roster: SharedRoster,
+}
+
+impl Handler {
+    /// Reads a game command from a message and sends it to the Controller, once its
+    /// Choice is checked against the Roster. Returns the reply to the message.
+    fn game_command(&self, text: &str, author: UserID) -> String {
+        let cmd = match parse_command(text, author) {
+            Ok(cmd) => cmd,
+            Err(ParseError::UnknownCommand { .. }) => return "not a valid command".to_string(),
+            Err(err) => return err.to_string(),
+        };
+        let kind = cmd.kind();
+        let commands = self.commands.lock().unwrap();
+        let roster = self.roster.read().unwrap();
+        match dispatch_checked(&commands, &roster, cmd) {
+            Ok(()) => format!("got {:?} command", kind),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    /// Asks the Controller to let the author watch the running game
+    fn spectate(&self, author: UserID) -> String {
+        let commands = self.commands.lock().unwrap();
+        match commands.send(Command::Spectate(author)) {
+            Ok(()) => "got spectate command".to_string(),
+            Err(_) => "No Game".to_string(),
+        }
+    }
+}
 
 #[async_trait]
 impl EventHandler for Handler {
@@ -173,7 +210,8 @@ impl EventHandler for Handler {
                     r"!unvote",
                     r"!target",
                     r"!reveal",
-                    r"!status",]).unwrap();
+                    r"!status",
+                    r"!spectate",]).unwrap();
             };
             let text = &_msg.content;
             match CMD.matches(text).into_iter().collect::<Vec<_>>().as_slice() {
@@ -189,35 +227,17 @@ impl EventHandler for Handler {
                     let minutes = start_caps.get(2).unwrap().as_str();
                     _msg.reply(_ctx, format!("Starting game for {players} players in {minutes} minutes")).await;
                 },
-                [2] => {
-                    lazy_static! {
-                        static ref VOTE_CMD : Regex = Regex::new(r"!vote (\S+)").unwrap();
-                    };
-                    let name = VOTE_CMD.captures(text).expect("not a valid command").get(1).unwrap().as_str();
-                    _msg.reply(_ctx, format!("got vote for {name}")).await;
-                },
-                [3] => {
-                    lazy_static! {
-                        static ref UNVOTE_CMD : Regex = Regex::new(r"!unvote (\S+)").unwrap();
-                    };
-                    let name = UNVOTE_CMD.captures(text).expect("not a valid command").get(1).unwrap().as_str();
-                    _msg.reply(_ctx, format!("got unvote for {name}")).await;
-                },
-                [4] => {
-                    lazy_static! {
-                        static ref UNVOTE_CMD : Regex = Regex::new(r"!target ([A-Z])").unwrap();
-                    };
-                    let name = UNVOTE_CMD.captures(text).expect("not a valid command").get(1).unwrap().as_str();
-                    _msg.reply(_ctx, format!("Set target {name}")).await;
-                },
-                [5] => {
-                    _msg.reply(_ctx, "got reveal command").await;
-                },
                 [6] => {
                     _msg.reply(_ctx, "got status command").await;
                 },
+                [7] => {
+                    let reply = self.spectate(_msg.author.id.0);
+                    _msg.reply(_ctx, reply).await;
+                },
+                // Everything else is a game command, such as a vote or a night action
                 _ => {
-                    _msg.reply(_ctx, "not a valid command").await;
+                    let reply = self.game_command(text, _msg.author.id.0);
+                    _msg.reply(_ctx, reply).await;
                 }
             }
         };
@@ -337,7 +357,7 @@ impl EventHandler for Handler {
     }
 }
 
-pub async fn get_client() -> Client{
+pub async fn get_client(commands: Sender<Command>, roster: SharedRoster) -> Client{
     // Fetch token
     let token = env::var("DISCORD_TOKEN").expect("token");
     // Set up default framework
@@ -349,7 +369,10 @@ pub async fn get_client() -> Client{
     // 395137001472
     println!("Set up gateway intent. raw bits: {}",intent.bits());
     return Client::builder(token, intent)
-        .event_handler(Handler)
+        .event_handler(Handler {
+            commands: std::sync::Mutex::new(commands),
+            roster,
+        })
         .framework(framework)
         .await
         .expect("where is the client :,(");
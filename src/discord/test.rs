@@ -0,0 +1,18 @@
+use super::*;
+
+#[test]
+fn parse_mentions() {
+    assert_eq!(parse_mention("<@123>"), Some(123));
+    assert_eq!(parse_mention("<@!123>"), Some(123));
+    assert_eq!(parse_mention(" <@123> "), Some(123));
+    assert_eq!(parse_mention(&mention(456)), Some(456));
+    assert_eq!(mention(456), "<@456>");
+
+    assert_eq!(parse_mention("123"), None);
+    assert_eq!(parse_mention("<@>"), None);
+    assert_eq!(parse_mention("<@abc>"), None);
+    assert_eq!(parse_mention("<#123>"), None);
+    assert_eq!(parse_mention("<@123"), None);
+    assert_eq!(parse_mention("<@123> <@456>"), None);
+    assert_eq!(parse_mention("<@99999999999999999999999>"), None);
+}
@@ -2,9 +2,18 @@ pub mod controller;
 pub mod core;
 pub mod discord;
 
+use std::env;
+use std::sync::mpsc;
+
 #[tokio::main]
 async fn main() {
-    let mut client = discord::parser::get_client().await;
+    let guild = env::var("DISCORD_GUILD").expect("guild");
+    let guild = guild.parse().expect("guild id");
+    let (tx, rx) = mpsc::channel();
+    let roster = controller::SharedRoster::default();
+    let _controller = controller::Controller::spawn(guild, rx, roster.clone());
+
+    let mut client = discord::parser::get_client(tx, roster).await;
     //client.await.expect("I found a secret").start_autosharded();
     if let Err(why) = client.start().await {
         println!("Err with client: {:?}", why);